@@ -0,0 +1,200 @@
+use quorlin_analyzer::{AnalysisResult, Analyzer, AnalyzerConfig, Severity};
+use quorlin_codegen_evm::storage_layout::StorageLayout;
+use quorlin_lexer::Lexer;
+use quorlin_parser::{parse_module, ContractDecl, ContractMember, Item, Module};
+use quorlin_semantics::backend_consistency::BackendConsistencyChecker;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Severities in report order, most severe first
+const SEVERITIES: [Severity; 5] = [
+    Severity::Critical,
+    Severity::High,
+    Severity::Medium,
+    Severity::Low,
+    Severity::Info,
+];
+
+pub fn run(file: PathBuf, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let source = fs::read_to_string(&file)?;
+
+    let tokens = Lexer::new(&source)
+        .tokenize()
+        .map_err(|e| format!("Lexer error: {}", e))?;
+    let module = parse_module(tokens).map_err(|e| format!("Parse error: {}", e))?;
+
+    let config = AnalyzerConfig::load_for(&file)?;
+    let result = Analyzer::with_config(config)
+        .analyze(&module)
+        .map_err(|e| format!("Analysis error: {}", e))?;
+
+    let report = render_report(&file, &module, &result)?;
+
+    match output {
+        Some(path) => {
+            fs::write(&path, report)?;
+            println!("Audit report written to {}", path.display());
+        }
+        None => print!("{}", report),
+    }
+
+    Ok(())
+}
+
+/// Render the analyzer findings, gas estimates, storage layout and backend
+/// consistency report as one Markdown document
+fn render_report(
+    file: &Path,
+    module: &Module,
+    result: &AnalysisResult,
+) -> Result<String, std::fmt::Error> {
+    let mut md = String::new();
+    writeln!(md, "# Audit Report: {}", file.display())?;
+    writeln!(md)?;
+
+    // Summary
+    writeln!(md, "## Summary")?;
+    writeln!(md)?;
+    writeln!(md, "| Severity | Findings |")?;
+    writeln!(md, "|----------|----------|")?;
+    for severity in &SEVERITIES {
+        let count = result.security_issues.iter().filter(|i| i.severity == *severity).count();
+        writeln!(md, "| {:?} | {} |", severity, count)?;
+    }
+    writeln!(md)?;
+
+    // Security findings grouped by severity
+    writeln!(md, "## Security Findings")?;
+    writeln!(md)?;
+    if result.security_issues.is_empty() {
+        writeln!(md, "No security issues found.")?;
+        writeln!(md)?;
+    }
+    for severity in &SEVERITIES {
+        let issues: Vec<_> = result.security_issues.iter().filter(|i| i.severity == *severity).collect();
+        if issues.is_empty() {
+            continue;
+        }
+        writeln!(md, "### {:?}", severity)?;
+        writeln!(md)?;
+        writeln!(md, "| Category | Location | Description |")?;
+        writeln!(md, "|----------|----------|-------------|")?;
+        for issue in issues {
+            writeln!(
+                md,
+                "| {:?} | {} | {} |",
+                issue.category,
+                issue.location.as_deref().unwrap_or("-"),
+                escape_cell(&issue.message)
+            )?;
+        }
+        writeln!(md)?;
+    }
+
+    if !result.type_errors.is_empty() {
+        writeln!(md, "## Type Errors")?;
+        writeln!(md)?;
+        for error in &result.type_errors {
+            writeln!(md, "- {}", error)?;
+        }
+        writeln!(md)?;
+    }
+
+    if !result.lint_warnings.is_empty() {
+        writeln!(md, "## Lint Warnings")?;
+        writeln!(md)?;
+        writeln!(md, "| Rule | Location | Message |")?;
+        writeln!(md, "|------|----------|---------|")?;
+        for warning in &result.lint_warnings {
+            writeln!(
+                md,
+                "| {} | {} | {} |",
+                warning.rule,
+                warning.location.as_deref().unwrap_or("-"),
+                escape_cell(&warning.message)
+            )?;
+        }
+        writeln!(md)?;
+    }
+
+    // Gas estimates
+    writeln!(md, "## Gas Estimates")?;
+    writeln!(md)?;
+    writeln!(md, "| Function | Estimated Gas | Complexity |")?;
+    writeln!(md, "|----------|---------------|------------|")?;
+    for estimate in &result.gas_estimates {
+        writeln!(
+            md,
+            "| {} | ~{} | {:?} |",
+            estimate.function_name, estimate.estimated_gas, estimate.complexity
+        )?;
+    }
+    writeln!(md)?;
+
+    // Storage layout per contract
+    writeln!(md, "## Storage Layout")?;
+    writeln!(md)?;
+    let contracts: Vec<&ContractDecl> = module
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Contract(contract) => Some(contract),
+            _ => None,
+        })
+        .collect();
+    for contract in &contracts {
+        writeln!(md, "### {}", contract.name)?;
+        writeln!(md)?;
+        let mut layout = StorageLayout::new();
+        match layout.allocate_inherited(contract, &contracts) {
+            Ok(()) => {
+                writeln!(md, "```text")?;
+                write!(md, "{}", layout.generate_report())?;
+                writeln!(md, "```")?;
+            }
+            Err(e) => writeln!(md, "Storage layout could not be computed: {}", e)?,
+        }
+        writeln!(md)?;
+    }
+
+    // Backend consistency, nested under this report's headings
+    let checker = BackendConsistencyChecker::new();
+    for line in checker.generate_report().lines() {
+        if line.starts_with('#') {
+            writeln!(md, "#{}", line)?;
+        } else {
+            writeln!(md, "{}", line)?;
+        }
+    }
+
+    let inconsistencies: Vec<_> = contracts
+        .iter()
+        .flat_map(|contract| &contract.body)
+        .filter_map(|member| match member {
+            ContractMember::StateVar(var) => Some(var),
+            _ => None,
+        })
+        .flat_map(|var| {
+            checker
+                .check_type(&var.type_annotation)
+                .into_iter()
+                .map(move |error| format!("`{}`: {}", var.name, error))
+        })
+        .collect();
+    if !inconsistencies.is_empty() {
+        writeln!(md)?;
+        writeln!(md, "### State Variable Inconsistencies")?;
+        writeln!(md)?;
+        for inconsistency in inconsistencies {
+            writeln!(md, "- {}", inconsistency)?;
+        }
+    }
+
+    Ok(md)
+}
+
+/// Keep table cells on one line and stop `|` from splitting them
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
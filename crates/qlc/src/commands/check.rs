@@ -1,6 +1,148 @@
-use std::path::PathBuf;
+use colored::Colorize;
+use quorlin_analyzer::{Analyzer, AnalyzerConfig, Severity};
+use quorlin_lexer::Lexer;
+use quorlin_parser::parse_module;
+use quorlin_semantics::SemanticAnalyzer;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub fn run(_file: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Check command not yet implemented");
-    Err("Not implemented yet".into())
+pub fn run(
+    paths: Vec<PathBuf>,
+    include: &[PathBuf],
+    no_security: bool,
+    no_lints: bool,
+    no_gas: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    for path in &paths {
+        collect_sources(path, &mut files)?;
+    }
+
+    // A single file reports its error directly, as the only result
+    if let [file] = files.as_slice() {
+        return check_file(file, include, no_security, no_lints, no_gas);
+    }
+    if files.is_empty() {
+        return Err("No .ql files to check".into());
+    }
+
+    let mut failed = 0;
+    for file in &files {
+        if let Err(e) = check_file(file, include, no_security, no_lints, no_gas) {
+            eprintln!("{}: {}: {}", "error".red().bold(), file.display(), e);
+            failed += 1;
+        }
+        println!();
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} file(s) failed the check", failed, files.len()).into());
+    }
+    println!("{} {} files", "✓ Checked".green().bold(), files.len());
+    Ok(())
+}
+
+/// `path` itself if it is a file, or every `.ql` file under it, in name order
+fn collect_sources(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            collect_sources(&entry, files)?;
+        } else if entry.extension().is_some_and(|ext| ext == "ql") {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+fn check_file(
+    file: &Path,
+    include: &[PathBuf],
+    no_security: bool,
+    no_lints: bool,
+    no_gas: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!(
+        "{} {}",
+        "Checking".green().bold(),
+        file.display().to_string().bold()
+    );
+
+    // Read source file
+    let source = fs::read_to_string(file)?;
+
+    // Tokenize
+    let lexer = Lexer::new(&source);
+    let tokens = lexer.tokenize().map_err(|e| format!("Lexer error: {}", e))?;
+
+    // Parse
+    let mut module = parse_module(tokens).map_err(|e| format!("Parse error: {}", e))?;
+    crate::imports::link_imports(&mut module, file, include)?;
+
+    // Semantic analysis (the security pass is reported below by the analyzer)
+    let mut semantics = SemanticAnalyzer::new();
+    semantics.set_security_analysis(false);
+    semantics
+        .analyze_all(&mut module)
+        .map_err(|errors| format!("Semantic error: {}", join_errors(&errors)))?;
+
+    // Static analysis, honouring any .quorlin.toml next to the source
+    let config = AnalyzerConfig::load_for(file)?;
+    let mut analyzer = Analyzer::with_config(config);
+    analyzer.set_security_enabled(!no_security);
+    analyzer.set_lints_enabled(!no_lints);
+    analyzer.set_gas_enabled(!no_gas);
+    let result = analyzer
+        .analyze(&module)
+        .map_err(|e| format!("Analysis error: {}", e))?;
+
+    for error in &result.type_errors {
+        println!("  {} {}", "type error:".red().bold(), error);
+    }
+
+    for issue in &result.security_issues {
+        let label = match issue.severity {
+            Severity::Critical | Severity::High => format!("{:?}", issue.severity).red().bold(),
+            Severity::Medium => format!("{:?}", issue.severity).yellow().bold(),
+            Severity::Low | Severity::Info => format!("{:?}", issue.severity).normal(),
+        };
+        println!("  {} [{}] {}", "security:".bright_red(), label, issue.message);
+    }
+
+    for warning in &result.lint_warnings {
+        match &warning.severity {
+            Some(severity) => println!("  {} [{}] [{:?}] {}", "lint:".yellow(), warning.rule, severity, warning.message),
+            None => println!("  {} [{}] {}", "lint:".yellow(), warning.rule, warning.message),
+        }
+    }
+
+    for estimate in &result.gas_estimates {
+        println!(
+            "  {} {} ~{} ({:?})",
+            "gas:".cyan(),
+            estimate.function_name,
+            estimate.estimated_gas,
+            estimate.complexity
+        );
+    }
+
+    if !result.type_errors.is_empty() {
+        return Err(format!("{} type error(s) found", result.type_errors.len()).into());
+    }
+
+    println!("{}", "✓ Check completed".green().bold());
+    Ok(())
+}
+
+/// All semantic errors on one line, in the order they were found
+pub(crate) fn join_errors(errors: &[quorlin_semantics::SemanticError]) -> String {
+    errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
 }
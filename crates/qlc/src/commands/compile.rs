@@ -1,17 +1,19 @@
 use colored::Colorize;
+use quorlin_codegen_evm::abi::ContractAbi;
 use quorlin_codegen_evm::EvmCodegen;
 use quorlin_codegen_solana::SolanaCodegen;
 use quorlin_codegen_ink::InkCodegen;
 use quorlin_codegen_aptos::AptosCodegen;
 use quorlin_codegen_quorlin::QuorlinCodegen;
 use quorlin_lexer::Lexer;
-use quorlin_parser::parse_module;
+use quorlin_parser::{parse_module, Item, Module};
+use quorlin_semantics::security_analyzer::Severity;
 use quorlin_semantics::SemanticAnalyzer;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-fn print_header(file: &PathBuf, target: &str) {
+fn print_header(file: &Path, target: &str) {
     println!();
     println!("{}", "╔════════════════════════════════════════════════════════════╗".bright_cyan());
     println!("{}", "║                  🚀 QUORLIN COMPILER 🚀                   ║".bright_cyan().bold());
@@ -59,7 +61,7 @@ fn format_size(bytes: usize) -> String {
     }
 }
 
-fn print_success_box(output_file: &PathBuf, size: usize, elapsed_ms: u128) {
+fn print_success_box(output_file: &Path, size: usize, elapsed_ms: u128) {
     let elapsed_str = if elapsed_ms >= 1000 {
         format!("{:.2}s", elapsed_ms as f64 / 1000.0)
     } else {
@@ -79,75 +81,188 @@ fn print_success_box(output_file: &PathBuf, size: usize, elapsed_ms: u128) {
     println!();
 }
 
+/// Accepted `--target` values, including aliases
+pub const TARGETS: &[&str] = &[
+    "evm", "ethereum", "solana", "polkadot", "ink", "aptos", "move", "quorlin", "bytecode",
+];
+
+/// How `compile` reports progress and results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Banners, progress bars and a summary box (interactive default)
+    Pretty,
+    /// Only the essential result line; errors still go to stderr
+    Quiet,
+    /// A single JSON object describing the result on stdout
+    Json,
+}
+
+/// Whether the security pass runs and which findings fail the build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityPolicy {
+    pub enabled: bool,
+    /// Findings at or above this severity are errors
+    pub deny: Option<Severity>,
+}
+
+/// Machine-readable compilation result for `--format json`
+#[derive(serde::Serialize)]
+struct CompileReport<'a> {
+    success: bool,
+    target: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<usize>,
+    elapsed_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     file: PathBuf,
     target: String,
     output: Option<PathBuf>,
+    include: &[PathBuf],
     _emit_ir: bool,
-    _optimize: bool,
+    optimize: bool,
+    security: SecurityPolicy,
+    mode: OutputMode,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let start_time = Instant::now();
+    let pretty = mode == OutputMode::Pretty;
 
-    // Print beautiful header
-    print_header(&file, &target);
+    let result = compile(&file, &target, output, include, optimize, security, pretty);
+    let elapsed = start_time.elapsed().as_millis();
+
+    match mode {
+        OutputMode::Pretty => {
+            if let Ok((output_file, size)) = &result {
+                print_success_box(output_file, *size, elapsed);
+            }
+        }
+        OutputMode::Quiet => {
+            if let Ok((output_file, size)) = &result {
+                println!("{} ({})", output_file.display(), format_size(*size));
+            }
+        }
+        OutputMode::Json => {
+            let report = match &result {
+                Ok((output_file, size)) => CompileReport {
+                    success: true,
+                    target: &target,
+                    output: Some(output_file.display().to_string()),
+                    size: Some(*size),
+                    elapsed_ms: elapsed,
+                    error: None,
+                },
+                Err(e) => CompileReport {
+                    success: false,
+                    target: &target,
+                    output: None,
+                    size: None,
+                    elapsed_ms: elapsed,
+                    error: Some(e.to_string()),
+                },
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+
+    result.map(|_| ())
+}
+
+/// Run the pipeline and write the output, returning its path and size
+fn compile(
+    file: &Path,
+    target: &str,
+    output: Option<PathBuf>,
+    include: &[PathBuf],
+    optimize: bool,
+    security: SecurityPolicy,
+    pretty: bool,
+) -> Result<(PathBuf, usize), Box<dyn std::error::Error>> {
+    if pretty {
+        print_header(file, target);
+    }
 
     // Read source file
-    let source = fs::read_to_string(&file)?;
+    let source = fs::read_to_string(file)?;
 
     // Step 1: Tokenize
-    print_step_header("1", "4", "Tokenizing");
+    if pretty {
+        print_step_header("1", "4", "Tokenizing");
+    }
     let lexer = Lexer::new(&source);
     let tokens = lexer.tokenize().map_err(|e| format!("Lexer error: {}", e))?;
-    print_success(&format!("{} tokens generated", tokens.len()));
-    print_progress_bar(1, 4);
-    println!();
+    if pretty {
+        print_success(&format!("{} tokens generated", tokens.len()));
+        print_progress_bar(1, 4);
+        println!();
+    }
 
     // Step 2: Parse
-    print_step_header("2", "4", "Parsing");
-    let module = parse_module(tokens).map_err(|e| format!("Parse error: {}", e))?;
-    print_success("AST generated successfully");
-    print_progress_bar(2, 4);
-    println!();
+    if pretty {
+        print_step_header("2", "4", "Parsing");
+    }
+    let mut module = parse_module(tokens).map_err(|e| format!("Parse error: {}", e))?;
+    if !module.items.iter().any(|item| matches!(item, Item::Contract(_))) {
+        return Err(format!("No contract to compile: {} declares no contract", file.display()).into());
+    }
+    crate::imports::link_imports(&mut module, file, include)?;
+    if pretty {
+        print_success("AST generated successfully");
+        print_progress_bar(2, 4);
+        println!();
+    }
 
     // Step 3: Semantic analysis
-    print_step_header("3", "4", "Semantic Analysis");
+    if pretty {
+        print_step_header("3", "4", "Semantic Analysis");
+    }
     let mut analyzer = SemanticAnalyzer::new();
+    analyzer.set_security_analysis(security.enabled);
+    analyzer.set_deny_security(security.deny);
     analyzer
-        .analyze(&module)
-        .map_err(|e| format!("Semantic error: {}", e))?;
-    print_success("Type checking passed");
-    println!();
-    print_progress_bar(3, 4);
-    println!();
+        .analyze_all(&mut module)
+        .map_err(|errors| format!("Semantic error: {}", super::check::join_errors(&errors)))?;
+    if pretty {
+        print_success("Type checking passed");
+        println!();
+        print_progress_bar(3, 4);
+        println!();
+    }
 
     // Step 4: Code generation
-    print_step_header("4", "4", "Code Generation");
-    let (code, extension) = match target.as_str() {
+    if pretty {
+        print_step_header("4", "4", "Code Generation");
+    }
+    let (code, extension) = match target {
         "evm" | "ethereum" => {
-            let mut codegen = EvmCodegen::new();
+            let mut codegen = EvmCodegen::new().with_optimize(optimize);
             let code = codegen.generate(&module).map_err(|e| format!("Codegen error: {}", e))?;
-            (code, "yul")
+            (code.into_bytes(), "yul")
         }
         "solana" => {
             let mut codegen = SolanaCodegen::new();
             let code = codegen.generate(&module).map_err(|e| format!("Codegen error: {}", e))?;
-            (code, "rs")
+            (code.into_bytes(), "rs")
         }
         "polkadot" | "ink" => {
             let mut codegen = InkCodegen::new();
             let code = codegen.generate(&module).map_err(|e| format!("Codegen error: {}", e))?;
-            (code, "rs")
+            (code.into_bytes(), "rs")
         }
         "aptos" | "move" => {
             let codegen = AptosCodegen::default();
             let code = codegen.generate(&module).map_err(|e| format!("Codegen error: {}", e))?;
-            (code, "move")
+            (code.into_bytes(), "move")
         }
         "quorlin" | "bytecode" => {
             let mut codegen = QuorlinCodegen::new();
             let bytecode = codegen.generate(&module).map_err(|e| format!("Codegen error: {}", e))?;
-            // Convert bytecode to string for now (in real implementation, write as binary)
-            (String::from_utf8_lossy(&bytecode).to_string(), "qbc")
+            (bytecode, "qbc")
         }
         _ => {
             return Err(format!("Unknown target: {}", target).into());
@@ -156,18 +271,51 @@ pub fn run(
 
     // Write output
     let output_file = output.unwrap_or_else(|| {
-        let mut path = file.clone();
+        let mut path = file.to_path_buf();
         path.set_extension(extension);
         path
     });
 
     fs::write(&output_file, &code)?;
-    print_success(&format!("Generated {}", output_file.display()));
-    print_progress_bar(4, 4);
+    if pretty {
+        print_success(&format!("Generated {}", output_file.display()));
+    }
 
-    // Print success summary
-    let elapsed = start_time.elapsed().as_millis();
-    print_success_box(&output_file, code.len(), elapsed);
+    if matches!(target, "evm" | "ethereum") {
+        for abi_file in write_interface_abis(&module, &output_file)? {
+            if pretty {
+                print_success(&format!("Generated {}", abi_file.display()));
+            }
+        }
+    }
+
+    if pretty {
+        print_progress_bar(4, 4);
+    }
 
-    Ok(())
+    Ok((output_file, code.len()))
+}
+
+/// Write `<Interface>.abi.json` next to `output_file` for every interface in
+/// the module, so callers can generate typed clients for the contracts they
+/// call through
+fn write_interface_abis(module: &Module, output_file: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let contracts: Vec<_> = module
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Contract(contract) => Some(contract),
+            _ => None,
+        })
+        .collect();
+
+    let mut written = Vec::new();
+    for item in &module.items {
+        if let Item::Interface(interface) = item {
+            let path = output_file.with_file_name(format!("{}.abi.json", interface.name));
+            fs::write(&path, ContractAbi::from_interface(interface, &contracts)?.to_json()?)?;
+            written.push(path);
+        }
+    }
+    Ok(written)
 }
@@ -0,0 +1,169 @@
+use colored::Colorize;
+use quorlin_lexer::Lexer;
+use quorlin_parser::visit::{self, Visitor};
+use quorlin_parser::{parse_module, ContractDecl, ContractMember, Expr, Function, Item};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Decorators that make a function callable from outside the contract
+const ENTRY_DECORATORS: &[&str] = &["external", "public", "view", "pure", "payable"];
+
+pub fn run(file: PathBuf, dot: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let source = fs::read_to_string(&file)?;
+
+    let tokens = Lexer::new(&source)
+        .tokenize()
+        .map_err(|e| format!("Lexer error: {}", e))?;
+    let module = parse_module(tokens).map_err(|e| format!("Parse error: {}", e))?;
+
+    for item in &module.items {
+        if let Item::Contract(contract) = item {
+            let graph = CallGraph::build(contract);
+            if dot {
+                print!("{}", graph.to_dot());
+            } else {
+                graph.print();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Internal call graph of one contract
+struct CallGraph<'a> {
+    contract: &'a str,
+    /// Functions in declaration order
+    functions: Vec<&'a Function>,
+    /// Callees of each function in `functions`, in order of first call
+    edges: Vec<Vec<&'a str>>,
+    /// Functions reachable from the constructor or an external entry point
+    reachable: HashSet<&'a str>,
+}
+
+impl<'a> CallGraph<'a> {
+    fn build(contract: &'a ContractDecl) -> Self {
+        let functions: Vec<&Function> = contract
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ContractMember::Function(func) => Some(func),
+                _ => None,
+            })
+            .collect();
+        let names: HashSet<&str> = functions.iter().map(|func| func.name.as_str()).collect();
+
+        let edges: Vec<Vec<&str>> = functions
+            .iter()
+            .map(|func| {
+                let mut collector = CallCollector { names: &names, calls: Vec::new() };
+                for stmt in &func.body {
+                    collector.visit_stmt(stmt);
+                }
+                collector.calls
+            })
+            .collect();
+
+        // Depth-first walk from every entry point
+        let mut reachable = HashSet::new();
+        let mut stack: Vec<&str> = functions
+            .iter()
+            .filter(|func| is_entry_point(func))
+            .map(|func| func.name.as_str())
+            .collect();
+        while let Some(name) = stack.pop() {
+            if !reachable.insert(name) {
+                continue;
+            }
+            if let Some(index) = functions.iter().position(|func| func.name == name) {
+                stack.extend(&edges[index]);
+            }
+        }
+
+        Self {
+            contract: &contract.name,
+            functions,
+            edges,
+            reachable,
+        }
+    }
+
+    fn is_reachable(&self, func: &Function) -> bool {
+        self.reachable.contains(func.name.as_str())
+    }
+
+    /// Print the graph as an adjacency list
+    fn print(&self) {
+        println!("{} {}", "Call graph for".green().bold(), self.contract.bold());
+        for (func, callees) in self.functions.iter().zip(&self.edges) {
+            let mut line = format!("  {}", func.name);
+            if is_entry_point(func) {
+                line.push_str(" [entry]");
+            }
+            if !callees.is_empty() {
+                line.push_str(&format!(" -> {}", callees.join(", ")));
+            }
+            if self.is_reachable(func) {
+                println!("{}", line);
+            } else {
+                println!("{} {}", line, "(unreachable)".red().bold());
+            }
+        }
+        println!();
+    }
+
+    /// Render the graph in Graphviz DOT format. Entry points are boxes and
+    /// unreachable functions are dashed red nodes.
+    fn to_dot(&self) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n", self.contract);
+        for func in &self.functions {
+            if is_entry_point(func) {
+                dot.push_str(&format!("  \"{}\" [shape=box];\n", func.name));
+            } else if !self.is_reachable(func) {
+                dot.push_str(&format!(
+                    "  \"{}\" [style=dashed, color=red, label=\"{} (unreachable)\"];\n",
+                    func.name, func.name
+                ));
+            }
+        }
+        for (func, callees) in self.functions.iter().zip(&self.edges) {
+            for callee in callees {
+                dot.push_str(&format!("  \"{}\" -> \"{}\";\n", func.name, callee));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Externally callable functions and the constructor start every call chain
+fn is_entry_point(func: &Function) -> bool {
+    func.name == "__init__"
+        || (!func.has_decorator("internal") && func.decorators.iter().any(|d| ENTRY_DECORATORS.contains(&d.name.as_str())))
+}
+
+/// Collects calls to the contract's own functions, as `self.name(...)` or
+/// `name(...)`
+struct CallCollector<'a, 'n> {
+    names: &'n HashSet<&'a str>,
+    calls: Vec<&'a str>,
+}
+
+impl<'ast> Visitor<'ast> for CallCollector<'ast, '_> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Expr::Call(callee, _) = expr {
+            let name = match &**callee {
+                Expr::Ident(name) => Some(name),
+                Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => Some(name),
+                _ => None,
+            };
+            if let Some(name) = name.filter(|name| self.names.contains(name.as_str())) {
+                if !self.calls.contains(&name.as_str()) {
+                    self.calls.push(name);
+                }
+            }
+        }
+        visit::walk_expr(self, expr);
+    }
+}
@@ -0,0 +1,245 @@
+//! Minimal language server over stdio
+//!
+//! Implements the initialize handshake and full-document `didOpen`/`didChange`
+//! synchronisation. Every change is lexed, parsed and analyzed, and the findings
+//! are sent back as `textDocument/publishDiagnostics`. Findings are anchored at
+//! the name of the declaration they were raised in, with columns counted in
+//! UTF-16 code units as the protocol requires.
+
+use quorlin_analyzer::{Analyzer, Severity};
+use quorlin_lexer::{Lexer, LexerError, Span};
+use quorlin_parser::{parse_module, ParseError};
+use quorlin_semantics::SemanticAnalyzer;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+/// LSP `DiagnosticSeverity` values
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const SEVERITY_INFORMATION: u8 = 3;
+
+/// JSON-RPC error code for requests the server does not implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            // Drop the bad frame; the client may still send well-formed ones
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                eprintln!("qlc lsp: skipping malformed message: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+
+        match message["method"].as_str().unwrap_or_default() {
+            "initialize" => {
+                let capabilities = json!({
+                    "capabilities": {
+                        // Full document sync
+                        "textDocumentSync": 1
+                    },
+                    "serverInfo": {
+                        "name": "qlc",
+                        "version": env!("CARGO_PKG_VERSION")
+                    }
+                });
+                write_message(&mut output, &json!({ "jsonrpc": "2.0", "id": id, "result": capabilities }))?;
+            }
+            "shutdown" => {
+                write_message(&mut output, &json!({ "jsonrpc": "2.0", "id": id, "result": null }))?;
+            }
+            "exit" => break,
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                let text = document["text"].as_str().unwrap_or_default();
+                publish_diagnostics(&mut output, &document["uri"], text)?;
+            }
+            "textDocument/didChange" => {
+                // With full sync the last change holds the whole document
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                    .unwrap_or_default();
+                publish_diagnostics(&mut output, &params["textDocument"]["uri"], text)?;
+            }
+            _ => {
+                // Notifications we don't handle are ignored; requests get an error
+                if let Some(id) = id {
+                    write_message(
+                        &mut output,
+                        &json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": { "code": METHOD_NOT_FOUND, "message": "Method not found" }
+                        }),
+                    )?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Read one `Content-Length` framed message, or `None` at end of input.
+/// Malformed frames are reported as `InvalidData`; an unparsable body is still
+/// consumed so the next frame can be read
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(length) = header.strip_prefix("Content-Length:") {
+            content_length = length.trim().parse::<usize>().ok();
+        }
+    }
+
+    let length = content_length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+fn publish_diagnostics(output: &mut impl Write, uri: &Value, text: &str) -> io::Result<()> {
+    write_message(
+        output,
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics(text) }
+        }),
+    )
+}
+
+/// Run the front end and analyzer over `source` and collect LSP diagnostics
+fn diagnostics(source: &str) -> Vec<Value> {
+    let tokens = match Lexer::new(source).tokenize() {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            let (line, column) = match &e {
+                LexerError::InvalidToken { line, column }
+                | LexerError::UnterminatedString { line, column }
+                | LexerError::InvalidFString { line, column }
+                | LexerError::InvalidEscape { line, column, .. } => (*line, *column),
+                LexerError::IndentationError(_) => (1, 1),
+            };
+            let offset = line_offset(source, line) + column.saturating_sub(1);
+            let position = position(source, offset);
+            return vec![diagnostic(json!({ "start": position, "end": position }), SEVERITY_ERROR, e.to_string())];
+        }
+    };
+
+    let mut module = match parse_module(tokens.clone()) {
+        Ok(module) => module,
+        Err(e) => {
+            let span = match &e {
+                ParseError::UnexpectedToken(index, _) => tokens.get(*index).or(tokens.last()),
+                ParseError::UnexpectedEof => tokens.last(),
+            };
+            let range = span.map_or_else(document_start, |token| span_range(source, &token.span));
+            return vec![diagnostic(range, SEVERITY_ERROR, e.to_string())];
+        }
+    };
+
+    let mut semantics = SemanticAnalyzer::new();
+    semantics.set_security_analysis(false);
+    if let Err(errors) = semantics.analyze_all(&mut module) {
+        return errors
+            .iter()
+            .zip(semantics.error_spans())
+            .map(|(e, span)| diagnostic(locate(source, span.as_ref()), SEVERITY_ERROR, e.to_string()))
+            .collect();
+    }
+
+    let Ok(result) = Analyzer::new().analyze(&module) else {
+        return Vec::new();
+    };
+
+    let mut diagnostics = Vec::new();
+    for (error, span) in result.type_errors.iter().zip(&result.type_error_spans) {
+        diagnostics.push(diagnostic(locate(source, span.as_ref()), SEVERITY_ERROR, error.clone()));
+    }
+    for issue in &result.security_issues {
+        let severity = diagnostic_severity(&issue.severity);
+        diagnostics.push(diagnostic(locate(source, issue.span.as_ref()), severity, issue.message.clone()));
+    }
+    for warning in &result.lint_warnings {
+        diagnostics.push(diagnostic(
+            locate(source, warning.span.as_ref()),
+            warning.severity.as_ref().map_or(SEVERITY_INFORMATION, diagnostic_severity),
+            format!("[{}] {}", warning.rule, warning.message),
+        ));
+    }
+    diagnostics
+}
+
+fn diagnostic_severity(severity: &Severity) -> u8 {
+    match severity {
+        Severity::Critical | Severity::High | Severity::Medium => SEVERITY_WARNING,
+        Severity::Low | Severity::Info => SEVERITY_INFORMATION,
+    }
+}
+
+fn diagnostic(range: Value, severity: u8, message: String) -> Value {
+    json!({ "range": range, "severity": severity, "source": "qlc", "message": message })
+}
+
+/// Range of a declaration span, or the document start when there is none
+fn locate(source: &str, span: Option<&Span>) -> Value {
+    span.map_or_else(document_start, |span| span_range(source, span))
+}
+
+/// Convert a lexer span (byte offsets into `source`) to an LSP range
+fn span_range(source: &str, span: &Span) -> Value {
+    json!({ "start": position(source, span.start), "end": position(source, span.end) })
+}
+
+/// Byte offset at which the 1-based `line` starts
+fn line_offset(source: &str, line: usize) -> usize {
+    source
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum()
+}
+
+/// 0-based LSP position of a byte offset, with the column in UTF-16 code units
+fn position(source: &str, offset: usize) -> Value {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    let character = before[line_start..].encode_utf16().count();
+    json!({ "line": line, "character": character })
+}
+
+fn document_start() -> Value {
+    json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } })
+}
@@ -1,6 +1,9 @@
+pub mod audit;
 pub mod check;
 pub mod compile;
 pub mod fmt;
+pub mod graph;
 pub mod init;
+pub mod lsp;
 pub mod parse;
 pub mod tokenize;
@@ -1,11 +1,12 @@
 use colored::Colorize;
 use quorlin_lexer::Lexer;
 use quorlin_parser::parse_module;
-use quorlin_semantics::SemanticAnalyzer;
+use quorlin_parser::pretty::pretty_print;
 use std::fs;
 use std::path::PathBuf;
 
-pub fn run(file: PathBuf, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+/// Lex and parse only; semantic errors are left to `check` and `compile`
+pub fn run(file: PathBuf, json: bool, pretty: bool) -> Result<(), Box<dyn std::error::Error>> {
     // Read source file
     let source = fs::read_to_string(&file)?;
 
@@ -16,16 +17,12 @@ pub fn run(file: PathBuf, json: bool) -> Result<(), Box<dyn std::error::Error>>
     // Parse
     let module = parse_module(tokens).map_err(|e| format!("Parse error: {}", e))?;
 
-    // Semantic analysis
-    let mut analyzer = SemanticAnalyzer::new();
-    analyzer
-        .analyze(&module)
-        .map_err(|e| format!("Semantic error: {}", e))?;
-
     if json {
         // Output as JSON
         let json = serde_json::to_string_pretty(&module)?;
         println!("{}", json);
+    } else if pretty {
+        print!("{}", pretty_print(&module));
     } else {
         // Pretty-print AST
         println!(
@@ -45,7 +42,7 @@ pub fn run(file: PathBuf, json: bool) -> Result<(), Box<dyn std::error::Error>>
                     println!(
                         "{} from {} import {}",
                         "Import".blue().bold(),
-                        import.module,
+                        import.source_path(),
                         import.items.join(", ")
                     );
                 }
@@ -57,6 +54,9 @@ pub fn run(file: PathBuf, json: bool) -> Result<(), Box<dyn std::error::Error>>
                         event.params.len()
                     );
                 }
+                quorlin_parser::Item::Const(c) => {
+                    println!("{} {}: {:?}", "Const".cyan().bold(), c.name.bold(), c.type_annotation);
+                }
                 quorlin_parser::Item::Error(error) => {
                     println!(
                         "{} {} ({} parameters)",
@@ -119,7 +119,7 @@ pub fn run(file: PathBuf, json: bool) -> Result<(), Box<dyn std::error::Error>>
         }
 
         println!();
-        println!("{}", "✓ Parse and semantic analysis successful!".green().bold());
+        println!("{}", "✓ Parse successful!".green().bold());
     }
 
     Ok(())
@@ -0,0 +1,84 @@
+//! Linking of project modules imported relatively or found on `--include`
+//! paths
+
+use quorlin_lexer::Lexer;
+use quorlin_parser::{parse_module, ImportStmt, Item, Module};
+use quorlin_resolver::StdlibResolver;
+use std::path::{Path, PathBuf};
+
+/// Append the items `module`, read from `file`, imports from modules next to
+/// it (relative imports) or on the `include` paths, so they are checked and
+/// compiled along with it. std imports are left alone.
+pub fn link_imports(module: &mut Module, file: &Path, include: &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> {
+    let imports: Vec<&ImportStmt> = module
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Import(import)
+                if import.is_relative()
+                    || (!include.is_empty() && import.module.split('.').next() != Some("std")) =>
+            {
+                Some(import)
+            }
+            _ => None,
+        })
+        .collect();
+    if imports.is_empty() {
+        return Ok(());
+    }
+
+    let mut resolver = StdlibResolver::new(&compiler_root());
+    for dir in include {
+        resolver.add_include_path(dir);
+    }
+
+    let mut linked = Vec::new();
+    for import in imports {
+        let module_name = import.source_path();
+        // A missing relative module is already an error here
+        let source = resolver.resolve_import_from(import, file)?.ok_or_else(|| {
+            let searched: Vec<String> = include.iter().map(|dir| dir.display().to_string()).collect();
+            format!("Module not found: {} (searched {})", module_name, searched.join(", "))
+        })?;
+        let tokens = Lexer::new(&source)
+            .tokenize()
+            .map_err(|e| format!("Lexer error in {}: {}", module_name, e))?;
+        let imported = parse_module(tokens).map_err(|e| format!("Parse error in {}: {}", module_name, e))?;
+
+        for name in &import.items {
+            let item = imported
+                .items
+                .iter()
+                .find(|item| item_name(item) == Some(name.as_str()))
+                .ok_or_else(|| format!("Module {} has no item '{}'", module_name, name))?;
+            linked.push(item.clone());
+        }
+    }
+
+    module.items.extend(linked);
+    Ok(())
+}
+
+fn item_name(item: &Item) -> Option<&str> {
+    match item {
+        Item::Import(_) => None,
+        Item::Contract(decl) => Some(&decl.name),
+        Item::Struct(decl) => Some(&decl.name),
+        Item::Enum(decl) => Some(&decl.name),
+        Item::Interface(decl) => Some(&decl.name),
+        Item::Event(decl) => Some(&decl.name),
+        Item::Error(decl) => Some(&decl.name),
+        Item::Const(decl) => Some(&decl.name),
+    }
+}
+
+/// The installation directory holding `stdlib`, found by walking up from the
+/// running executable
+fn compiler_root() -> PathBuf {
+    let exe = std::env::current_exe().unwrap_or_default();
+    exe.ancestors()
+        .find(|dir| dir.join("stdlib").is_dir())
+        .or_else(|| exe.parent())
+        .unwrap_or(Path::new("."))
+        .to_path_buf()
+}
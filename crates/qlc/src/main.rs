@@ -1,8 +1,10 @@
 use clap::{Parser, Subcommand};
 use colored::Colorize;
+use quorlin_semantics::security_analyzer::Severity;
 use std::path::PathBuf;
 
 mod commands;
+mod imports;
 
 #[derive(Parser)]
 #[command(name = "qlc")]
@@ -11,6 +13,10 @@ mod commands;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Add a source root searched for imported project modules (repeatable)
+    #[arg(long = "include", value_name = "DIR", global = true)]
+    include: Vec<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -20,8 +26,8 @@ enum Commands {
         /// Input .ql file
         file: PathBuf,
 
-        /// Target platform (evm, solana, ink)
-        #[arg(short, long)]
+        /// Target platform (evm, solana, ink, aptos, quorlin)
+        #[arg(short, long, value_parser = clap::builder::PossibleValuesParser::new(commands::compile::TARGETS))]
         target: String,
 
         /// Output file or directory
@@ -35,12 +41,52 @@ enum Commands {
         /// Enable optimizations
         #[arg(long)]
         optimize: bool,
+
+        /// Skip the security analysis pass
+        #[arg(long)]
+        no_security: bool,
+
+        /// Fail when security warnings at or above this severity are found
+        /// (low, medium, high, critical)
+        #[arg(long, value_name = "SEVERITY", conflicts_with = "no_security")]
+        deny_security: Option<Severity>,
+
+        /// Suppress banners and progress output
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Result format
+        #[arg(long, value_enum, default_value_t = Format::Pretty)]
+        format: Format,
     },
 
     /// Type-check without generating code
     Check {
+        /// Input .ql files, or directories to search for them
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Skip the security analysis pass
+        #[arg(long)]
+        no_security: bool,
+
+        /// Skip the lint pass
+        #[arg(long)]
+        no_lints: bool,
+
+        /// Skip gas estimation
+        #[arg(long)]
+        no_gas: bool,
+    },
+
+    /// Write a Markdown audit report of all analyzer findings
+    Audit {
         /// Input .ql file
         file: PathBuf,
+
+        /// Output Markdown file (stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 
     /// Tokenize a file and display tokens (for debugging)
@@ -59,8 +105,23 @@ enum Commands {
         file: PathBuf,
 
         /// Output as JSON
-        #[arg(long)]
+        #[arg(long, conflicts_with = "pretty")]
         json: bool,
+
+        /// Output the full AST as an indented tree
+        #[arg(long)]
+        pretty: bool,
+    },
+
+    /// Print the internal call graph of each contract, flagging functions
+    /// unreachable from any external entry point
+    Graph {
+        /// Input .ql file
+        file: PathBuf,
+
+        /// Output Graphviz DOT instead of an adjacency list
+        #[arg(long)]
+        dot: bool,
     },
 
     /// Format Quorlin source code
@@ -74,6 +135,15 @@ enum Commands {
         /// Project name
         name: String,
     },
+
+    /// Run a language server over stdio
+    Lsp,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Format {
+    Pretty,
+    Json,
 }
 
 fn main() {
@@ -86,17 +156,43 @@ fn main() {
             output,
             emit_ir,
             optimize,
-        } => commands::compile::run(file, target, output, emit_ir, optimize),
-
-        Commands::Check { file } => commands::check::run(file),
+            no_security,
+            deny_security,
+            quiet,
+            format,
+        } => {
+            let mode = match (format, quiet) {
+                (Format::Json, _) => commands::compile::OutputMode::Json,
+                (Format::Pretty, true) => commands::compile::OutputMode::Quiet,
+                (Format::Pretty, false) => commands::compile::OutputMode::Pretty,
+            };
+            let security = commands::compile::SecurityPolicy {
+                enabled: !no_security,
+                deny: deny_security,
+            };
+            commands::compile::run(file, target, output, &cli.include, emit_ir, optimize, security, mode)
+        }
+
+        Commands::Check {
+            files,
+            no_security,
+            no_lints,
+            no_gas,
+        } => commands::check::run(files, &cli.include, no_security, no_lints, no_gas),
+
+        Commands::Audit { file, output } => commands::audit::run(file, output),
 
         Commands::Tokenize { file, json } => commands::tokenize::run(file, json),
 
-        Commands::Parse { file, json } => commands::parse::run(file, json),
+        Commands::Parse { file, json, pretty } => commands::parse::run(file, json, pretty),
+
+        Commands::Graph { file, dot } => commands::graph::run(file, dot),
 
         Commands::Fmt { file } => commands::fmt::run(file),
 
         Commands::Init { name } => commands::init::run(name),
+
+        Commands::Lsp => commands::lsp::run(),
     };
 
     if let Err(e) = result {
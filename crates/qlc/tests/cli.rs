@@ -0,0 +1,470 @@
+//! End-to-end tests driving the `qlc` binary
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Write `source` to a uniquely named file in the temp directory
+fn write_source(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("qlc-cli-{}-{}.ql", std::process::id(), name));
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+fn qlc(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_qlc"))
+        .args(args)
+        .output()
+        .expect("failed to run qlc")
+}
+
+const REENTRANT: &str = r#"
+contract Bank:
+    _balances: mapping[address, uint256]
+
+    @external
+    fn withdraw(vault: address):
+        let ok: bool = vault.send(self._balances[msg.sender])
+        self._balances[msg.sender] = 0
+"#;
+
+#[test]
+fn test_no_security_suppresses_reentrancy_warning() {
+    let file = write_source("reentrant", REENTRANT);
+    let path = file.to_str().unwrap();
+    let out = file.with_extension("move");
+
+    let output = qlc(&["compile", path, "--target", "aptos", "--quiet"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("REENTRANCY"));
+
+    let output = qlc(&["compile", path, "--target", "aptos", "--quiet", "--no-security"]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("REENTRANCY"));
+
+    for path in [file, out] {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+#[test]
+fn test_parse_skips_semantic_analysis() {
+    let source = r#"
+contract Broken:
+    _count: uint256
+
+    fn __init__():
+        self._count = 0
+
+    fn bad():
+        self._count = True
+"#;
+    let file = write_source("parse-only", source);
+    let path = file.to_str().unwrap();
+
+    let output = qlc(&["parse", path]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = qlc(&["check", path]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Semantic error"));
+
+    std::fs::remove_file(file).ok();
+}
+
+#[test]
+fn test_check_directory_fails_if_any_file_fails() {
+    let dir = std::env::temp_dir().join(format!("qlc-cli-{}-check-dir", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("clean.ql"), "event Transfer(sender: address, amount: uint256)\n").unwrap();
+    std::fs::write(dir.join("broken.ql"), "contract C:\n    _count: uint256\n\n    fn bad():\n        self._count = True\n").unwrap();
+
+    let output = qlc(&["check", dir.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("clean.ql") && stdout.contains("broken.ql"), "{}", stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 of 2 file(s) failed"), "{}", stderr);
+
+    std::fs::remove_file(dir.join("broken.ql")).unwrap();
+    let output = qlc(&["check", dir.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_include_path_resolves_project_imports() {
+    let root = std::env::temp_dir().join(format!("qlc-cli-{}-include", std::process::id()));
+    std::fs::create_dir_all(root.join("myproj")).unwrap();
+    std::fs::write(root.join("myproj").join("events.ql"), "event Ping(value: uint256)\n").unwrap();
+    let file = write_source(
+        "include",
+        "from myproj.events import Ping\n\ncontract Pinger:\n    @external\n    fn ping():\n        emit Ping(1)\n",
+    );
+    let path = file.to_str().unwrap();
+
+    let output = qlc(&["compile", path, "--target", "evm", "--quiet"]);
+    assert!(!output.status.success());
+
+    let output = qlc(&["compile", path, "--target", "evm", "--quiet", "--include", root.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let yul = std::fs::read_to_string(file.with_extension("yul")).unwrap();
+    assert!(yul.contains("log1"), "{}", yul);
+
+    std::fs::remove_file(file.with_extension("yul")).ok();
+    std::fs::remove_file(file).ok();
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn test_relative_import_links_sibling_module() {
+    let dir = std::env::temp_dir().join(format!("qlc-cli-{}-relative", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("utils.ql"), "event Ping(value: uint256)\n").unwrap();
+    let main = dir.join("main.ql");
+    std::fs::write(
+        &main,
+        "from .utils import Ping\n\ncontract Pinger:\n    @external\n    fn ping():\n        emit Ping(1)\n",
+    )
+    .unwrap();
+    let path = main.to_str().unwrap();
+
+    let output = qlc(&["check", path]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = qlc(&["compile", path, "--target", "evm", "--quiet"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let yul = std::fs::read_to_string(main.with_extension("yul")).unwrap();
+    assert!(yul.contains("log1"), "{}", yul);
+
+    std::fs::remove_file(dir.join("utils.ql")).unwrap();
+    let output = qlc(&["compile", path, "--target", "evm", "--quiet"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Module not found: .utils"), "{}", stderr);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_deny_security_fails_compile_on_reentrancy() {
+    let file = write_source("deny-security", REENTRANT);
+    let path = file.to_str().unwrap();
+    let out = file.with_extension("move");
+    let out = out.to_str().unwrap();
+
+    let output = qlc(&["compile", path, "--target", "aptos", "--quiet", "-o", out]);
+    assert!(output.status.success());
+
+    let output = qlc(&["compile", path, "--target", "aptos", "--quiet", "-o", out, "--deny-security", "high"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("security warning(s) at or above high severity"), "{}", stderr);
+
+    std::fs::remove_file(file).ok();
+    std::fs::remove_file(out).ok();
+}
+
+#[test]
+fn test_event_only_file_checks_but_does_not_compile() {
+    let file = write_source("event-only", "event Transfer(sender: address, amount: uint256)\n");
+    let path = file.to_str().unwrap();
+
+    let output = qlc(&["check", path]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = qlc(&["compile", path, "--target", "evm", "--quiet"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No contract to compile"), "{}", stderr);
+    assert!(!file.with_extension("yul").exists());
+
+    std::fs::remove_file(file).ok();
+}
+
+#[test]
+fn test_parse_pretty_prints_indented_tree() {
+    let source = r#"
+contract SimpleStorage:
+    stored: uint256
+
+    @external
+    fn set(value: uint256):
+        self.stored = value
+
+    @view
+    fn get() -> uint256:
+        return self.stored
+"#;
+    let file = write_source("pretty", source);
+
+    let output = qlc(&["parse", file.to_str().unwrap(), "--pretty"]);
+    assert!(output.status.success());
+    let expected = "\
+Contract \"SimpleStorage\"
+  StateVar stored: uint256
+  Function \"set\" @external
+    Param value: uint256
+    Assign self.stored = value
+  Function \"get\" @view
+    Returns uint256
+    Return self.stored
+";
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+
+    std::fs::remove_file(file).ok();
+}
+
+const TARGETED: &str = r#"
+contract Bridge:
+    _count: uint256
+
+    fn __init__():
+        self._count = 0
+
+    @external
+    fn shared() -> uint256:
+        return self._count
+
+    @target("evm")
+    @external
+    fn evm_only() -> uint256:
+        return 1
+"#;
+
+#[test]
+fn test_target_decorator_gates_backend_output() {
+    let file = write_source("targeted", TARGETED);
+    let path = file.to_str().unwrap();
+    let yul = file.with_extension("yul");
+    let ink = file.with_extension("rs");
+
+    let output = qlc(&["compile", path, "--target", "evm", "-o", yul.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let code = std::fs::read_to_string(&yul).unwrap();
+    assert!(code.contains("evm_only"));
+    assert!(code.contains("shared"));
+
+    let output = qlc(&["compile", path, "--target", "ink", "-o", ink.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let code = std::fs::read_to_string(&ink).unwrap();
+    assert!(!code.contains("evm_only"));
+    assert!(code.contains("shared"));
+
+    for path in [file, yul, ink] {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+const COUNTER: &str = r#"
+contract Counter:
+    _count: uint256
+
+    fn __init__():
+        self._count = 0
+
+    @view
+    fn count() -> uint256:
+        return self._count
+"#;
+
+#[test]
+fn test_quiet_compile_has_no_decorations() {
+    let file = write_source("quiet", COUNTER);
+    let yul = file.with_extension("yul");
+
+    let output = qlc(&["compile", file.to_str().unwrap(), "--target", "evm", "--quiet"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let is_box_char = |c: char| ('\u{2500}'..='\u{259F}').contains(&c);
+    assert!(!stdout.chars().any(is_box_char), "{}", stdout);
+    assert!(!stderr.chars().any(is_box_char), "{}", stderr);
+    assert!(stdout.contains(yul.to_str().unwrap()));
+
+    let output = qlc(&["compile", file.to_str().unwrap(), "--target", "evm", "--format", "json"]);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["success"], true);
+    assert_eq!(report["target"], "evm");
+
+    for path in [file, yul] {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+#[test]
+fn test_compile_to_quorlin_bytecode() {
+    let file = write_source("bytecode", COUNTER);
+    let qbc = file.with_extension("qbc");
+
+    let output = qlc(&["compile", file.to_str().unwrap(), "--target", "quorlin", "--quiet"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let bytecode = std::fs::read(&qbc).unwrap();
+    assert!(bytecode.starts_with(b"# Quorlin Bytecode\n# Magic: QBC"));
+
+    let output = qlc(&["compile", file.to_str().unwrap(), "--target", "wasm"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("quorlin"));
+
+    for path in [file, qbc] {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+/// Frame a JSON-RPC message for the language server
+fn lsp_frame(message: serde_json::Value) -> String {
+    let body = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+/// Open `source` in a language server session, with `prefix` sent first,
+/// and return every message the server wrote
+fn lsp_session(prefix: &str, uri: &str, source: &str) -> Vec<serde_json::Value> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let session = [
+        prefix.to_string(),
+        lsp_frame(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} })),
+        lsp_frame(serde_json::json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} })),
+        lsp_frame(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": uri, "languageId": "quorlin", "version": 1, "text": source } }
+        })),
+        lsp_frame(serde_json::json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" })),
+        lsp_frame(serde_json::json!({ "jsonrpc": "2.0", "method": "exit" })),
+    ]
+    .concat();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_qlc"))
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run qlc lsp");
+    child.stdin.take().unwrap().write_all(session.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stdout
+        .split("Content-Length: ")
+        .filter_map(|frame| frame.split_once("\r\n\r\n"))
+        .map(|(_, body)| serde_json::from_str(body).unwrap())
+        .collect()
+}
+
+/// The first error diagnostic published for `uri`
+fn lsp_error(messages: &[serde_json::Value], uri: &str) -> serde_json::Value {
+    let published = messages
+        .iter()
+        .find(|m| m["method"] == "textDocument/publishDiagnostics")
+        .expect("no diagnostics published");
+    assert_eq!(published["params"]["uri"], uri);
+    published["params"]["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["severity"] == 1)
+        .expect("no error diagnostic")
+        .clone()
+}
+
+#[test]
+fn test_lsp_publishes_type_error_at_enclosing_function() {
+    let source = "contract Counter:\n    _count: uint256\n\n    @external\n    fn bump(n: uint256):\n        self._count = helper(n)\n";
+    let uri = "file:///counter.ql";
+    let messages = lsp_session("", uri, source);
+
+    assert_eq!(messages[0]["id"], 1);
+    assert_eq!(messages[0]["result"]["capabilities"]["textDocumentSync"], 1);
+
+    let diagnostic = lsp_error(&messages, uri);
+    assert_eq!(diagnostic["message"], "call to undefined function 'helper'");
+    assert_eq!(
+        diagnostic["range"],
+        serde_json::json!({ "start": { "line": 4, "character": 7 }, "end": { "line": 4, "character": 11 } })
+    );
+}
+
+#[test]
+fn test_lsp_skips_malformed_frame_and_counts_utf16_columns() {
+    // The string literal is 5 UTF-16 code units but 8 bytes long
+    let source = "contract Notes:\n    _note: str\n\n    @external\n    fn write():\n        self._note = \"é😀\" $\n";
+    let uri = "file:///notes.ql";
+    let messages = lsp_session("Content-Length: 9\r\n\r\nnot json!", uri, source);
+
+    assert_eq!(messages[0]["id"], 1);
+    let diagnostic = lsp_error(&messages, uri);
+    assert_eq!(
+        diagnostic["range"]["start"],
+        serde_json::json!({ "line": 5, "character": 27 })
+    );
+}
+
+const REENTRANT_TOKEN: &str = r#"
+contract Token:
+    _balances: mapping[address, uint256]
+
+    @external
+    fn withdraw(vault: address):
+        vault.send(self._balances[msg.sender])
+        self._balances[msg.sender] = 0
+"#;
+
+#[test]
+fn test_audit_report_lists_reentrancy() {
+    let file = write_source("audit", REENTRANT_TOKEN);
+    let report = file.with_extension("md");
+
+    let output = qlc(&["audit", file.to_str().unwrap(), "--output", report.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let markdown = std::fs::read_to_string(&report).unwrap();
+    assert!(markdown.contains("## Security Findings"), "{}", markdown);
+    assert!(markdown.contains("| Reentrancy | withdraw |"), "{}", markdown);
+    assert!(markdown.contains("## Storage Layout"));
+    assert!(markdown.contains("Backend Type Consistency Report"));
+
+    for path in [file, report] {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+#[test]
+fn test_graph_marks_dead_helpers_unreachable() {
+    let source = r#"
+contract Token:
+    _balances: mapping[address, uint256]
+
+    @external
+    fn transfer(to: address, amount: uint256):
+        self._move(msg.sender, to, amount)
+
+    fn _move(sender: address, to: address, amount: uint256):
+        self._balances[sender] = self._balances[sender] - amount
+        self._balances[to] = self._balances[to] + amount
+
+    fn _unused_helper():
+        pass
+"#;
+    let file = write_source("graph", source);
+    let path = file.to_str().unwrap();
+
+    let output = qlc(&["graph", path]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = |name: &str| stdout.lines().find(|l| l.trim_start().starts_with(name)).unwrap().to_string();
+    assert_eq!(line("transfer").trim(), "transfer [entry] -> _move");
+    assert!(!line("_move").contains("unreachable"), "{}", stdout);
+    assert!(line("_unused_helper").contains("(unreachable)"), "{}", stdout);
+
+    let output = qlc(&["graph", path, "--dot"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\"transfer\" -> \"_move\";"), "{}", stdout);
+    assert!(stdout.contains("\"_unused_helper\" [style=dashed"), "{}", stdout);
+
+    std::fs::remove_file(file).ok();
+}
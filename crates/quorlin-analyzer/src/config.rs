@@ -0,0 +1,290 @@
+//! Project Configuration
+//!
+//! Rule selection read from an optional `.quorlin.toml` next to the sources:
+//!
+//! ```toml
+//! [lints]
+//! magic-number = false          # or "off"
+//! magic-number-allowlist = [0, 1, 2, 18, 100]
+//!
+//! [security]
+//! timestamp-dependence = "off"
+//! access-control = "medium"     # override the reported severity
+//!
+//! [limits]
+//! max-functions = 20            # functions per contract
+//! max-total-gas = 2000000       # sum of the contract's function estimates
+//! ```
+//!
+//! Rules that are not mentioned keep their default behaviour; naming a rule
+//! that does not exist is an error.
+
+use crate::lints::LINT_RULES;
+use crate::{AnalyzerError, SecurityCategory, Severity};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Name of the project manifest searched for next to the source file
+pub const CONFIG_FILE_NAME: &str = ".quorlin.toml";
+
+/// How a single rule is configured
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleSetting {
+    Enabled,
+    Disabled,
+    /// Enabled, reporting findings at this severity
+    Severity(Severity),
+}
+
+/// Rule configuration consumed by `Linter`, `SecurityAnalyzer` and `Analyzer`
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzerConfig {
+    /// Lint rules by name, e.g. `magic-number`
+    pub lints: HashMap<String, RuleSetting>,
+    /// Security rules by category name, e.g. `reentrancy`
+    pub security: HashMap<String, RuleSetting>,
+    /// Numbers the `magic-number` lint accepts; `None` keeps the default 0, 1 and 2
+    pub magic_number_allowlist: Option<Vec<u64>>,
+    /// Contract size thresholds; exceeding one is reported as a lint
+    pub limits: ContractLimits,
+}
+
+/// Project-level caps on contract size, unlimited by default
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ContractLimits {
+    /// Maximum number of functions in one contract
+    pub max_functions: Option<usize>,
+    /// Maximum sum of the estimated gas of a contract's functions
+    pub max_total_gas: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    #[serde(default)]
+    lints: RawLints,
+    #[serde(default)]
+    security: HashMap<String, RawSetting>,
+    #[serde(default)]
+    limits: ContractLimits,
+}
+
+#[derive(Deserialize, Default)]
+struct RawLints {
+    #[serde(rename = "magic-number-allowlist")]
+    magic_number_allowlist: Option<Vec<u64>>,
+    #[serde(flatten)]
+    rules: HashMap<String, RawSetting>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSetting {
+    Flag(bool),
+    Name(String),
+}
+
+impl AnalyzerConfig {
+    /// Parses the contents of a `.quorlin.toml`
+    pub fn from_toml(source: &str) -> Result<Self, AnalyzerError> {
+        let raw: RawConfig = toml::from_str(source)
+            .map_err(|e| AnalyzerError::ConfigError(e.to_string()))?;
+
+        Ok(Self {
+            lints: Self::convert_section("lints", LINT_RULES, raw.lints.rules)?,
+            security: Self::convert_section("security", SecurityCategory::RULE_NAMES, raw.security)?,
+            magic_number_allowlist: raw.lints.magic_number_allowlist,
+            limits: raw.limits,
+        })
+    }
+
+    /// Loads the `.quorlin.toml` nearest to `source_file`, searching its
+    /// directory and then each parent. Returns the default configuration when
+    /// there is none.
+    pub fn load_for(source_file: &Path) -> Result<Self, AnalyzerError> {
+        match Self::find_manifest(source_file) {
+            Some(path) => {
+                let contents = std::fs::read_to_string(&path).map_err(|e| {
+                    AnalyzerError::ConfigError(format!("{}: {}", path.display(), e))
+                })?;
+                Self::from_toml(&contents).map_err(|e| match e {
+                    AnalyzerError::ConfigError(msg) => {
+                        AnalyzerError::ConfigError(format!("{}: {}", path.display(), msg))
+                    }
+                    other => other,
+                })
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn find_manifest(source_file: &Path) -> Option<PathBuf> {
+        let start = source_file.parent().unwrap_or(Path::new("."));
+        let start = if start.as_os_str().is_empty() { Path::new(".") } else { start };
+        start
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    fn convert_section(
+        section: &str,
+        known: &[&str],
+        raw: HashMap<String, RawSetting>,
+    ) -> Result<HashMap<String, RuleSetting>, AnalyzerError> {
+        raw.into_iter()
+            .map(|(rule, setting)| {
+                if !known.contains(&rule.as_str()) {
+                    return Err(AnalyzerError::ConfigError(format!(
+                        "Unknown rule '{}' in [{}]; expected one of: {}",
+                        rule, section, known.join(", ")
+                    )));
+                }
+                let setting = match setting {
+                    RawSetting::Flag(true) => RuleSetting::Enabled,
+                    RawSetting::Flag(false) => RuleSetting::Disabled,
+                    RawSetting::Name(name) => match name.to_ascii_lowercase().as_str() {
+                        "on" | "enabled" => RuleSetting::Enabled,
+                        "off" | "disabled" => RuleSetting::Disabled,
+                        level => RuleSetting::Severity(Severity::from_name(level).ok_or_else(|| {
+                            AnalyzerError::ConfigError(format!(
+                                "Invalid setting '{}' for rule '{}' in [{}]; expected on, off or a severity",
+                                name, rule, section
+                            ))
+                        })?),
+                    },
+                };
+                Ok((rule, setting))
+            })
+            .collect()
+    }
+
+    /// The setting for a lint rule, if configured
+    pub fn lint_setting(&self, rule: &str) -> Option<&RuleSetting> {
+        self.lints.get(rule)
+    }
+
+    /// The setting for a security category, if configured
+    pub fn security_setting(&self, category: &SecurityCategory) -> Option<&RuleSetting> {
+        self.security.get(category.rule_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lints::Linter;
+    use quorlin_lexer::Lexer;
+
+    #[test]
+    fn test_disabling_magic_number_suppresses_lint() {
+        let source = r#"
+contract Fees:
+    _fee: uint256
+
+    fn set_fee():
+        self._fee = 12345
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+
+        let has_magic_number = |linter: &mut Linter| {
+            linter.lint(&module).iter().any(|w| w.rule == "magic-number")
+        };
+        assert!(has_magic_number(&mut Linter::new()));
+
+        let config = AnalyzerConfig::from_toml("[lints]\nmagic-number = false\n").unwrap();
+        assert!(!has_magic_number(&mut Linter::with_config(config)));
+    }
+
+    #[test]
+    fn test_magic_number_allowlist() {
+        let source = r#"
+contract Token:
+    _decimals: uint256
+
+    fn setup():
+        self._decimals = 18
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+
+        let has_magic_number = |linter: &mut Linter| {
+            linter.lint(&module).iter().any(|w| w.rule == "magic-number")
+        };
+        assert!(has_magic_number(&mut Linter::new()));
+        assert!(!has_magic_number(&mut Linter::with_allowed_numbers([0, 1, 2, 18])));
+
+        let config = AnalyzerConfig::from_toml("[lints]\nmagic-number-allowlist = [0, 1, 2, 18]\n").unwrap();
+        assert!(!has_magic_number(&mut Linter::with_config(config)));
+    }
+
+    #[test]
+    fn test_contract_over_function_limit_is_flagged() {
+        let source = r#"
+contract Sprawl:
+    _value: uint256
+
+    fn first():
+        pass
+
+    fn second():
+        pass
+
+    fn third():
+        pass
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+
+        let size_warnings = |config: AnalyzerConfig| -> Vec<String> {
+            Linter::with_config(config)
+                .lint(&module)
+                .into_iter()
+                .filter(|w| w.rule == "max-functions")
+                .map(|w| w.message)
+                .collect()
+        };
+        assert!(size_warnings(AnalyzerConfig::default()).is_empty());
+
+        let config = AnalyzerConfig::from_toml("[limits]\nmax-functions = 2\n").unwrap();
+        let warnings = size_warnings(config);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'Sprawl' has 3 functions"), "{}", warnings[0]);
+    }
+
+    #[test]
+    fn test_invalid_setting_is_rejected() {
+        let err = AnalyzerConfig::from_toml("[security]\nreentrancy = \"sometimes\"\n").unwrap_err();
+        assert!(err.to_string().contains("reentrancy"), "{}", err);
+    }
+
+    #[test]
+    fn test_unknown_rule_is_rejected() {
+        let err = AnalyzerConfig::from_toml("[lints]\nmagic-numbers = false\n").unwrap_err();
+        assert!(err.to_string().contains("Unknown rule 'magic-numbers' in [lints]"), "{}", err);
+
+        let err = AnalyzerConfig::from_toml("[security]\nre-entrancy = \"off\"\n").unwrap_err();
+        assert!(err.to_string().contains("Unknown rule 're-entrancy' in [security]"), "{}", err);
+    }
+
+    #[test]
+    fn test_configured_lint_severity_is_reported() {
+        let source = r#"
+contract Fees:
+    _fee: uint256
+
+    fn set_fee():
+        self._fee = 12345
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+
+        let config = AnalyzerConfig::from_toml("[lints]\nmagic-number = \"high\"\n").unwrap();
+        let warnings = Linter::with_config(config).lint(&module);
+        let magic = warnings.iter().find(|w| w.rule == "magic-number").unwrap();
+        assert_eq!(magic.severity, Some(Severity::High));
+    }
+}
@@ -9,6 +9,12 @@ pub struct GasEstimator {
     estimates: Vec<GasEstimate>,
 }
 
+impl Default for GasEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GasEstimator {
     pub fn new() -> Self {
         Self {
@@ -37,7 +43,7 @@ impl GasEstimator {
         }
     }
     
-    fn estimate_function(&self, func: &Function) -> GasEstimate {
+    pub fn estimate_function(&self, func: &Function) -> GasEstimate {
         let mut gas = 21000; // Base transaction cost
         let mut complexity = GasComplexity::Constant;
         
@@ -102,8 +108,12 @@ impl GasEstimator {
                     .map(|s| self.estimate_statement(s).0)
                     .sum();
                 
+                let else_gas: u64 = while_stmt.else_branch.iter().flatten()
+                    .map(|s| self.estimate_statement(s).0)
+                    .sum();
+
                 // Assume 10 iterations for estimation
-                let gas = (condition_gas + body_gas) * 10;
+                let gas = (condition_gas + body_gas) * 10 + else_gas;
                 
                 (gas, GasComplexity::Linear)
             }
@@ -124,8 +134,12 @@ impl GasEstimator {
                     GasComplexity::Linear
                 };
                 
+                let else_gas: u64 = for_stmt.else_branch.iter().flatten()
+                    .map(|s| self.estimate_statement(s).0)
+                    .sum();
+
                 // Assume 10 iterations
-                (body_gas * 10, complexity)
+                (body_gas * 10 + else_gas, complexity)
             }
             
             Stmt::Expr(expr) => {
@@ -138,7 +152,7 @@ impl GasEstimator {
         }
     }
     
-    fn estimate_expression(&self, expr: &Expr) -> u64 {
+    pub(crate) fn estimate_expression(&self, expr: &Expr) -> u64 {
         match expr {
             Expr::IntLiteral(_) | Expr::BoolLiteral(_) | Expr::NoneLiteral => 10,
             
@@ -156,8 +170,11 @@ impl GasEstimator {
                     BinOp::Mul => 5,
                     BinOp::Div | BinOp::Mod | BinOp::FloorDiv => 5,
                     BinOp::Pow => 10,
+                    BinOp::Shl | BinOp::Shr => 3,
+                    BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor => 3,
                     BinOp::Eq | BinOp::NotEq | BinOp::Lt | BinOp::LtEq | BinOp::Gt | BinOp::GtEq => 3,
                     BinOp::And | BinOp::Or => 3,
+                    BinOp::In => 36, // keccak256 of key and slot
                 };
                 left_gas + right_gas + op_gas
             }
@@ -217,6 +234,19 @@ impl GasEstimator {
                 let orelse_gas = self.estimate_expression(orelse);
                 test_gas + body_gas.max(orelse_gas) + 50 // Add overhead for conditional logic
             }
+
+            // Naming an argument costs nothing at runtime
+            Expr::KeywordArg(_, value) => self.estimate_expression(value),
+
+            Expr::ListComp { element, iterable, condition, .. } => {
+                // The length is unknown statically; count a single element
+                let condition_gas = condition.as_ref().map_or(0, |c| self.estimate_expression(c));
+                self.estimate_expression(iterable) + self.estimate_expression(element) + condition_gas + 100
+            }
+
+            Expr::FString(parts) => {
+                parts.iter().map(|part| self.estimate_expression(part)).sum::<u64>() + 100
+            }
         }
     }
 }
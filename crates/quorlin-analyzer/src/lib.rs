@@ -10,7 +10,12 @@ pub mod typeck;
 pub mod security;
 pub mod gas;
 pub mod lints;
+pub mod config;
 
+pub use config::AnalyzerConfig;
+pub use quorlin_common::severity::Severity;
+
+use quorlin_lexer::Span;
 use quorlin_parser::ast::Module;
 use thiserror::Error;
 
@@ -24,12 +29,17 @@ pub enum AnalyzerError {
     
     #[error("Lint error: {0}")]
     LintError(String),
+    
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
 }
 
 /// Analysis result containing all findings
 #[derive(Debug, Clone)]
 pub struct AnalysisResult {
     pub type_errors: Vec<String>,
+    /// Declaration each type error was found in, by index into `type_errors`
+    pub type_error_spans: Vec<Option<Span>>,
     pub security_issues: Vec<SecurityIssue>,
     pub gas_estimates: Vec<GasEstimate>,
     pub lint_warnings: Vec<LintWarning>,
@@ -41,15 +51,8 @@ pub struct SecurityIssue {
     pub category: SecurityCategory,
     pub message: String,
     pub location: Option<String>,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Severity {
-    Critical,
-    High,
-    Medium,
-    Low,
-    Info,
+    /// Declaration of the flagged item
+    pub span: Option<Span>,
 }
 
 #[derive(Debug, Clone)]
@@ -63,6 +66,31 @@ pub enum SecurityCategory {
     Other(String),
 }
 
+impl SecurityCategory {
+    /// Rule names of the built-in categories
+    pub const RULE_NAMES: &'static [&'static str] = &[
+        "reentrancy",
+        "integer-overflow",
+        "unchecked-call",
+        "access-control",
+        "front-running",
+        "timestamp-dependence",
+    ];
+
+    /// Rule name used to configure this category in `.quorlin.toml`
+    pub fn rule_name(&self) -> &str {
+        match self {
+            SecurityCategory::Reentrancy => "reentrancy",
+            SecurityCategory::IntegerOverflow => "integer-overflow",
+            SecurityCategory::UncheckedCall => "unchecked-call",
+            SecurityCategory::AccessControl => "access-control",
+            SecurityCategory::FrontRunning => "front-running",
+            SecurityCategory::TimestampDependence => "timestamp-dependence",
+            SecurityCategory::Other(name) => name,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GasEstimate {
     pub function_name: String,
@@ -83,12 +111,23 @@ pub struct LintWarning {
     pub rule: String,
     pub message: String,
     pub location: Option<String>,
+    /// Declaration of the flagged item
+    pub span: Option<Span>,
+    /// Severity configured for the rule in `.quorlin.toml`, if any
+    pub severity: Option<Severity>,
+}
+
+impl Default for AnalysisResult {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AnalysisResult {
     pub fn new() -> Self {
         Self {
             type_errors: Vec::new(),
+            type_error_spans: Vec::new(),
             security_issues: Vec::new(),
             gas_estimates: Vec::new(),
             lint_warnings: Vec::new(),
@@ -112,18 +151,44 @@ pub struct Analyzer {
     security_analyzer: security::SecurityAnalyzer,
     gas_estimator: gas::GasEstimator,
     linter: lints::Linter,
+    security_enabled: bool,
+    gas_enabled: bool,
+    lints_enabled: bool,
 }
 
 impl Analyzer {
     pub fn new() -> Self {
+        Self::with_config(AnalyzerConfig::default())
+    }
+    
+    /// Creates an analyzer whose lint and security rules follow `config`
+    pub fn with_config(config: AnalyzerConfig) -> Self {
         Self {
             type_checker: typeck::TypeChecker::new(),
-            security_analyzer: security::SecurityAnalyzer::new(),
+            security_analyzer: security::SecurityAnalyzer::with_config(config.clone()),
             gas_estimator: gas::GasEstimator::new(),
-            linter: lints::Linter::new(),
+            linter: lints::Linter::with_config(config),
+            security_enabled: true,
+            gas_enabled: true,
+            lints_enabled: true,
         }
     }
     
+    /// Enables or disables the security pass of `analyze`
+    pub fn set_security_enabled(&mut self, enabled: bool) {
+        self.security_enabled = enabled;
+    }
+    
+    /// Enables or disables the gas estimation pass of `analyze`
+    pub fn set_gas_enabled(&mut self, enabled: bool) {
+        self.gas_enabled = enabled;
+    }
+    
+    /// Enables or disables the lint pass of `analyze`
+    pub fn set_lints_enabled(&mut self, enabled: bool) {
+        self.lints_enabled = enabled;
+    }
+    
     /// Runs all analysis passes on a module
     pub fn analyze(&mut self, module: &Module) -> Result<AnalysisResult, AnalyzerError> {
         let mut result = AnalysisResult::new();
@@ -131,16 +196,23 @@ impl Analyzer {
         // Type checking
         if let Err(errors) = self.type_checker.check(module) {
             result.type_errors = errors;
+            result.type_error_spans = self.type_checker.error_spans().to_vec();
         }
         
         // Security analysis
-        result.security_issues = self.security_analyzer.analyze(module);
+        if self.security_enabled {
+            result.security_issues = self.security_analyzer.analyze(module);
+        }
         
         // Gas estimation
-        result.gas_estimates = self.gas_estimator.estimate(module);
+        if self.gas_enabled {
+            result.gas_estimates = self.gas_estimator.estimate(module);
+        }
         
         // Linting
-        result.lint_warnings = self.linter.lint(module);
+        if self.lints_enabled {
+            result.lint_warnings = self.linter.lint(module);
+        }
         
         Ok(result)
     }
@@ -3,19 +3,61 @@
 //! Code quality and style checks
 
 use quorlin_parser::ast::*;
-use crate::LintWarning;
+use quorlin_parser::visit::{self, Visitor};
+use crate::config::RuleSetting;
+use crate::gas::GasEstimator;
+use crate::{AnalyzerConfig, LintWarning};
+use std::collections::HashSet;
+
+/// Every lint rule name, as configured under `[lints]` in `.quorlin.toml`
+pub const LINT_RULES: &[&str] = &[
+    "duplicate-require",
+    "high-complexity",
+    "long-function",
+    "magic-number",
+    "max-functions",
+    "max-total-gas",
+    "missing-docstring",
+    "missing-zero-address-check",
+    "naming-convention",
+    "require-ordering",
+    "unguarded-block-subtraction",
+    "unused-import",
+    "unused-variable",
+];
 
 pub struct Linter {
     warnings: Vec<LintWarning>,
+    config: AnalyzerConfig,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Linter {
     pub fn new() -> Self {
+        Self::with_config(AnalyzerConfig::default())
+    }
+    
+    /// Creates a linter that skips rules disabled in `config`
+    pub fn with_config(config: AnalyzerConfig) -> Self {
         Self {
             warnings: Vec::new(),
+            config,
         }
     }
     
+    /// Creates a linter whose magic-number rule accepts exactly `allowed`
+    pub fn with_allowed_numbers(allowed: impl IntoIterator<Item = u64>) -> Self {
+        Self::with_config(AnalyzerConfig {
+            magic_number_allowlist: Some(allowed.into_iter().collect()),
+            ..AnalyzerConfig::default()
+        })
+    }
+    
     pub fn lint(&mut self, module: &Module) -> Vec<LintWarning> {
         self.warnings.clear();
         
@@ -23,13 +65,48 @@ impl Linter {
             self.lint_item(item);
         }
         
+        self.check_unused_imports(module);
+        
+        let config = &self.config;
+        self.warnings.retain_mut(|warning| match config.lint_setting(&warning.rule) {
+            Some(RuleSetting::Disabled) => false,
+            Some(RuleSetting::Severity(severity)) => {
+                warning.severity = Some(*severity);
+                true
+            }
+            Some(RuleSetting::Enabled) | None => true,
+        });
         self.warnings.clone()
     }
     
+    fn check_unused_imports(&mut self, module: &Module) {
+        let mut collector = NameCollector::default();
+        collector.visit_module(module);
+        
+        // Imports carry no aliases yet, so the imported name is the local name
+        for item in &module.items {
+            if let Item::Import(import) = item {
+                for name in &import.items {
+                    if !collector.names.contains(name.as_str()) {
+                        self.warnings.push(LintWarning {
+                            rule: "unused-import".to_string(),
+                            message: format!(
+                                "'{}' is imported from '{}' but never used",
+                                name, import.source_path()
+                            ),
+                            location: Some(import.source_path()),
+                            span: None,
+                            severity: None,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    
     fn lint_item(&mut self, item: &Item) {
-        match item {
-            Item::Contract(contract) => self.lint_contract(contract),
-            _ => {}
+        if let Item::Contract(contract) = item {
+            self.lint_contract(contract);
         }
     }
     
@@ -43,6 +120,8 @@ impl Linter {
                     contract.name
                 ),
                 location: Some(contract.name.clone()),
+                span: Some(contract.span),
+                severity: None,
             });
         }
         
@@ -59,6 +138,57 @@ impl Linter {
                 self.lint_state_variable(var);
             }
         }
+        
+        // Check the contract against the configured size limits
+        self.check_contract_limits(contract);
+    }
+    
+    fn check_contract_limits(&mut self, contract: &ContractDecl) {
+        let functions: Vec<&Function> = contract
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ContractMember::Function(func) => Some(func),
+                _ => None,
+            })
+            .collect();
+        
+        if let Some(max) = self.config.limits.max_functions {
+            if functions.len() > max {
+                self.warnings.push(LintWarning {
+                    rule: "max-functions".to_string(),
+                    message: format!(
+                        "Contract '{}' has {} functions, more than the configured maximum of {}. \
+                         Consider splitting it into smaller contracts.",
+                        contract.name, functions.len(), max
+                    ),
+                    location: Some(contract.name.clone()),
+                    span: Some(contract.span),
+                    severity: None,
+                });
+            }
+        }
+        
+        if let Some(max) = self.config.limits.max_total_gas {
+            let estimator = GasEstimator::new();
+            let total: u64 = functions
+                .iter()
+                .map(|func| estimator.estimate_function(func).estimated_gas)
+                .sum();
+            if total > max {
+                self.warnings.push(LintWarning {
+                    rule: "max-total-gas".to_string(),
+                    message: format!(
+                        "Contract '{}' has an estimated total of ~{} gas across its functions, \
+                         more than the configured maximum of {}.",
+                        contract.name, total, max
+                    ),
+                    location: Some(contract.name.clone()),
+                    span: Some(contract.span),
+                    severity: None,
+                });
+            }
+        }
     }
     
     fn lint_function(&mut self, func: &Function, contract: Option<&ContractDecl>) {
@@ -71,6 +201,8 @@ impl Linter {
                     func.name
                 ),
                 location: Some(func.name.clone()),
+                span: Some(func.span),
+                severity: None,
             });
         }
         
@@ -83,6 +215,8 @@ impl Linter {
                     func.name
                 ),
                 location: Some(func.name.clone()),
+                span: Some(func.span),
+                severity: None,
             });
         }
         
@@ -96,6 +230,8 @@ impl Linter {
                     func.name, complexity
                 ),
                 location: Some(func.name.clone()),
+                span: Some(func.span),
+                severity: None,
             });
         }
         
@@ -108,14 +244,28 @@ impl Linter {
                     func.name, func.body.len()
                 ),
                 location: Some(func.name.clone()),
+                span: Some(func.span),
+                severity: None,
             });
         }
         
         // Check for magic numbers
-        self.check_magic_numbers(&func.body, &func.name);
+        self.check_magic_numbers(&func.body, func);
+        
+        // Check ordering of leading require guards
+        self.check_require_ordering(func);
+        
+        // Check for the same require repeated back to back
+        self.check_duplicate_requires(&func.body, func);
         
         // Check for unused variables
         self.check_unused_variables(func);
+        
+        // Check block.timestamp / block.number subtractions for ordering guards
+        self.check_block_subtractions(func);
+        
+        // Check address parameters that are stored or paid without a zero check
+        self.check_zero_address_guards(func);
     }
     
     fn lint_state_variable(&mut self, var: &StateVar) {
@@ -128,6 +278,8 @@ impl Linter {
                     var.name, var.name
                 ),
                 location: Some(var.name.clone()),
+                span: Some(var.span),
+                severity: None,
             });
         }
     }
@@ -148,13 +300,128 @@ impl Linter {
         complexity
     }
     
-    fn check_magic_numbers(&mut self, stmts: &[Stmt], func_name: &str) {
+    fn check_require_ordering(&mut self, func: &Function) {
+        // Cheap checks should run first so the common revert path fails early
+        let estimator = GasEstimator::new();
+        let mut most_expensive: Option<(usize, u64)> = None;
+        
+        let guards = func.body.iter().map_while(|stmt| match stmt {
+            Stmt::Require(require) => Some(&require.condition),
+            _ => None,
+        });
+        
+        for (position, condition) in guards.enumerate() {
+            let cost = estimator.estimate_expression(condition);
+            
+            if let Some((earlier, earlier_cost)) = most_expensive {
+                if self.is_cheap_check(condition) && cost < earlier_cost {
+                    self.warnings.push(LintWarning {
+                        rule: "require-ordering".to_string(),
+                        message: format!(
+                            "require #{} in function '{}' (~{} gas) is cheaper than require #{} (~{} gas). \
+                             Consider moving cheap checks first.",
+                            position + 1, func.name, cost, earlier + 1, earlier_cost
+                        ),
+                        location: Some(func.name.clone()),
+                        span: Some(func.span),
+                        severity: None,
+                    });
+                    return;
+                }
+            }
+            
+            let is_new_max = most_expensive.is_none_or(|(_, max)| cost > max);
+            if self.is_expensive_check(condition) && is_new_max {
+                most_expensive = Some((position, cost));
+            }
+        }
+    }
+    
+    fn check_duplicate_requires(&mut self, stmts: &[Stmt], func: &Function) {
+        for pair in stmts.windows(2) {
+            if let [Stmt::Require(first), Stmt::Require(second)] = pair {
+                if first.condition == second.condition {
+                    self.warnings.push(LintWarning {
+                        rule: "duplicate-require".to_string(),
+                        message: format!(
+                            "Function '{}' repeats the same require condition twice in a row. \
+                             The second check is redundant and wastes gas.",
+                            func.name
+                        ),
+                        location: Some(func.name.clone()),
+                        span: Some(func.span),
+                        severity: None,
+                    });
+                }
+            }
+        }
+        
+        for stmt in stmts {
+            match stmt {
+                Stmt::If(if_stmt) => {
+                    self.check_duplicate_requires(&if_stmt.then_branch, func);
+                    for (_, body) in &if_stmt.elif_branches {
+                        self.check_duplicate_requires(body, func);
+                    }
+                    if let Some(else_body) = &if_stmt.else_branch {
+                        self.check_duplicate_requires(else_body, func);
+                    }
+                }
+                Stmt::While(while_stmt) => {
+                    self.check_duplicate_requires(&while_stmt.body, func);
+                    if let Some(else_body) = &while_stmt.else_branch {
+                        self.check_duplicate_requires(else_body, func);
+                    }
+                }
+                Stmt::For(for_stmt) => {
+                    self.check_duplicate_requires(&for_stmt.body, func);
+                    if let Some(else_body) = &for_stmt.else_branch {
+                        self.check_duplicate_requires(else_body, func);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// Calls (including hashing) and accesses on other contracts
+    fn is_expensive_check(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Call(_, _) => true,
+            Expr::Attribute(object, _) => {
+                !matches!(&**object, Expr::Ident(name) if name == "self" || name == "msg" || name == "block")
+                    || self.is_expensive_check(object)
+            }
+            Expr::BinOp(left, _, right) | Expr::Index(left, right) => {
+                self.is_expensive_check(left) || self.is_expensive_check(right)
+            }
+            Expr::UnaryOp(_, operand) => self.is_expensive_check(operand),
+            Expr::IfExp { test, body, orelse } => {
+                self.is_expensive_check(test) || self.is_expensive_check(body) || self.is_expensive_check(orelse)
+            }
+            Expr::List(items) | Expr::Tuple(items) => items.iter().any(|item| self.is_expensive_check(item)),
+            _ => false,
+        }
+    }
+    
+    /// Comparisons over locals and literals only (no storage, calls or attribute access)
+    fn is_cheap_check(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Ident(name) => name != "self",
+            Expr::IntLiteral(_) | Expr::BoolLiteral(_) | Expr::HexLiteral(_) | Expr::NoneLiteral => true,
+            Expr::BinOp(left, _, right) => self.is_cheap_check(left) && self.is_cheap_check(right),
+            Expr::UnaryOp(_, operand) => self.is_cheap_check(operand),
+            _ => false,
+        }
+    }
+    
+    fn check_magic_numbers(&mut self, stmts: &[Stmt], func: &Function) {
         for stmt in stmts {
-            self.check_stmt_for_magic_numbers(stmt, func_name);
+            self.check_stmt_for_magic_numbers(stmt, func);
         }
     }
     
-    fn check_stmt_for_magic_numbers(&mut self, stmt: &Stmt, func_name: &str) {
+    fn check_stmt_for_magic_numbers(&mut self, stmt: &Stmt, func: &Function) {
         match stmt {
             Stmt::Assign(assign) => {
                 if let Some(number) = self.find_magic_number(&assign.value) {
@@ -162,9 +429,11 @@ impl Linter {
                         rule: "magic-number".to_string(),
                         message: format!(
                             "Magic number {} in function '{}'. Consider using a named constant.",
-                            number, func_name
+                            number, func.name
                         ),
-                        location: Some(func_name.to_string()),
+                        location: Some(func.name.clone()),
+                        span: Some(func.span),
+                        severity: None,
                     });
                 }
             }
@@ -175,33 +444,35 @@ impl Linter {
                         rule: "magic-number".to_string(),
                         message: format!(
                             "Magic number {} in function '{}'. Consider using a named constant.",
-                            number, func_name
+                            number, func.name
                         ),
-                        location: Some(func_name.to_string()),
+                        location: Some(func.name.clone()),
+                        span: Some(func.span),
+                        severity: None,
                     });
                 }
             }
             
             Stmt::If(if_stmt) => {
                 for s in &if_stmt.then_branch {
-                    self.check_stmt_for_magic_numbers(s, func_name);
+                    self.check_stmt_for_magic_numbers(s, func);
                 }
                 if let Some(else_stmts) = &if_stmt.else_branch {
                     for s in else_stmts {
-                        self.check_stmt_for_magic_numbers(s, func_name);
+                        self.check_stmt_for_magic_numbers(s, func);
                     }
                 }
             }
             
             Stmt::While(while_stmt) => {
-                for s in &while_stmt.body {
-                    self.check_stmt_for_magic_numbers(s, func_name);
+                for s in while_stmt.body.iter().chain(while_stmt.else_branch.iter().flatten()) {
+                    self.check_stmt_for_magic_numbers(s, func);
                 }
             }
             
             Stmt::For(for_stmt) => {
-                for s in &for_stmt.body {
-                    self.check_stmt_for_magic_numbers(s, func_name);
+                for s in for_stmt.body.iter().chain(for_stmt.else_branch.iter().flatten()) {
+                    self.check_stmt_for_magic_numbers(s, func);
                 }
             }
             
@@ -213,7 +484,12 @@ impl Linter {
         match expr {
             Expr::IntLiteral(n) => {
                 // Ignore common constants
-                if n == "0" || n == "1" || n == "2" {
+                let allowed = match (&self.config.magic_number_allowlist, n.parse::<u64>()) {
+                    (Some(allowlist), Ok(value)) => allowlist.contains(&value),
+                    (Some(_), Err(_)) => false,
+                    (None, _) => n == "0" || n == "1" || n == "2",
+                };
+                if allowed {
                     None
                 } else {
                     Some(n.clone())
@@ -246,99 +522,585 @@ impl Linter {
                         param.name, func.name
                     ),
                     location: Some(func.name.clone()),
+                    span: Some(func.span),
+                    severity: None,
                 });
             }
         }
     }
     
+    fn check_block_subtractions(&mut self, func: &Function) {
+        // `deadline - now` reverts under checked arithmetic once the deadline has passed
+        let mut finder = BlockSubtractionFinder::default();
+        for stmt in &func.body {
+            finder.visit_stmt(stmt);
+        }
+        
+        for (left, right) in finder.unguarded {
+            self.warnings.push(LintWarning {
+                rule: "unguarded-block-subtraction".to_string(),
+                message: format!(
+                    "Subtraction '{} - {}' in function '{}' reverts if the right side is larger. \
+                     Consider a prior require({} >= {}).",
+                    describe_operand(left), describe_operand(right), func.name,
+                    describe_operand(left), describe_operand(right)
+                ),
+                location: Some(func.name.clone()),
+                span: Some(func.span),
+                severity: None,
+            });
+        }
+    }
+    
+    fn check_zero_address_guards(&mut self, func: &Function) {
+        let mut finder = ZeroAddressFinder {
+            params: func
+                .params
+                .iter()
+                .filter(|param| matches!(&param.type_annotation, Type::Simple(ty) if ty == "address"))
+                .map(|param| param.name.as_str())
+                .collect(),
+            ..Default::default()
+        };
+        if finder.params.is_empty() {
+            return;
+        }
+        for stmt in &func.body {
+            finder.visit_stmt(stmt);
+        }
+        
+        for param in finder.unguarded {
+            self.warnings.push(LintWarning {
+                rule: "missing-zero-address-check".to_string(),
+                message: format!(
+                    "Address parameter '{}' in function '{}' is stored or sent funds without a zero-address check. \
+                     Consider a prior require({} != address(0)).",
+                    param, func.name, param
+                ),
+                location: Some(func.name.clone()),
+                span: Some(func.span),
+                severity: None,
+            });
+        }
+    }
+    
     fn is_variable_used(&self, stmts: &[Stmt], var_name: &str) -> bool {
+        let mut collector = NameCollector::default();
         for stmt in stmts {
-            if self.stmt_uses_variable(stmt, var_name) {
-                return true;
-            }
+            collector.visit_stmt(stmt);
+        }
+        collector.names.contains(var_name)
+    }
+    
+    // Naming convention helpers
+    
+    fn is_pascal_case(&self, s: &str) -> bool {
+        if s.is_empty() {
+            return false;
+        }
+        
+        let first_char = s.chars().next().unwrap();
+        first_char.is_uppercase() && !s.contains('_')
+    }
+    
+    fn is_snake_case(&self, s: &str) -> bool {
+        s.chars().all(|c| c.is_lowercase() || c.is_numeric() || c == '_')
+    }
+}
+
+/// Collects every identifier, type name, event and error referenced in a module
+#[derive(Default)]
+struct NameCollector<'ast> {
+    names: HashSet<&'ast str>,
+}
+
+impl<'ast> Visitor<'ast> for NameCollector<'ast> {
+    fn visit_item(&mut self, item: &'ast Item) {
+        if let Item::Contract(contract) = item {
+            self.names.extend(contract.bases.iter().map(String::as_str));
         }
-        false
+        visit::walk_item(self, item);
     }
     
-    fn stmt_uses_variable(&self, stmt: &Stmt, var_name: &str) -> bool {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
         match stmt {
-            Stmt::Assign(assign) => {
-                self.expr_uses_variable(&assign.value, var_name) ||
-                self.expr_uses_variable(&assign.target, var_name)
+            Stmt::Emit(emit) => {
+                self.names.insert(&emit.event);
             }
-            
-            Stmt::Return(Some(expr)) | Stmt::Expr(expr) => {
-                self.expr_uses_variable(expr, var_name)
+            Stmt::Raise(raise) => {
+                self.names.insert(&raise.error);
             }
-            
-            Stmt::If(if_stmt) => {
-                self.expr_uses_variable(&if_stmt.condition, var_name) ||
-                if_stmt.then_branch.iter().any(|s| self.stmt_uses_variable(s, var_name)) ||
-                if_stmt.else_branch.as_ref().map(|stmts| {
-                    stmts.iter().any(|s| self.stmt_uses_variable(s, var_name))
-                }).unwrap_or(false)
+            Stmt::Require(require) => {
+                if let Some(error) = &require.error {
+                    self.names.insert(&error.error);
+                }
             }
-            
-            Stmt::While(while_stmt) => {
-                self.expr_uses_variable(&while_stmt.condition, var_name) ||
-                while_stmt.body.iter().any(|s| self.stmt_uses_variable(s, var_name))
+            _ => {}
+        }
+        visit::walk_stmt(self, stmt);
+    }
+    
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Expr::Ident(name) = expr {
+            self.names.insert(name);
+        }
+        visit::walk_expr(self, expr);
+    }
+    
+    fn visit_type(&mut self, ty: &'ast Type) {
+        match ty {
+            Type::Simple(name) | Type::ConstArray(_, name) => {
+                self.names.insert(name);
             }
-            
-            Stmt::For(for_stmt) => {
-                for_stmt.body.iter().any(|s| self.stmt_uses_variable(s, var_name))
+            _ => {}
+        }
+        visit::walk_type(self, ty);
+    }
+}
+
+/// Finds subtractions of `block.timestamp` or `block.number` that are not
+/// preceded by a `require` (or enclosed by an `if`) ordering the operands
+#[derive(Default)]
+struct BlockSubtractionFinder<'ast> {
+    /// Operand pairs `(larger, smaller)` ordered by an enclosing or earlier guard
+    guards: Vec<(&'ast Expr, &'ast Expr)>,
+    unguarded: Vec<(&'ast Expr, &'ast Expr)>,
+}
+
+impl<'ast> BlockSubtractionFinder<'ast> {
+    /// Record the orderings implied by `condition` evaluating to `holds`
+    fn add_guards(&mut self, condition: &'ast Expr, holds: bool) {
+        match condition {
+            Expr::BinOp(left, BinOp::And, right) if holds => {
+                self.add_guards(left, holds);
+                self.add_guards(right, holds);
             }
-            
-            _ => false,
+            Expr::BinOp(left, BinOp::Or, right) if !holds => {
+                self.add_guards(left, holds);
+                self.add_guards(right, holds);
+            }
+            Expr::UnaryOp(UnaryOp::Not, operand) => self.add_guards(operand, !holds),
+            Expr::BinOp(left, op @ (BinOp::Lt | BinOp::LtEq | BinOp::Gt | BinOp::GtEq), right) => {
+                if matches!(op, BinOp::Gt | BinOp::GtEq) == holds {
+                    self.guards.push((left, right));
+                } else {
+                    self.guards.push((right, left));
+                }
+            }
+            _ => {}
         }
     }
     
-    fn expr_uses_variable(&self, expr: &Expr, var_name: &str) -> bool {
-        match expr {
-            Expr::Ident(name) => name == var_name,
-            
-            Expr::BinOp(left, _, right) => {
-                self.expr_uses_variable(left, var_name) ||
-                self.expr_uses_variable(right, var_name)
+    fn visit_body(&mut self, body: &'ast [Stmt]) {
+        for stmt in body {
+            self.visit_stmt(stmt);
+        }
+    }
+    
+    fn is_guarded(&self, left: &Expr, right: &Expr) -> bool {
+        self.guards.iter().any(|(larger, smaller)| *larger == left && *smaller == right)
+    }
+}
+
+impl<'ast> Visitor<'ast> for BlockSubtractionFinder<'ast> {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        match stmt {
+            Stmt::Require(require) => {
+                visit::walk_stmt(self, stmt);
+                self.add_guards(&require.condition, true);
             }
-            
-            Expr::UnaryOp(_, operand) => {
-                self.expr_uses_variable(operand, var_name)
+            Stmt::If(if_stmt) => {
+                // Each branch runs only when its condition holds and every
+                // earlier one failed
+                let outer = self.guards.len();
+                let branches = std::iter::once((&if_stmt.condition, &if_stmt.then_branch))
+                    .chain(if_stmt.elif_branches.iter().map(|(condition, body)| (condition, body)));
+                for (condition, body) in branches {
+                    self.visit_expr(condition);
+                    let failed = self.guards.len();
+                    self.add_guards(condition, true);
+                    self.visit_body(body);
+                    self.guards.truncate(failed);
+                    self.add_guards(condition, false);
+                }
+                if let Some(else_body) = &if_stmt.else_branch {
+                    self.visit_body(else_body);
+                }
+                self.guards.truncate(outer);
             }
-            
-            Expr::Call(function, args) => {
-                self.expr_uses_variable(function, var_name) ||
-                args.iter().any(|arg| self.expr_uses_variable(arg, var_name))
+            _ => visit::walk_stmt(self, stmt),
+        }
+    }
+    
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Expr::BinOp(left, BinOp::Sub, right) = expr {
+            if is_block_counter(right) && !self.is_guarded(left, right) {
+                self.unguarded.push((left, right));
             }
-            
-            Expr::Index(object, index) => {
-                self.expr_uses_variable(object, var_name) ||
-                self.expr_uses_variable(index, var_name)
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Finds `address` parameters that are written to state or used as a
+/// transfer recipient before a `require`, an enclosing `if` or an earlier
+/// `if x == address(0):` that always exits rules out the zero address
+#[derive(Default)]
+struct ZeroAddressFinder<'ast> {
+    /// The function's `address` parameters
+    params: HashSet<&'ast str>,
+    /// Parameters compared against `address(0)` by an enclosing or earlier guard
+    guarded: Vec<&'ast str>,
+    /// Offending parameters in order of first use
+    unguarded: Vec<&'ast str>,
+}
+
+impl<'ast> ZeroAddressFinder<'ast> {
+    /// Record the parameters `condition` rules out as the zero address when
+    /// it evaluates to `holds`
+    fn add_guards(&mut self, condition: &'ast Expr, holds: bool) {
+        match condition {
+            Expr::BinOp(left, BinOp::And, right) if holds => {
+                self.add_guards(left, holds);
+                self.add_guards(right, holds);
             }
-            
-            Expr::Attribute(object, _) => {
-                self.expr_uses_variable(object, var_name)
+            Expr::BinOp(left, BinOp::Or, right) if !holds => {
+                self.add_guards(left, holds);
+                self.add_guards(right, holds);
             }
-            
-            Expr::List(items) | Expr::Tuple(items) => {
-                items.iter().any(|item| self.expr_uses_variable(item, var_name))
+            Expr::UnaryOp(UnaryOp::Not, operand) => self.add_guards(operand, !holds),
+            Expr::BinOp(left, op @ (BinOp::NotEq | BinOp::Eq), right) if holds == matches!(op, BinOp::NotEq) => {
+                let checked = match (&**left, &**right) {
+                    (Expr::Ident(name), other) | (other, Expr::Ident(name)) if is_zero_address(other) => name,
+                    _ => return,
+                };
+                self.guarded.push(checked);
             }
-            
-            _ => false,
+            _ => {}
         }
     }
     
-    // Naming convention helpers
+    /// Visit a block whose guards do not outlive it
+    fn visit_body(&mut self, body: &'ast [Stmt]) {
+        let outer = self.guarded.len();
+        for stmt in body {
+            self.visit_stmt(stmt);
+        }
+        self.guarded.truncate(outer);
+    }
     
-    fn is_pascal_case(&self, s: &str) -> bool {
-        if s.is_empty() {
-            return false;
+    fn report(&mut self, expr: &'ast Expr) {
+        if let Expr::Ident(name) = expr {
+            let name = name.as_str();
+            if self.params.contains(name) && !self.guarded.contains(&name) && !self.unguarded.contains(&name) {
+                self.unguarded.push(name);
+            }
         }
+    }
+}
+
+impl<'ast> Visitor<'ast> for ZeroAddressFinder<'ast> {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        match stmt {
+            Stmt::Require(require) => {
+                visit::walk_stmt(self, stmt);
+                self.add_guards(&require.condition, true);
+            }
+            Stmt::If(if_stmt) => {
+                // Each branch runs only when its condition holds and every
+                // earlier one failed
+                let outer = self.guarded.len();
+                let branches = std::iter::once((&if_stmt.condition, &if_stmt.then_branch))
+                    .chain(if_stmt.elif_branches.iter().map(|(condition, body)| (condition, body)));
+                for (condition, body) in branches.clone() {
+                    self.visit_expr(condition);
+                    let failed = self.guarded.len();
+                    self.add_guards(condition, true);
+                    self.visit_body(body);
+                    self.guarded.truncate(failed);
+                    self.add_guards(condition, false);
+                }
+                if let Some(else_body) = &if_stmt.else_branch {
+                    self.visit_body(else_body);
+                }
+                self.guarded.truncate(outer);
+                // Past `if to == address(0): raise ...` every condition failed
+                if if_stmt.else_branch.is_none() && branches.clone().all(|(_, body)| always_exits(body)) {
+                    for (condition, _) in branches {
+                        self.add_guards(condition, false);
+                    }
+                }
+            }
+            Stmt::For(_) | Stmt::While(_) => {
+                let outer = self.guarded.len();
+                visit::walk_stmt(self, stmt);
+                self.guarded.truncate(outer);
+            }
+            Stmt::Assign(assign) => {
+                if assign.target.assigned_state_var().is_some() {
+                    self.report(&assign.value);
+                }
+                visit::walk_stmt(self, stmt);
+            }
+            _ => visit::walk_stmt(self, stmt),
+        }
+    }
+    
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Expr::Call(callee, _) = expr {
+            if let Expr::Attribute(recipient, method) = &**callee {
+                if method == "send" || method == "transfer" {
+                    self.report(recipient);
+                }
+            }
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Whether running `body` always leaves the function: it ends in `raise`,
+/// `revert` or `return`, or in an `if` whose branches, `else` included, all do
+fn always_exits(body: &[Stmt]) -> bool {
+    match body.last() {
+        Some(Stmt::Raise(_) | Stmt::Revert(_) | Stmt::Return(_)) => true,
+        Some(Stmt::If(if_stmt)) => {
+            always_exits(&if_stmt.then_branch)
+                && if_stmt.elif_branches.iter().all(|(_, body)| always_exits(body))
+                && if_stmt.else_branch.as_deref().is_some_and(always_exits)
+        }
+        _ => false,
+    }
+}
+
+/// `address(0)`
+fn is_zero_address(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Call(callee, args)
+            if matches!(&**callee, Expr::Ident(name) if name == "address")
+                && matches!(args.as_slice(), [Expr::IntLiteral(n)] if n == "0")
+    )
+}
+
+/// `block.timestamp` or `block.number`
+fn is_block_counter(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Attribute(object, member)
+            if matches!(&**object, Expr::Ident(name) if name == "block")
+                && (member == "timestamp" || member == "number")
+    )
+}
+
+/// Short source-like rendering of a subtraction operand for lint messages
+fn describe_operand(expr: &Expr) -> String {
+    match expr {
+        Expr::Ident(name) => name.clone(),
+        Expr::IntLiteral(n) => n.clone(),
+        Expr::Attribute(object, member) => format!("{}.{}", describe_operand(object), member),
+        _ => "...".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quorlin_lexer::Lexer;
+    
+    fn lint(source: &str) -> Vec<LintWarning> {
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        Linter::new().lint(&module)
+    }
+    
+    #[test]
+    fn test_unused_import_is_reported() {
+        let source = r#"
+from std.math import safe_add, safe_sub
+
+contract Counter:
+    _count: uint256
+    
+    fn bump(n: uint256):
+        self._count = safe_add(self._count, n)
+"#;
+        let unused: Vec<_> = lint(source)
+            .into_iter()
+            .filter(|w| w.rule == "unused-import")
+            .collect();
+        assert_eq!(unused.len(), 1);
+        assert!(unused[0].message.contains("'safe_sub'"));
+    }
+    
+    #[test]
+    fn test_expensive_require_before_cheap_one() {
+        let source = r#"
+contract Gate:
+    fn enter(a: uint256):
+        require(external_check())
+        require(a > 0)
+"#;
+        let warnings = lint(source);
+        let ordering: Vec<_> = warnings.iter().filter(|w| w.rule == "require-ordering").collect();
+        assert_eq!(ordering.len(), 1);
+        assert!(ordering[0].message.contains("require #2"));
         
-        let first_char = s.chars().next().unwrap();
-        first_char.is_uppercase() && !s.contains('_')
+        let reordered = r#"
+contract Gate:
+    fn enter(a: uint256):
+        require(a > 0)
+        require(external_check())
+"#;
+        assert!(lint(reordered).iter().all(|w| w.rule != "require-ordering"));
     }
     
-    fn is_snake_case(&self, s: &str) -> bool {
-        s.chars().all(|c| c.is_lowercase() || c.is_numeric() || c == '_')
+    #[test]
+    fn test_block_timestamp_subtraction_without_guard() {
+        let source = r#"
+contract Vesting:
+    _end: uint256
+    _remaining: uint256
+    
+    fn poke():
+        self._remaining = self._end - block.timestamp
+"#;
+        let warnings = lint(source);
+        let unguarded: Vec<_> = warnings.iter().filter(|w| w.rule == "unguarded-block-subtraction").collect();
+        assert_eq!(unguarded.len(), 1);
+        assert!(unguarded[0].message.contains("self._end - block.timestamp"), "{}", unguarded[0].message);
+        
+        let guarded = r#"
+contract Vesting:
+    _end: uint256
+    _remaining: uint256
+    
+    fn poke():
+        require(block.timestamp <= self._end)
+        self._remaining = self._end - block.timestamp
+"#;
+        assert!(lint(guarded).iter().all(|w| w.rule != "unguarded-block-subtraction"));
+        
+        let else_branch = r#"
+contract Vesting:
+    _end: uint256
+    _remaining: uint256
+    
+    fn poke():
+        if self._end < block.timestamp:
+            self._remaining = 0
+        else:
+            self._remaining = self._end - block.timestamp
+"#;
+        assert!(lint(else_branch).iter().all(|w| w.rule != "unguarded-block-subtraction"));
+    }
+    
+    #[test]
+    fn test_block_subtraction_guard_must_order_operands() {
+        let reversed = r#"
+contract Vesting:
+    _end: uint256
+    _remaining: uint256
+    
+    fn poke():
+        require(self._end < block.timestamp)
+        self._remaining = self._end - block.timestamp
+"#;
+        assert!(lint(reversed).iter().any(|w| w.rule == "unguarded-block-subtraction"));
+        
+        // Only a block counter on the right of the subtraction is flagged
+        let elapsed = r#"
+contract Vesting:
+    _start: uint256
+    _elapsed: uint256
+    
+    fn poke():
+        self._elapsed = block.timestamp - self._start
+"#;
+        assert!(lint(elapsed).iter().all(|w| w.rule != "unguarded-block-subtraction"));
+    }
+    
+    #[test]
+    fn test_duplicate_require_is_reported() {
+        let source = r#"
+contract Owned:
+    _owner: address
+    
+    fn guard():
+        require(msg.sender == self._owner)
+        require(msg.sender == self._owner)
+"#;
+        let duplicates: Vec<_> = lint(source).into_iter().filter(|w| w.rule == "duplicate-require").collect();
+        assert_eq!(duplicates.len(), 1);
+        assert!(duplicates[0].message.contains("'guard'"));
+    }
+    
+    #[test]
+    fn test_unchecked_address_parameter_written_to_state() {
+        let source = r#"
+contract Owned:
+    _owner: address
+    
+    fn set_owner(new_owner: address):
+        self._owner = new_owner
+"#;
+        let warnings: Vec<_> = lint(source).into_iter().filter(|w| w.rule == "missing-zero-address-check").collect();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("'new_owner'"), "{}", warnings[0].message);
+        
+        let checked = r#"
+contract Owned:
+    _owner: address
+    
+    fn set_owner(new_owner: address):
+        require(new_owner != address(0), "Zero address")
+        self._owner = new_owner
+"#;
+        assert!(lint(checked).iter().all(|w| w.rule != "missing-zero-address-check"));
+        
+        let field = r#"
+contract Owned:
+    _roles: Roles
+    
+    fn set_admin(admin: address):
+        self._roles.admin = admin
+"#;
+        assert!(lint(field).iter().any(|w| w.rule == "missing-zero-address-check"));
+        
+        let early_exit = r#"
+error ZeroAddress()
+
+contract Owned:
+    _owner: address
+    
+    fn set_owner(new_owner: address):
+        if new_owner == address(0):
+            raise ZeroAddress()
+        self._owner = new_owner
+"#;
+        assert!(lint(early_exit).iter().all(|w| w.rule != "missing-zero-address-check"));
+        
+        let else_scoped = r#"
+contract Owned:
+    _owner: address
+    
+    fn set_owner(new_owner: address, checked: bool):
+        if checked:
+            pass
+        else:
+            require(new_owner != address(0), "Zero address")
+        self._owner = new_owner
+"#;
+        assert!(lint(else_scoped).iter().any(|w| w.rule == "missing-zero-address-check"));
+        
+        let loop_scoped = r#"
+contract Owned:
+    _owner: address
+    
+    fn set_owner(new_owner: address, rounds: uint256):
+        for i in range(rounds):
+            require(new_owner != address(0), "Zero address")
+        self._owner = new_owner
+"#;
+        assert!(lint(loop_scoped).iter().any(|w| w.rule == "missing-zero-address-check"));
     }
 }
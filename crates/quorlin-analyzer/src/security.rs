@@ -3,16 +3,31 @@
 //! Detects common security vulnerabilities in smart contracts
 
 use quorlin_parser::ast::*;
-use crate::{SecurityIssue, SecurityCategory, Severity};
+use quorlin_parser::visit::{self, Visitor};
+use crate::config::RuleSetting;
+use crate::{AnalyzerConfig, SecurityIssue, SecurityCategory, Severity};
 
 pub struct SecurityAnalyzer {
     issues: Vec<SecurityIssue>,
+    config: AnalyzerConfig,
+}
+
+impl Default for SecurityAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SecurityAnalyzer {
     pub fn new() -> Self {
+        Self::with_config(AnalyzerConfig::default())
+    }
+    
+    /// Creates an analyzer that skips or re-grades categories per `config`
+    pub fn with_config(config: AnalyzerConfig) -> Self {
         Self {
             issues: Vec::new(),
+            config,
         }
     }
     
@@ -25,6 +40,15 @@ impl SecurityAnalyzer {
             }
         }
         
+        let config = &self.config;
+        self.issues.retain_mut(|issue| match config.security_setting(&issue.category) {
+            Some(RuleSetting::Disabled) => false,
+            Some(RuleSetting::Severity(severity)) => {
+                issue.severity = *severity;
+                true
+            }
+            Some(RuleSetting::Enabled) | None => true,
+        });
         self.issues.clone()
     }
     
@@ -83,6 +107,7 @@ impl SecurityAnalyzer {
                     func.name
                 ),
                 location: Some(func.name.clone()),
+                span: Some(func.span),
             });
         }
     }
@@ -90,47 +115,46 @@ impl SecurityAnalyzer {
     /// Checks for unsafe integer operations
     fn check_integer_operations(&mut self, func: &Function) {
         for stmt in &func.body {
-            self.check_stmt_for_unsafe_math(stmt, &func.name);
+            self.check_stmt_for_unsafe_math(stmt, func);
         }
     }
     
-    fn check_stmt_for_unsafe_math(&mut self, stmt: &Stmt, func_name: &str) {
+    fn check_stmt_for_unsafe_math(&mut self, stmt: &Stmt, func: &Function) {
         match stmt {
-            Stmt::Assign(assign) => {
-                if self.has_unsafe_arithmetic(&assign.value) {
-                    self.issues.push(SecurityIssue {
-                        severity: Severity::Medium,
-                        category: SecurityCategory::IntegerOverflow,
-                        message: format!(
-                            "Unsafe arithmetic operation in function '{}'. \
-                             Use safe_add, safe_sub, safe_mul, safe_div from std.math",
-                            func_name
-                        ),
-                        location: Some(func_name.to_string()),
-                    });
-                }
+            Stmt::Assign(assign) if self.has_unsafe_arithmetic(&assign.value) => {
+                self.issues.push(SecurityIssue {
+                    severity: Severity::Medium,
+                    category: SecurityCategory::IntegerOverflow,
+                    message: format!(
+                        "Unsafe arithmetic operation in function '{}'. \
+                         Use safe_add, safe_sub, safe_mul, safe_div from std.math",
+                        func.name
+                    ),
+                    location: Some(func.name.clone()),
+                    span: Some(func.span),
+                });
             }
             
             Stmt::If(if_stmt) => {
                 for s in &if_stmt.then_branch {
-                    self.check_stmt_for_unsafe_math(s, func_name);
+                    self.check_stmt_for_unsafe_math(s, func);
                 }
                 if let Some(else_stmts) = &if_stmt.else_branch {
                     for s in else_stmts {
-                        self.check_stmt_for_unsafe_math(s, func_name);
+                        self.check_stmt_for_unsafe_math(s, func);
                     }
                 }
             }
             
             Stmt::While(while_stmt) => {
-                for s in &while_stmt.body {
-                    self.check_stmt_for_unsafe_math(s, func_name);
+                for s in while_stmt.body.iter().chain(while_stmt.else_branch.iter().flatten()) {
+                    self.check_stmt_for_unsafe_math(s, func);
                 }
             }
             
             Stmt::For(for_stmt) => {
-                for s in &for_stmt.body {
-                    self.check_stmt_for_unsafe_math(s, func_name);
+                for s in for_stmt.body.iter().chain(for_stmt.else_branch.iter().flatten()) {
+                    self.check_stmt_for_unsafe_math(s, func);
                 }
             }
             
@@ -142,7 +166,7 @@ impl SecurityAnalyzer {
         match expr {
             Expr::BinOp(left, op, right) => {
                 // Check if this is arithmetic without safe_ functions
-                if matches!(op, BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod) {
+                if matches!(op, BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::FloorDiv | BinOp::Mod) {
                     let uses_safe_math = self.uses_safe_math_function(expr);
                     !uses_safe_math
                 } else {
@@ -190,6 +214,7 @@ impl SecurityAnalyzer {
                             func.name
                         ),
                         location: Some(func.name.clone()),
+                        span: Some(func.span),
                     });
                 }
             }
@@ -205,7 +230,7 @@ impl SecurityAnalyzer {
         let has_access_control = func.body.iter().any(|stmt| self.has_access_control_check(stmt));
         
         // Check if function is public/external (check decorators)
-        let is_public = func.decorators.iter().any(|d| d == "external" || d == "public");
+        let is_public = func.has_decorator("external") || func.has_decorator("public");
         
         if modifies_state && is_public && !has_access_control && !func.name.starts_with('_') {
             self.issues.push(SecurityIssue {
@@ -217,26 +242,34 @@ impl SecurityAnalyzer {
                     func.name
                 ),
                 location: Some(func.name.clone()),
+                span: Some(func.span),
             });
         }
     }
     
     /// Checks for timestamp dependence
+    ///
+    /// Only comparisons that gate execution (require, if/elif, while and
+    /// ternary conditions) are reported; merely reading, storing or returning
+    /// the timestamp is not a manipulation risk.
     fn check_timestamp_dependence(&mut self, func: &Function) {
+        let mut finder = TimestampGuardFinder { analyzer: self, found: false };
         for stmt in &func.body {
-            if self.uses_timestamp(stmt) {
-                self.issues.push(SecurityIssue {
-                    severity: Severity::Low,
-                    category: SecurityCategory::TimestampDependence,
-                    message: format!(
-                        "Function '{}' depends on block.timestamp. \
-                         Be aware that miners can manipulate timestamps within bounds.",
-                        func.name
-                    ),
-                    location: Some(func.name.clone()),
-                });
-                break;
-            }
+            finder.visit_stmt(stmt);
+        }
+        
+        if finder.found {
+            self.issues.push(SecurityIssue {
+                severity: Severity::Low,
+                category: SecurityCategory::TimestampDependence,
+                message: format!(
+                    "Function '{}' uses block.timestamp as a guard condition. \
+                     Be aware that miners can manipulate timestamps within bounds.",
+                    func.name
+                ),
+                location: Some(func.name.clone()),
+                span: Some(func.span),
+            });
         }
     }
     
@@ -272,11 +305,17 @@ impl SecurityAnalyzer {
         }
     }
     
-    fn uses_timestamp(&self, stmt: &Stmt) -> bool {
-        match stmt {
-            Stmt::Assign(assign) => self.expr_uses_timestamp(&assign.value),
-            Stmt::Expr(expr) => self.expr_uses_timestamp(expr),
-            Stmt::If(if_stmt) => self.expr_uses_timestamp(&if_stmt.condition),
+    /// Whether `condition` compares a timestamp-derived value
+    fn compares_timestamp(&self, condition: &Expr) -> bool {
+        match condition {
+            Expr::BinOp(left, op, right) => match op {
+                BinOp::Eq | BinOp::NotEq | BinOp::Lt | BinOp::LtEq | BinOp::Gt | BinOp::GtEq => {
+                    self.expr_uses_timestamp(left) || self.expr_uses_timestamp(right)
+                }
+                BinOp::And | BinOp::Or => self.compares_timestamp(left) || self.compares_timestamp(right),
+                _ => false,
+            },
+            Expr::UnaryOp(UnaryOp::Not, operand) => self.compares_timestamp(operand),
             _ => false,
         }
     }
@@ -303,7 +342,79 @@ impl SecurityAnalyzer {
                 self.expr_uses_timestamp(left) || self.expr_uses_timestamp(right)
             }
             
+            Expr::UnaryOp(_, operand) => self.expr_uses_timestamp(operand),
+            
             _ => false,
         }
     }
 }
+
+/// Looks for timestamp comparisons in conditions that gate execution
+struct TimestampGuardFinder<'a> {
+    analyzer: &'a SecurityAnalyzer,
+    found: bool,
+}
+
+impl<'ast> Visitor<'ast> for TimestampGuardFinder<'_> {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        let guards_timestamp = match stmt {
+            Stmt::Require(require) => self.analyzer.compares_timestamp(&require.condition),
+            Stmt::If(if_stmt) => {
+                self.analyzer.compares_timestamp(&if_stmt.condition)
+                    || if_stmt.elif_branches.iter().any(|(cond, _)| self.analyzer.compares_timestamp(cond))
+            }
+            Stmt::While(while_stmt) => self.analyzer.compares_timestamp(&while_stmt.condition),
+            _ => false,
+        };
+        self.found |= guards_timestamp;
+        visit::walk_stmt(self, stmt);
+    }
+    
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Expr::IfExp { test, .. } = expr {
+            self.found |= self.analyzer.compares_timestamp(test);
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quorlin_lexer::Lexer;
+    
+    fn analyze(source: &str) -> Vec<SecurityIssue> {
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        SecurityAnalyzer::new().analyze(&module)
+    }
+    
+    fn timestamp_issues(source: &str) -> usize {
+        analyze(source)
+            .iter()
+            .filter(|i| matches!(i.category, SecurityCategory::TimestampDependence))
+            .count()
+    }
+    
+    #[test]
+    fn test_timestamp_guard_warns() {
+        let source = r#"
+contract Sale:
+    _deadline: uint256
+    
+    fn close():
+        require(block.timestamp > self._deadline)
+"#;
+        assert_eq!(timestamp_issues(source), 1);
+    }
+    
+    #[test]
+    fn test_timestamp_read_does_not_warn() {
+        let source = r#"
+contract Clock:
+    fn now() -> uint256:
+        return block.timestamp
+"#;
+        assert_eq!(timestamp_issues(source), 0);
+    }
+}
@@ -2,8 +2,17 @@
 //!
 //! Performs type checking on Quorlin AST
 
+use quorlin_lexer::Span;
 use quorlin_parser::ast::*;
-use std::collections::HashMap;
+use quorlin_parser::type_names::{canonical_display, canonical_name};
+use std::collections::{HashMap, HashSet};
+
+/// Built-in functions and type constructors callable without a declaration
+const BUILTIN_FUNCTIONS: &[&str] = &[
+    "require", "assert", "revert", "range", "len", "min", "max", "abs",
+    "keccak256", "sha256", "ecrecover",
+    "address", "bool", "str", "bytes",
+];
 
 pub struct TypeChecker {
     /// Type environment (variable name -> type)
@@ -15,33 +24,64 @@ pub struct TypeChecker {
     /// Current contract context
     current_contract: Option<String>,
     
+    /// Name and declared return type of the function being checked
+    current_function: Option<(String, Option<Type>)>,
+    
+    /// Imported names and declared types that may be called like functions
+    callable_names: HashSet<String>,
+    
     /// Errors collected during type checking
     errors: Vec<String>,
+    
+    /// Declaration each error was found in, by index into `errors`
+    error_spans: Vec<Option<Span>>,
 }
 
 #[derive(Debug, Clone)]
 struct FunctionSignature {
-    params: Vec<(String, Type)>,
     return_type: Option<Type>,
 }
 
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TypeChecker {
     pub fn new() -> Self {
         Self {
             type_env: HashMap::new(),
             functions: HashMap::new(),
             current_contract: None,
+            current_function: None,
+            callable_names: HashSet::new(),
             errors: Vec::new(),
+            error_spans: Vec::new(),
         }
     }
     
     pub fn check(&mut self, module: &Module) -> Result<(), Vec<String>> {
         self.errors.clear();
+        self.error_spans.clear();
         
-        // First pass: collect all function signatures
+        // First pass: collect all function signatures and callable names
         for item in &module.items {
-            if let Item::Contract(contract) = item {
-                self.collect_signatures(contract);
+            match item {
+                Item::Contract(contract) => {
+                    self.callable_names.insert(contract.name.clone());
+                    self.collect_signatures(contract);
+                }
+                Item::Import(import) => {
+                    self.callable_names.extend(import.items.iter().cloned());
+                }
+                Item::Struct(s) => {
+                    self.callable_names.insert(s.name.clone());
+                }
+                Item::Interface(i) => {
+                    self.callable_names.insert(i.name.clone());
+                }
+                _ => {}
             }
         }
         
@@ -49,6 +89,7 @@ impl TypeChecker {
         for item in &module.items {
             self.check_item(item);
         }
+        self.error_spans.resize(self.errors.len(), None);
         
         if self.errors.is_empty() {
             Ok(())
@@ -57,17 +98,17 @@ impl TypeChecker {
         }
     }
     
+    /// Declarations the errors of the last `check` were found in, in the same order
+    pub fn error_spans(&self) -> &[Option<Span>] {
+        &self.error_spans
+    }
+    
     fn collect_signatures(&mut self, contract: &ContractDecl) {
         self.current_contract = Some(contract.name.clone());
         
         for member in &contract.body {
             if let ContractMember::Function(func) = member {
-                let params = func.params.iter()
-                    .map(|p| (p.name.clone(), p.type_annotation.clone()))
-                    .collect();
-                
                 let sig = FunctionSignature {
-                    params,
                     return_type: func.return_type.clone(),
                 };
                 
@@ -78,9 +119,9 @@ impl TypeChecker {
     }
     
     fn check_item(&mut self, item: &Item) {
-        match item {
-            Item::Contract(contract) => self.check_contract(contract),
-            _ => {}, // Other items don't need checking
+        // Other items don't need checking
+        if let Item::Contract(contract) = item {
+            self.check_contract(contract);
         }
     }
     
@@ -98,6 +139,7 @@ impl TypeChecker {
         for member in &contract.body {
             if let ContractMember::Function(func) = member {
                 self.check_function(func);
+                self.error_spans.resize(self.errors.len(), Some(func.span));
             }
         }
     }
@@ -109,9 +151,11 @@ impl TypeChecker {
         }
         
         // Check function body
+        self.current_function = Some((func.name.clone(), func.return_type.clone()));
         for stmt in &func.body {
             self.check_statement(stmt);
         }
+        self.current_function = None;
         
         // Check return type
         if let Some(_return_type) = &func.return_type {
@@ -126,28 +170,31 @@ impl TypeChecker {
     
     fn check_statement(&mut self, stmt: &Stmt) {
         match stmt {
+            // Unpacking arity and element types are checked by the semantic pass
+            Stmt::Assign(assign) if matches!(assign.target, Expr::Tuple(_)) => {}
             Stmt::Assign(assign) => {
                 let target_type = self.infer_type(&assign.target);
                 let value_type = self.infer_type(&assign.value);
                 
                 if !self.types_compatible(&value_type, &target_type) {
                     self.errors.push(format!(
-                        "Type mismatch in assignment: cannot assign {:?} to {:?}",
-                        value_type, target_type
+                        "Type mismatch in assignment: cannot assign {} to {}",
+                        canonical_display(&value_type), canonical_display(&target_type)
                     ));
                 }
             }
             
             Stmt::Return(Some(expr)) => {
-                let _ = self.infer_type(expr);
+                let value_type = self.infer_type(expr);
+                self.check_return_type(expr, &value_type);
             }
             
             Stmt::If(if_stmt) => {
                 let cond_type = self.infer_type(&if_stmt.condition);
                 if !matches!(cond_type, Type::Simple(ref s) if s == "bool") {
                     self.errors.push(format!(
-                        "If condition must be boolean, got {:?}",
-                        cond_type
+                        "If condition must be boolean, got {}",
+                        canonical_display(&cond_type)
                     ));
                 }
                 
@@ -155,6 +202,12 @@ impl TypeChecker {
                     self.check_statement(stmt);
                 }
                 
+                for (_, elif_stmts) in &if_stmt.elif_branches {
+                    for stmt in elif_stmts {
+                        self.check_statement(stmt);
+                    }
+                }
+                
                 if let Some(else_stmts) = &if_stmt.else_branch {
                     for stmt in else_stmts {
                         self.check_statement(stmt);
@@ -166,18 +219,18 @@ impl TypeChecker {
                 let cond_type = self.infer_type(&while_stmt.condition);
                 if !matches!(cond_type, Type::Simple(ref s) if s == "bool") {
                     self.errors.push(format!(
-                        "While condition must be boolean, got {:?}",
-                        cond_type
+                        "While condition must be boolean, got {}",
+                        canonical_display(&cond_type)
                     ));
                 }
                 
-                for stmt in &while_stmt.body {
+                for stmt in while_stmt.body.iter().chain(while_stmt.else_branch.iter().flatten()) {
                     self.check_statement(stmt);
                 }
             }
             
             Stmt::For(for_stmt) => {
-                for stmt in &for_stmt.body {
+                for stmt in for_stmt.body.iter().chain(for_stmt.else_branch.iter().flatten()) {
                     self.check_statement(stmt);
                 }
             }
@@ -192,6 +245,44 @@ impl TypeChecker {
         }
     }
     
+    /// Report a `return value` whose type conflicts with the declared return
+    /// type. Only values whose type is known for certain are compared, since
+    /// `infer_type` falls back to a default for anything it cannot resolve.
+    fn check_return_type(&mut self, expr: &Expr, value_type: &Type) {
+        let Some((function, Some(declared))) = self.current_function.clone() else {
+            return;
+        };
+        let known = match expr {
+            Expr::IntLiteral(_) => {
+                // Integer literals fit any integer type
+                let Type::Simple(name) = &declared else { return };
+                if name.starts_with("uint") || name.starts_with("int") {
+                    return;
+                }
+                true
+            }
+            Expr::BoolLiteral(_) | Expr::StringLiteral(_) => true,
+            Expr::BinOp(_, op, _) => matches!(
+                op,
+                BinOp::Eq | BinOp::NotEq | BinOp::Lt | BinOp::LtEq | BinOp::Gt | BinOp::GtEq | BinOp::And | BinOp::Or
+            ),
+            Expr::UnaryOp(UnaryOp::Not, _) => true,
+            Expr::Ident(name) => self.type_env.contains_key(name),
+            Expr::Call(callee, _) => {
+                matches!(&**callee, Expr::Ident(name) if self.lookup_function(name).is_some_and(|sig| sig.return_type.is_some()))
+            }
+            _ => false,
+        };
+        if known && !self.types_compatible(value_type, &declared) {
+            self.errors.push(format!(
+                "Type mismatch in return from '{}': declared {}, but returns {}",
+                function,
+                canonical_display(&declared),
+                canonical_display(value_type)
+            ));
+        }
+    }
+    
     fn infer_type(&mut self, expr: &Expr) -> Type {
         match expr {
             Expr::IntLiteral(_) => Type::Simple("uint256".to_string()),
@@ -213,10 +304,11 @@ impl TypeChecker {
             
             Expr::BinOp(left, op, right) => {
                 let left_type = self.infer_type(left);
-                let right_type = self.infer_type(right);
+                let _right_type = self.infer_type(right);
                 
                 match op {
-                    BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod | BinOp::Pow => {
+                    BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::FloorDiv | BinOp::Mod | BinOp::Pow
+                    | BinOp::Shl | BinOp::Shr | BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor => {
                         left_type
                     }
                     
@@ -224,11 +316,9 @@ impl TypeChecker {
                         Type::Simple("bool".to_string())
                     }
                     
-                    BinOp::And | BinOp::Or => {
+                    BinOp::And | BinOp::Or | BinOp::In => {
                         Type::Simple("bool".to_string())
                     }
-                    
-                    _ => Type::Simple("uint256".to_string()),
                 }
             }
             
@@ -237,20 +327,35 @@ impl TypeChecker {
                 
                 match op {
                     UnaryOp::Not => Type::Simple("bool".to_string()),
-                    UnaryOp::Neg => operand_type,
+                    UnaryOp::Neg | UnaryOp::BitNot => operand_type,
                     _ => Type::Simple("uint256".to_string()),
                 }
             }
             
-            Expr::Call(function, _args) => {
+            Expr::Call(function, args) => {
+                for arg in args {
+                    let _ = self.infer_type(arg);
+                }
+                
                 // Look up function signature
                 if let Expr::Ident(name) = &**function {
-                    if let Some(sig) = self.functions.get(name) {
+                    if let Some(sig) = self.lookup_function(name) {
                         sig.return_type.clone().unwrap_or(Type::Simple("uint256".to_string()))
+                    } else if Self::is_builtin_function(name) {
+                        if BUILTIN_FUNCTIONS.contains(&name.as_str()) {
+                            Type::Simple("uint256".to_string())
+                        } else {
+                            // Sized type constructors such as uint8(x) or bytes32(x)
+                            Type::Simple(name.clone())
+                        }
+                    } else if self.callable_names.contains(name) {
+                        Type::Simple("any".to_string())
                     } else {
-                        Type::Simple("uint256".to_string())
+                        self.errors.push(format!("call to undefined function '{}'", name));
+                        Type::Simple("any".to_string())
                     }
                 } else {
+                    // Method calls are not resolved yet
                     Type::Simple("uint256".to_string())
                 }
             }
@@ -268,13 +373,39 @@ impl TypeChecker {
                 // In a more sophisticated type system, we'd check that body and orelse have compatible types
                 self.infer_type(body)
             }
+
+            Expr::KeywordArg(_, value) => self.infer_type(value),
+
+            Expr::ListComp { .. } => Type::Simple("list".to_string()),
+
+            Expr::FString(_) => Type::Simple("string".to_string()),
+        }
+    }
+    
+    /// Look up a function declared in the current contract by its bare name
+    fn lookup_function(&self, name: &str) -> Option<&FunctionSignature> {
+        let contract = self.current_contract.as_ref()?;
+        self.functions.get(&format!("{}.{}", contract, name))
+    }
+    
+    fn is_builtin_function(name: &str) -> bool {
+        if BUILTIN_FUNCTIONS.contains(&name) {
+            return true;
         }
+        
+        // Sized integer and byte types double as conversion functions
+        ["uint", "int", "bytes"].iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|bits| !bits.is_empty() && bits.chars().all(|c| c.is_ascii_digit()))
+        })
     }
     
     fn types_compatible(&self, t1: &Type, t2: &Type) -> bool {
         // Simplified type compatibility check
         match (t1, t2) {
-            (Type::Simple(s1), Type::Simple(s2)) => s1 == s2 || s1 == "any" || s2 == "any",
+            (Type::Simple(s1), Type::Simple(s2)) => {
+                canonical_name(s1) == canonical_name(s2) || s1 == "any" || s2 == "any"
+            }
             _ => true, // Be lenient for now
         }
     }
@@ -283,3 +414,61 @@ impl TypeChecker {
         stmts.iter().any(|stmt| matches!(stmt, Stmt::Return(_)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quorlin_lexer::Lexer;
+    
+    fn check(source: &str) -> Result<(), Vec<String>> {
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        TypeChecker::new().check(&module)
+    }
+    
+    #[test]
+    fn test_undefined_function_call_is_reported() {
+        let source = r#"
+from std.math import safe_add
+
+contract Counter:
+    _count: uint256
+    
+    fn bump(n: uint256):
+        self._count = safe_add(helper(n), uint256(n))
+"#;
+        let errors = check(source).unwrap_err();
+        assert_eq!(errors, vec!["call to undefined function 'helper'".to_string()]);
+    }
+    
+    #[test]
+    fn test_method_calls_are_tolerated() {
+        let source = r#"
+contract Counter:
+    _count: uint256
+    
+    fn bump(n: uint256):
+        self._count = self.compute(n)
+"#;
+        assert!(check(source).is_ok());
+    }
+    
+    #[test]
+    fn test_return_value_conflicting_with_declared_type_is_reported() {
+        let source = r#"
+contract Flags:
+    fn is_ready(ready: bool) -> bool:
+        if ready:
+            return ready
+        return 5
+    
+    fn count() -> uint8:
+        return 5
+"#;
+        let errors = check(source).unwrap_err();
+        assert_eq!(
+            errors,
+            vec!["Type mismatch in return from 'is_ready': declared bool, but returns uint256".to_string()]
+        );
+    }
+}
@@ -30,6 +30,8 @@ impl AptosCodegen {
     }
     
     pub fn generate(&self, module: &Module) -> Result<String, AptosCodegenError> {
+        let module = &module.for_target("aptos");
+
         let mut generator = move_gen::MoveGenerator::new(&self.module_address);
         generator.generate_module(module)
     }
@@ -44,7 +46,13 @@ impl Default for AptosCodegen {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use quorlin_parser::parse;
+    use quorlin_lexer::Lexer;
+    use quorlin_parser::parse_module;
+
+    fn parse(source: &str) -> Result<Module, String> {
+        let tokens = Lexer::new(source).tokenize().map_err(|e| e.to_string())?;
+        parse_module(tokens).map_err(|e| e.to_string())
+    }
     
     #[test]
     fn test_simple_contract() {
@@ -72,7 +72,7 @@ impl MoveGenerator {
         let mut output = String::new();
         
         // Generate contract resource struct
-        output.push_str("\n");
+        output.push('\n');
         output.push_str(&self.indent());
         output.push_str(&format!("/// Contract: {}\n", contract.name));
         
@@ -134,7 +134,7 @@ impl MoveGenerator {
         output.push_str(&self.indent());
         output.push_str(&format!("/// Initialize the {} contract\n", contract.name));
         output.push_str(&self.indent());
-        output.push_str(&format!("public entry fun initialize(account: &signer) {{\n"));
+        output.push_str("public entry fun initialize(account: &signer) {\n");
         self.indent_level += 1;
         
         output.push_str(&self.indent());
@@ -175,7 +175,7 @@ impl MoveGenerator {
         }
         
         // Determine function visibility
-        let is_public = func.decorators.iter().any(|d| d == "external" || d == "public");
+        let is_public = func.has_decorator("external") || func.has_decorator("public");
         let visibility = if is_public { "public entry fun" } else { "fun" };
         
         output.push_str(&self.indent());
@@ -183,7 +183,7 @@ impl MoveGenerator {
         output.push_str(&format!(" {}", func.name));
         
         // Parameters
-        output.push_str("(");
+        output.push('(');
         
         // Add account parameter for public functions
         if is_public {
@@ -194,7 +194,7 @@ impl MoveGenerator {
         }
         
         // Add contract reference for non-static functions
-        let needs_contract_ref = func.body.iter().any(|stmt| self.references_self(stmt));
+        let needs_contract_ref = func.references_self();
         if needs_contract_ref && !is_public {
             output.push_str(&format!("contract: &mut {}", contract_name));
             if !func.params.is_empty() {
@@ -211,7 +211,7 @@ impl MoveGenerator {
             }
         }
         
-        output.push_str(")");
+        output.push(')');
         
         // Return type
         if let Some(return_type) = &func.return_type {
@@ -256,7 +256,7 @@ impl MoveGenerator {
             Stmt::Return(Some(expr)) => {
                 output.push_str(&self.indent());
                 output.push_str(&self.generate_expr(expr)?);
-                output.push_str("\n");
+                output.push('\n');
             }
             
             Stmt::Return(None) => {
@@ -277,7 +277,7 @@ impl MoveGenerator {
                 
                 self.indent_level -= 1;
                 output.push_str(&self.indent());
-                output.push_str("}");
+                output.push('}');
                 
                 if let Some(else_branch) = &if_stmt.else_branch {
                     output.push_str(" else {\n");
@@ -289,13 +289,16 @@ impl MoveGenerator {
                     
                     self.indent_level -= 1;
                     output.push_str(&self.indent());
-                    output.push_str("}");
+                    output.push('}');
                 }
                 
-                output.push_str("\n");
+                output.push('\n');
             }
             
             Stmt::While(while_stmt) => {
+                if while_stmt.else_branch.is_some() {
+                    return Err(AptosCodegenError::UnsupportedFeature("else clause on while loops".to_string()));
+                }
                 output.push_str(&self.indent());
                 output.push_str("while (");
                 output.push_str(&self.generate_expr(&while_stmt.condition)?);
@@ -315,7 +318,7 @@ impl MoveGenerator {
                 output.push_str(&self.indent());
                 output.push_str("assert!(");
                 output.push_str(&self.generate_expr(&req.condition)?);
-                if let Some(msg) = &req.message {
+                if let Some(Expr::StringLiteral(msg)) = &req.message {
                     output.push_str(&format!(", {}", msg));
                 }
                 output.push_str(");\n");
@@ -340,7 +343,7 @@ impl MoveGenerator {
         match expr {
             Expr::IntLiteral(n) => Ok(n.clone()),
             Expr::BoolLiteral(b) => Ok(b.to_string()),
-            Expr::StringLiteral(s) => Ok(format!("b\"{}\"", s)),
+            Expr::StringLiteral(s) => Ok(format!("b\"{}\"", s.escape_default())),
             Expr::HexLiteral(h) => Ok(format!("@{}", h)),
             Expr::NoneLiteral => Ok("()".to_string()),
             
@@ -353,6 +356,12 @@ impl MoveGenerator {
                 }
             }
             
+            Expr::BinOp(key, BinOp::In, table) => {
+                let key_str = self.generate_expr(key)?;
+                let table_str = self.generate_expr(table)?;
+                Ok(format!("table::contains(&{}, {})", table_str, key_str))
+            }
+
             Expr::BinOp(left, op, right) => {
                 let left_str = self.generate_expr(left)?;
                 let right_str = self.generate_expr(right)?;
@@ -406,6 +415,9 @@ impl MoveGenerator {
                 let orelse_str = self.generate_expr(orelse)?;
                 Ok(format!("if ({}) {} else {}", test_str, body_str, orelse_str))
             }
+            Expr::KeywordArg(..) => Err(AptosCodegenError::UnsupportedFeature("keyword arguments".to_string())),
+            Expr::ListComp { .. } => Err(AptosCodegenError::UnsupportedFeature("list comprehensions".to_string())),
+            Expr::FString(_) => Err(AptosCodegenError::UnsupportedFeature("f-strings".to_string())),
         }
     }
     
@@ -414,8 +426,10 @@ impl MoveGenerator {
             BinOp::Add => "+",
             BinOp::Sub => "-",
             BinOp::Mul => "*",
-            BinOp::Div => "/",
+            BinOp::Div | BinOp::FloorDiv => "/",
             BinOp::Mod => "%",
+            BinOp::Shl => "<<",
+            BinOp::Shr => ">>",
             BinOp::Eq => "==",
             BinOp::NotEq => "!=",
             BinOp::Lt => "<",
@@ -424,6 +438,9 @@ impl MoveGenerator {
             BinOp::GtEq => ">=",
             BinOp::And => "&&",
             BinOp::Or => "||",
+            BinOp::BitAnd => "&",
+            BinOp::BitOr => "|",
+            BinOp::BitXor => "^",
             _ => "/* unsupported op */",
         }
     }
@@ -439,7 +456,7 @@ impl MoveGenerator {
     fn generate_struct(&mut self, struct_decl: &StructDecl) -> Result<String, AptosCodegenError> {
         let mut output = String::new();
         
-        output.push_str("\n");
+        output.push('\n');
         output.push_str(&self.indent());
         output.push_str(&format!("struct {} has copy, drop {{\n", struct_decl.name));
         self.indent_level += 1;
@@ -462,47 +479,6 @@ impl MoveGenerator {
         Ok(String::from("// Enums not yet supported in Move\n"))
     }
     
-    fn references_self(&self, stmt: &Stmt) -> bool {
-        match stmt {
-            Stmt::Assign(assign) => {
-                self.expr_references_self(&assign.target) || self.expr_references_self(&assign.value)
-            }
-            Stmt::Return(Some(expr)) | Stmt::Expr(expr) => {
-                self.expr_references_self(expr)
-            }
-            Stmt::If(if_stmt) => {
-                self.expr_references_self(&if_stmt.condition) ||
-                if_stmt.then_branch.iter().any(|s| self.references_self(s)) ||
-                if_stmt.else_branch.as_ref().map(|b| b.iter().any(|s| self.references_self(s))).unwrap_or(false)
-            }
-            _ => false,
-        }
-    }
-    
-    fn expr_references_self(&self, expr: &Expr) -> bool {
-        match expr {
-            Expr::Ident(name) => name == "self",
-            Expr::Attribute(obj, _) => self.expr_references_self(obj),
-            Expr::BinOp(left, _, right) => {
-                self.expr_references_self(left) || self.expr_references_self(right)
-            }
-            Expr::Call(func, args) => {
-                self.expr_references_self(func) || args.iter().any(|a| self.expr_references_self(a))
-            }
-            Expr::Index(obj, idx) => {
-                self.expr_references_self(obj) || self.expr_references_self(idx)
-            }
-            Expr::List(items) | Expr::Tuple(items) => {
-                items.iter().any(|i| self.expr_references_self(i))
-            }
-            Expr::IfExp { test, body, orelse } => {
-                self.expr_references_self(test) || self.expr_references_self(body) || self.expr_references_self(orelse)
-            }
-            Expr::UnaryOp(_, op) => self.expr_references_self(op),
-            _ => false,
-        }
-    }
-    
     fn indent(&self) -> String {
         "    ".repeat(self.indent_level)
     }
@@ -26,7 +26,7 @@ impl TypeMapper {
             }
             Type::Tuple(types) => {
                 let move_types: Result<Vec<_>, _> = types.iter()
-                    .map(|t| Self::to_move_type(t))
+                    .map(Self::to_move_type)
                     .collect();
                 Ok(format!("({})", move_types?.join(", ")))
             }
@@ -35,6 +35,10 @@ impl TypeMapper {
                 let inner_type = Self::to_move_type(inner)?;
                 Ok(format!("vector<{}> /* size: {} */", inner_type, size))
             }
+            Type::ConstArray(inner, size) => {
+                let inner_type = Self::to_move_type(inner)?;
+                Ok(format!("vector<{}> /* size: {} */", inner_type, size))
+            }
         }
     }
     
@@ -87,7 +91,7 @@ impl TypeMapper {
             Type::Mapping(_, _) => Ok("table::new()".to_string()),
             Type::Optional(_) => Ok("option::none()".to_string()),
             Type::Tuple(_) => Ok("()".to_string()),
-            Type::FixedArray(_, _) => Ok("vector::empty()".to_string()),
+            Type::FixedArray(_, _) | Type::ConstArray(_, _) => Ok("vector::empty()".to_string()),
         }
     }
     
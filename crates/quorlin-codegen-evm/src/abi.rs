@@ -2,7 +2,8 @@
 //!
 //! Generates Ethereum Contract ABI (Application Binary Interface) from Quorlin contracts.
 
-use quorlin_parser::{ContractDecl, ContractMember, Function, Type, EventDecl};
+use quorlin_parser::type_names::{canonical_abi_type, UnsupportedAbiType};
+use quorlin_parser::{ContractDecl, ContractMember, Function, FunctionSignature, InterfaceDecl, EventDecl, Type};
 use serde::{Deserialize, Serialize};
 use serde_json;
 
@@ -23,6 +24,9 @@ pub struct AbiFunction {
     pub outputs: Vec<AbiParam>,
     #[serde(rename = "stateMutability")]
     pub state_mutability: String,
+    /// `@validate` constraints checked on entry; not part of the Solidity ABI
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validate: Vec<String>,
 }
 
 /// ABI specification for an event
@@ -58,26 +62,59 @@ pub struct AbiEventParam {
 
 impl ContractAbi {
     /// Generate ABI from a contract declaration
-    pub fn from_contract(contract: &ContractDecl, events: &[EventDecl]) -> Self {
+    pub fn from_contract(contract: &ContractDecl, events: &[EventDecl]) -> Result<Self, UnsupportedAbiType> {
         let mut functions = Vec::new();
 
         for member in &contract.body {
             if let ContractMember::Function(func) = member {
-                // Skip constructor
-                if func.name == "__init__" {
+                // Skip constructor and internal functions (not dispatched)
+                if func.name == "__init__" || crate::is_internal(func) {
                     continue;
                 }
 
-                functions.push(AbiFunction::from_function(func));
+                functions.push(AbiFunction::from_function(func)?);
             }
         }
 
-        let abi_events = events.iter().map(AbiEvent::from_event).collect();
+        let abi_events = events.iter().map(AbiEvent::from_event).collect::<Result<_, _>>()?;
 
-        ContractAbi {
+        Ok(ContractAbi {
             functions,
             events: abi_events,
-        }
+        })
+    }
+
+    /// Generate ABI from an interface declaration. Interfaces carry no
+    /// decorators, so each function's state mutability is taken from a
+    /// function in `contracts` with the same name and parameter types,
+    /// defaulting to `nonpayable`.
+    pub fn from_interface(interface: &InterfaceDecl, contracts: &[&ContractDecl]) -> Result<Self, UnsupportedAbiType> {
+        let implementation = |sig: &FunctionSignature| {
+            contracts
+                .iter()
+                .flat_map(|contract| &contract.body)
+                .find_map(|member| match member {
+                    ContractMember::Function(func)
+                        if func.name == sig.name
+                            && func.params.iter().map(|p| &p.type_annotation)
+                                .eq(sig.params.iter().map(|p| &p.type_annotation)) =>
+                    {
+                        Some(func)
+                    }
+                    _ => None,
+                })
+        };
+
+        let functions = interface
+            .functions
+            .iter()
+            .map(|sig| AbiFunction::from_signature(sig, implementation(sig)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ContractAbi {
+            functions,
+            events: Vec::new(),
+        })
     }
 
     /// Convert ABI to JSON string
@@ -99,82 +136,79 @@ impl ContractAbi {
 }
 
 impl AbiFunction {
-    fn from_function(func: &Function) -> Self {
-        let inputs = func.params.iter().map(|p| AbiParam {
-            name: p.name.clone(),
-            typ: type_to_abi_string(&p.type_annotation),
-            internal_type: type_to_abi_string(&p.type_annotation),
-        }).collect();
-
-        let outputs = if let Some(ret_type) = &func.return_type {
-            vec![AbiParam {
-                name: String::new(),
-                typ: type_to_abi_string(ret_type),
-                internal_type: type_to_abi_string(ret_type),
-            }]
-        } else {
-            Vec::new()
+    fn from_function(func: &Function) -> Result<Self, UnsupportedAbiType> {
+        let signature = FunctionSignature {
+            name: func.name.clone(),
+            params: func.params.clone(),
+            return_type: func.return_type.clone(),
+        };
+        Self::from_signature(&signature, Some(func))
+    }
+
+    /// Build the entry for `sig`, taking state mutability from `implementation`
+    fn from_signature(sig: &FunctionSignature, implementation: Option<&Function>) -> Result<Self, UnsupportedAbiType> {
+        let inputs = sig.params.iter().map(|p| {
+            let typ = canonical_abi_type(&p.type_annotation)?;
+            Ok(AbiParam {
+                name: p.name.clone(),
+                internal_type: typ.clone(),
+                typ,
+            })
+        }).collect::<Result<_, _>>()?;
+
+        // A tuple return is one output per element
+        let output_types: &[Type] = match &sig.return_type {
+            Some(Type::Tuple(types)) => types,
+            Some(ret_type) => std::slice::from_ref(ret_type),
+            None => &[],
         };
+        let outputs = output_types.iter().map(|ty| {
+            let typ = canonical_abi_type(ty)?;
+            Ok(AbiParam {
+                name: String::new(),
+                internal_type: typ.clone(),
+                typ,
+            })
+        }).collect::<Result<_, _>>()?;
 
-        let state_mutability = if func.decorators.contains(&"view".to_string()) {
+        let has_decorator = |name: &str| implementation.is_some_and(|f| f.has_decorator(name));
+        let state_mutability = if has_decorator("view") {
             "view"
-        } else if func.decorators.contains(&"payable".to_string()) {
+        } else if has_decorator("payable") {
             "payable"
         } else {
             "nonpayable"
         }.to_string();
 
-        AbiFunction {
+        Ok(AbiFunction {
             typ: "function".to_string(),
-            name: func.name.clone(),
+            name: sig.name.clone(),
             inputs,
             outputs,
             state_mutability,
-        }
+            validate: implementation.map(Function::validations).unwrap_or_default(),
+        })
     }
 }
 
 impl AbiEvent {
-    fn from_event(event: &EventDecl) -> Self {
-        let inputs = event.params.iter().map(|p| AbiEventParam {
-            name: p.name.clone(),
-            typ: type_to_abi_string(&p.type_annotation),
-            indexed: p.indexed,
-            internal_type: type_to_abi_string(&p.type_annotation),
-        }).collect();
-
-        AbiEvent {
+    fn from_event(event: &EventDecl) -> Result<Self, UnsupportedAbiType> {
+        let inputs = event.params.iter().map(|p| {
+            let typ = canonical_abi_type(&p.type_annotation)?;
+            Ok(AbiEventParam {
+                name: p.name.clone(),
+                internal_type: typ.clone(),
+                typ,
+                indexed: p.indexed,
+            })
+        }).collect::<Result<_, _>>()?;
+
+        Ok(AbiEvent {
             typ: "event".to_string(),
             name: event.name.clone(),
             inputs,
             anonymous: false,
-        }
-    }
-}
-
-/// Convert Quorlin type to ABI type string
-fn type_to_abi_string(typ: &Type) -> String {
-    match typ {
-        Type::Simple(name) => match name.as_str() {
-            "uint256" => "uint256".to_string(),
-            "int256" => "int256".to_string(),
-            "address" => "address".to_string(),
-            "bool" => "bool".to_string(),
-            "bytes32" => "bytes32".to_string(),
-            "string" => "string".to_string(),
-            _ => name.clone(),
-        },
-        Type::Mapping(key, val) => {
-            // Mappings don't appear in ABI (they're internal state)
-            format!("mapping({} => {})", type_to_abi_string(key), type_to_abi_string(val))
-        }
-        Type::List(inner) => format!("{}[]", type_to_abi_string(inner)),
-        Type::FixedArray(inner, size) => format!("{}[{}]", type_to_abi_string(inner), size),
-        Type::Optional(inner) => type_to_abi_string(inner),
-        Type::Tuple(types) => {
-            let type_strs: Vec<String> = types.iter().map(type_to_abi_string).collect();
-            format!("({})", type_strs.join(","))
-        }
+        })
     }
 }
 
@@ -184,8 +218,47 @@ mod tests {
 
     #[test]
     fn test_type_to_abi_string() {
-        assert_eq!(type_to_abi_string(&Type::Simple("uint256".to_string())), "uint256");
-        assert_eq!(type_to_abi_string(&Type::Simple("address".to_string())), "address");
-        assert_eq!(type_to_abi_string(&Type::Simple("bool".to_string())), "bool");
+        use quorlin_parser::Type;
+
+        assert_eq!(canonical_abi_type(&Type::Simple("uint256".to_string())).unwrap(), "uint256");
+        assert_eq!(canonical_abi_type(&Type::Simple("address".to_string())).unwrap(), "address");
+        assert_eq!(canonical_abi_type(&Type::Simple("bool".to_string())).unwrap(), "bool");
+    }
+
+    #[test]
+    fn test_interface_abi_lists_its_functions() {
+        let source = r#"
+interface IVault:
+    fn deposit(amount: uint256)
+    fn balance(owner: address) -> uint256
+
+contract Vault:
+    _total: uint256
+
+    @external
+    fn deposit(amount: uint256):
+        self._total = self._total + amount
+
+    @view
+    fn balance(owner: address) -> uint256:
+        return self._total
+"#;
+        let tokens = quorlin_lexer::Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        let quorlin_parser::Item::Interface(interface) = &module.items[0] else {
+            panic!("expected interface");
+        };
+        let quorlin_parser::Item::Contract(vault) = &module.items[1] else {
+            panic!("expected contract");
+        };
+
+        let abi = ContractAbi::from_interface(interface, &[vault]).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&abi.to_json().unwrap()).unwrap();
+        let entries = json.as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry["type"] == "function"));
+        assert_eq!(entries[0]["stateMutability"], "nonpayable");
+        assert_eq!(entries[1]["stateMutability"], "view");
+        assert_eq!(entries[1]["outputs"][0]["type"], "uint256");
     }
 }
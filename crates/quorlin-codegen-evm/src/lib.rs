@@ -8,9 +8,12 @@
 pub mod yul_generator;
 pub mod storage_layout;
 pub mod abi;
+pub mod yul_check;
 
-use quorlin_parser::Module;
-use std::collections::HashMap;
+use quorlin_parser::type_names::{canonical_abi_type, canonical_display};
+use quorlin_parser::visit::{self, Visitor};
+use quorlin_parser::{fold_fstring, BinOp, Expr, Function, Module, Stmt, Type, UnaryOp};
+use std::collections::{HashMap, HashSet};
 
 /// Errors that can occur during code generation
 #[derive(Debug, thiserror::Error)]
@@ -21,7 +24,7 @@ pub enum CodegenError {
     #[error("Unsupported feature: {0}")]
     UnsupportedFeature(String),
 
-    #[error("Contract not found")]
+    #[error("No contract to compile")]
     ContractNotFound,
 }
 
@@ -36,8 +39,106 @@ pub struct EvmCodegen {
     /// Current storage slot counter
     next_storage_slot: usize,
 
+    /// Declared types of state variables
+    state_types: HashMap<String, Type>,
+
+    /// Yul literals for state variables proven constant, read without `sload`
+    constant_state: HashMap<String, String>,
+
+    /// Yul expressions for `const` declarations, inlined at each use
+    constants: HashMap<String, String>,
+
+    /// Source values of `const` declarations, for folding f-strings
+    constant_values: HashMap<String, Expr>,
+
     /// Event signatures for event emission
     event_signatures: HashMap<String, String>,
+
+    /// Declared custom errors and their parameters, for revert payloads
+    errors: HashMap<String, Vec<quorlin_parser::Param>>,
+
+    /// Contract functions callable from generated code, keyed by name
+    functions: HashMap<String, FunctionSymbol>,
+
+    /// `@inline` functions spliced into their call sites instead of being
+    /// emitted: parameter names and the returned expression
+    inline_functions: HashMap<String, (Vec<quorlin_parser::Param>, Expr)>,
+
+    /// Whether the function being generated is internal (returns via `leave`)
+    in_internal_function: bool,
+
+    /// Parameters and locals of the current function that hold memory strings
+    string_locals: HashSet<String>,
+
+    /// Parameters and locals of the current function that hold memory lists
+    /// or tuples
+    list_locals: HashSet<String>,
+
+    /// Declared types of the parameters and annotated locals of the current
+    /// function
+    local_types: HashMap<String, Type>,
+
+    /// Parameters and locals of the current function, the locals declared
+    /// once at its entry
+    declared_locals: HashSet<String>,
+
+    /// Declared return type of the external function being generated
+    return_type: Option<Type>,
+
+    /// Enclosing loops, innermost last, with the "no break" flag of those
+    /// that have an `else` block
+    break_flags: Vec<Option<String>>,
+
+    /// Loops given a "no break" flag so far, used to keep flag names unique
+    flagged_loops: usize,
+
+    /// Tuple unpackings so far, used to keep their temporaries' names unique
+    unpackings: usize,
+
+    /// Conditional expressions lowered so far, used to name their temporaries
+    conditionals: usize,
+
+    /// Arguments of `@inline` calls bound so far, used to name their temporaries
+    inline_args: usize,
+
+    /// Shared operands of comparison chains bound so far, used to name their
+    /// temporaries
+    chain_operands: usize,
+
+    /// Whether optimizations such as storage-read caching are enabled
+    optimize: bool,
+
+    /// State variables of the current function whose value is held in a Yul
+    /// local, keyed by name with the local's name
+    cached_state: HashMap<String, String>,
+
+    /// Whether revert reasons are stored once in the runtime object's data
+    /// section instead of being written out at every revert
+    intern_reasons: bool,
+
+    /// Interned revert reasons, in order of first use; reason `i` lives in
+    /// the data object `reason_i`
+    revert_reasons: Vec<String>,
+}
+
+/// How a contract function is exposed in the generated Yul
+#[derive(Debug, Clone)]
+struct FunctionSymbol {
+    /// Internal functions become parameterised Yul functions and are not dispatched
+    internal: bool,
+    /// Declared return type, if the function produces a value
+    return_type: Option<Type>,
+    /// Declared parameters, for binding keyword arguments and defaults
+    params: Vec<quorlin_parser::Param>,
+}
+
+/// Decorators that make a function part of the external interface
+const EXTERNAL_DECORATORS: &[&str] = &["external", "public", "view", "pure", "payable"];
+
+/// A function is internal when marked `@internal` or when it carries no
+/// externally visible decorator at all
+pub(crate) fn is_internal(func: &Function) -> bool {
+    func.has_decorator("internal") || !func.decorators.iter().any(|d| EXTERNAL_DECORATORS.contains(&d.name.as_str()))
 }
 
 impl EvmCodegen {
@@ -46,12 +147,43 @@ impl EvmCodegen {
         Self {
             storage_layout: HashMap::new(),
             next_storage_slot: 0,
+            state_types: HashMap::new(),
+            constant_state: HashMap::new(),
+            constants: HashMap::new(),
+            constant_values: HashMap::new(),
             event_signatures: HashMap::new(),
+            errors: HashMap::new(),
+            functions: HashMap::new(),
+            inline_functions: HashMap::new(),
+            in_internal_function: false,
+            string_locals: HashSet::new(),
+            list_locals: HashSet::new(),
+            local_types: HashMap::new(),
+            declared_locals: HashSet::new(),
+            return_type: None,
+            break_flags: Vec::new(),
+            flagged_loops: 0,
+            unpackings: 0,
+            conditionals: 0,
+            inline_args: 0,
+            chain_operands: 0,
+            optimize: false,
+            cached_state: HashMap::new(),
+            intern_reasons: false,
+            revert_reasons: Vec::new(),
         }
     }
 
+    /// Enable or disable optimizations
+    pub fn with_optimize(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
     /// Generate Yul code from a module
     pub fn generate(&mut self, module: &Module) -> CodegenResult<String> {
+        let module = &module.for_target("evm");
+
         // Find the contract (for now, assume only one contract per module)
         let contract = module
             .items
@@ -67,9 +199,24 @@ impl EvmCodegen {
 
         // Collect event definitions
         self.collect_events(module)?;
+        for item in &module.items {
+            if let quorlin_parser::Item::Error(error) = item {
+                self.errors.insert(error.name.clone(), error.params.clone());
+            }
+        }
+
+        // Allocate storage slots for state variables (bases first)
+        self.allocate_storage(module, contract)?;
+
+        // Constants take no storage; they are inlined where used
+        let constants = self.collect_constants(module, &contract.body)?;
 
-        // Allocate storage slots for state variables
-        self.allocate_storage(&contract.body)?;
+        // Record which functions exist so calls can be resolved
+        self.collect_functions(&contract.body);
+        self.check_inline_cycles()?;
+
+        // Find state variables whose reads can be replaced by literals
+        self.collect_constant_state(&contract.body);
 
         // Generate Yul code
         let mut yul = String::new();
@@ -88,6 +235,7 @@ impl EvmCodegen {
         // Runtime code
         yul.push_str("  object \"runtime\" {\n");
         yul.push_str("    code {\n");
+        yul.push_str(&constants);
 
         // Add checked arithmetic helper functions
         yul.push_str(&self.generate_checked_math_helpers());
@@ -95,13 +243,21 @@ impl EvmCodegen {
         // Function dispatcher
         yul.push_str(&self.generate_dispatcher(&contract.body)?);
 
-        // Function implementations
+        // Function implementations. Runtime reverts may share reason data;
+        // the constructor cannot reach the runtime object's data.
+        self.intern_reasons = self.optimize;
         yul.push_str(&self.generate_functions(&contract.body)?);
+        self.intern_reasons = false;
 
         yul.push_str("    }\n");
+        for (i, reason) in self.revert_reasons.iter().enumerate() {
+            let payload = to_hex(&error_string_payload(reason));
+            yul.push_str(&format!("    data \"reason_{}\" hex\"{}\"\n", i, payload));
+        }
         yul.push_str("  }\n");
         yul.push_str("}\n");
 
+        yul_check::validate(&yul)?;
         Ok(yul)
     }
 
@@ -138,12 +294,47 @@ impl EvmCodegen {
           result := div(a, b)
       }
 
+      // Signed floor division: sdiv truncates toward zero, so step down
+      // when there is a remainder and the operands' signs differ
+      function checked_floor_sdiv(a, b) -> result {
+          if iszero(b) { revert(0, 0) }
+          if eq(b, not(0)) { if eq(a, shl(255, 1)) { revert(0, 0) } }
+          result := sdiv(a, b)
+          if smod(a, b) { if slt(xor(a, b), 0) { result := sub(result, 1) } }
+      }
+
       function checked_mod(a, b) -> result {
           // Modulo by zero check
           if iszero(b) { revert(0, 0) }
           result := mod(a, b)
       }
 
+      function checked_exp(base, exponent) -> result {
+          // Square-and-multiply; every multiplication is overflow checked
+          result := 1
+          for { } exponent { exponent := shr(1, exponent) } {
+              if mod(exponent, 2) { result := checked_mul(result, base) }
+              if gt(exponent, 1) { base := checked_mul(base, base) }
+          }
+      }
+
+      // Shifts by 256 bits or more would silently clear the value.
+      // Bits shifted out of the word are truncated, as in Solidity.
+      function checked_shl(value, shift) -> result {
+          if iszero(lt(shift, 256)) { revert(0, 0) }
+          result := shl(shift, value)
+      }
+
+      function checked_shr(value, shift) -> result {
+          if iszero(lt(shift, 256)) { revert(0, 0) }
+          result := shr(shift, value)
+      }
+
+      function checked_sar(value, shift) -> result {
+          if iszero(lt(shift, 256)) { revert(0, 0) }
+          result := sar(shift, value)
+      }
+
       // ========================================
       // STORAGE ACCESS HELPERS
       // Clean mapping/array access without block expressions
@@ -164,50 +355,354 @@ impl EvmCodegen {
           result := sload(keccak256(0, 64))
       }
 
-      function select(cond, a, b) -> result {
-          switch cond
-          case 0 { result := b }
-          default { result := a }
+      // ========================================
+      // MEMORY STRING HELPERS
+      // A str/bytes value in memory is a length word followed by the data
+      // ========================================
+
+      function memory_string(word, len) -> result {
+          result := mload(0x40)
+          mstore(result, len)
+          mstore(add(result, 32), word)
+          mstore(0x40, add(result, 64))
+      }
+
+      function concat_memory(a, b) -> result {
+          let a_len := mload(a)
+          let b_len := mload(b)
+          result := mload(0x40)
+          mstore(result, add(a_len, b_len))
+          let data := add(result, 32)
+          copy_memory(data, add(a, 32), a_len)
+          copy_memory(add(data, a_len), add(b, 32), b_len)
+          mstore(0x40, add(data, mul(div(add(add(a_len, b_len), 31), 32), 32)))
+      }
+
+      function copy_memory(dst, src, len) {
+          for { let i := 0 } lt(i, len) { i := add(i, 32) } {
+              mstore(add(dst, i), mload(add(src, i)))
+          }
+      }
+
+      // ========================================
+      // MEMORY LIST HELPERS
+      // A list or tuple in memory is a length word followed by one word
+      // per element
+      // ========================================
+
+      function memory_list(len) -> result {
+          result := mload(0x40)
+          mstore(result, len)
+          mstore(0x40, add(result, mul(add(len, 1), 32)))
+      }
+
+      function list_set(list, index, value) -> result {
+          mstore(add(list, mul(add(index, 1), 32)), value)
+          result := list
+      }
+
+      function list_at(list, index) -> result {
+          if iszero(lt(index, mload(list))) { revert(0, 0) }
+          result := mload(add(list, mul(add(index, 1), 32)))
       }
 
       // ========================================
 "#.to_string()
     }
 
-    /// Collect event definitions and calculate their signatures
+    /// Collect event definitions and calculate their topic0 hashes
     fn collect_events(&mut self, module: &Module) -> CodegenResult<()> {
         for item in &module.items {
             if let quorlin_parser::Item::Event(event) = item {
-                // Calculate event signature (simplified - using hash of name)
-                // In real implementation, should be keccak256(name + param types)
-                use std::collections::hash_map::DefaultHasher;
-                use std::hash::{Hash, Hasher};
+                let hash = signature_hash(&event.name, event.params.iter().map(|p| &p.type_annotation))?;
+                let topic: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+                self.event_signatures.insert(event.name.clone(), format!("0x{}", topic));
+            }
+        }
+        Ok(())
+    }
+
+    /// Revert with an ABI-encoded custom error: the 4-byte selector of
+    /// `Name(types...)` followed by one word per argument. Arguments are
+    /// evaluated before the payload is written, since they may use scratch
+    /// memory themselves
+    fn generate_error_revert(&mut self, raise: &quorlin_parser::RaiseStmt, indent: usize) -> CodegenResult<String> {
+        let indent_str = " ".repeat(indent);
+        let params = self
+            .errors
+            .get(&raise.error)
+            .cloned()
+            .ok_or_else(|| CodegenError::Error(format!("Unknown error: {}", raise.error)))?;
+        if params.len() != raise.args.len() {
+            return Err(CodegenError::Error(format!(
+                "Error {} expects {} arguments, got {}",
+                raise.error,
+                params.len(),
+                raise.args.len()
+            )));
+        }
+
+        let mut code = String::new();
+        for (i, arg) in raise.args.iter().enumerate() {
+            let arg_code = self.generate_expression(arg)?;
+            code.push_str(&format!("{}let err_{} := {}\n", indent_str, i, arg_code));
+        }
+        let selector = function_selector(&raise.error, &params)?;
+        code.push_str(&format!("{}mstore(0, shl(224, 0x{:08x}))\n", indent_str, selector));
+        for i in 0..raise.args.len() {
+            code.push_str(&format!("{}mstore({}, err_{})\n", indent_str, 4 + 32 * i, i));
+        }
+        code.push_str(&format!("{}revert(0, {})\n", indent_str, 4 + 32 * raise.args.len()));
+        Ok(code)
+    }
+
+    /// Revert with `message` as an `Error(string)` reason
+    fn generate_reason_revert(&mut self, message: &str, indent_str: &str) -> String {
+        if self.intern_reasons {
+            let index = match self.revert_reasons.iter().position(|reason| reason == message) {
+                Some(index) => index,
+                None => {
+                    self.revert_reasons.push(message.to_string());
+                    self.revert_reasons.len() - 1
+                }
+            };
+            return format!(
+                "{0}datacopy(0, dataoffset(\"reason_{1}\"), datasize(\"reason_{1}\"))\n{0}revert(0, datasize(\"reason_{1}\"))\n",
+                indent_str, index
+            );
+        }
+
+        let payload = error_string_payload(message);
+        let mut code = format!("{}mstore(0, shl(224, 0x{}))\n", indent_str, to_hex(&payload[..4]));
+        code.push_str(&format!("{}mstore(4, 32)\n", indent_str));
+        code.push_str(&format!("{}mstore(36, {})\n", indent_str, message.len()));
+        for (i, word) in payload[68..].chunks(32).enumerate() {
+            code.push_str(&format!("{}mstore({}, 0x{})\n", indent_str, 68 + 32 * i, to_hex(word)));
+        }
+        code.push_str(&format!("{}revert(0, {})\n", indent_str, payload.len()));
+        code
+    }
 
-                let mut hasher = DefaultHasher::new();
-                event.name.hash(&mut hasher);
-                for param in &event.params {
-                    param.name.hash(&mut hasher);
+    /// Collect the contract's functions into the call-resolution table
+    fn collect_functions(&mut self, members: &[quorlin_parser::ContractMember]) {
+        for member in members {
+            if let quorlin_parser::ContractMember::Function(func) = member {
+                if func.name == "__init__" {
+                    continue;
+                }
+                self.functions.insert(
+                    func.name.clone(),
+                    FunctionSymbol {
+                        internal: is_internal(func),
+                        return_type: func.return_type.clone(),
+                        params: func.params.clone(),
+                    },
+                );
+                if let Some(body) = inline_body(func) {
+                    self.inline_functions.insert(func.name.clone(), (func.params.clone(), body.clone()));
                 }
-                let sig = format!("0x{:064x}", hasher.finish());
-                self.event_signatures.insert(event.name.clone(), sig);
             }
         }
-        Ok(())
     }
 
-    /// Allocate storage slots for state variables
-    fn allocate_storage(&mut self, members: &[quorlin_parser::ContractMember]) -> CodegenResult<()> {
+    /// Find state variables that always hold the same literal: initialised
+    /// with a literal and never written, or written exactly once with a
+    /// literal at the top level of the constructor
+    fn collect_constant_state(&mut self, members: &[quorlin_parser::ContractMember]) {
+        use quorlin_parser::ContractMember;
+
+        let mut writes = StateWriteCounter::default();
+        for member in members {
+            if let ContractMember::Function(func) = member {
+                writes.visit_function(func);
+            }
+        }
+
+        let constructor_value = |name: &str| {
+            members.iter().find_map(|member| match member {
+                ContractMember::Function(func) if func.name == "__init__" => {
+                    func.body.iter().find_map(|stmt| match stmt {
+                        Stmt::Assign(assign) if assigned_state_var(&assign.target) == Some(name) => {
+                            Some(&assign.value)
+                        }
+                        _ => None,
+                    })
+                }
+                _ => None,
+            })
+        };
+
         for member in members {
-            if let quorlin_parser::ContractMember::StateVar(var) = member {
-                self.storage_layout.insert(var.name.clone(), self.next_storage_slot);
-                self.next_storage_slot += 1;
+            let ContractMember::StateVar(var) = member else {
+                continue;
+            };
+            let value = match (&var.initial_value, writes.counts.get(var.name.as_str())) {
+                (Some(value), None) => Some(value),
+                (None, Some(1)) => constructor_value(&var.name),
+                _ => None,
+            };
+            let Some(value) = value.filter(|v| is_literal(v)) else {
+                continue;
+            };
+            if let Ok(code) = self.generate_expression(value) {
+                self.constant_state.insert(var.name.clone(), code);
+            }
+        }
+    }
+
+    /// Reject `@inline` functions that reach themselves through other
+    /// `@inline` calls, which would expand forever
+    fn check_inline_cycles(&self) -> CodegenResult<()> {
+        let mut names: Vec<&str> = self.inline_functions.keys().map(String::as_str).collect();
+        names.sort_unstable();
+
+        for start in names {
+            let mut path = Vec::new();
+            let mut pending = vec![(start, 0)];
+            while let Some((name, depth)) = pending.pop() {
+                path.truncate(depth);
+                path.push(name);
+                let mut callees = CalleeCollector { functions: &self.functions, names: Vec::new(), external: false };
+                callees.visit_expr(&self.inline_functions[name].1);
+                for callee in callees.names.into_iter().filter(|c| self.inline_functions.contains_key(*c)) {
+                    if callee == start {
+                        return Err(CodegenError::Error(format!(
+                            "@inline functions cannot expand into themselves: {} -> {}",
+                            path.join(" -> "),
+                            start
+                        )));
+                    }
+                    if !path.contains(&callee) {
+                        pending.push((callee, depth + 1));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The `@inline` function called by `expr`, if any
+    fn inline_callee<'e>(&self, expr: &'e Expr) -> Option<&'e str> {
+        let Expr::Call(callee, _) = expr else {
+            return None;
+        };
+        let name = match &**callee {
+            Expr::Ident(name) => name,
+            Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => name,
+            _ => return None,
+        };
+        self.inline_functions.contains_key(name).then_some(name.as_str())
+    }
+
+    /// The parameters and returned expression of the `@inline` function
+    /// `name`, checked against the number of arguments passed
+    fn inline_signature(&self, name: &str, args: &[Expr]) -> CodegenResult<(Vec<quorlin_parser::Param>, Expr)> {
+        let (params, body) = &self.inline_functions[name];
+        if params.len() != args.len() {
+            return Err(CodegenError::Error(format!(
+                "'{}' expects {} argument(s), got {}",
+                name,
+                params.len(),
+                args.len()
+            )));
+        }
+        Ok((params.clone(), body.clone()))
+    }
+
+    /// Expand a call to an `@inline` function into its returned expression
+    /// with the arguments substituted for the parameters. Statements bind
+    /// the arguments to temporaries first (see `bind_inline_calls`); here an
+    /// argument is only substituted where that still evaluates it exactly once
+    fn generate_inline_call(&self, name: &str, args: &[Expr]) -> Option<CodegenResult<String>> {
+        self.inline_functions.get(name)?;
+        let (params, body) = match self.inline_signature(name, args) {
+            Ok(signature) => signature,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let mut uses = IdentCounter::default();
+        uses.visit_expr(&body);
+        for (param, arg) in params.iter().zip(args) {
+            let count = uses.counts.get(param.name.as_str()).copied().unwrap_or(0);
+            if !is_inline_operand(arg) && count != 1 {
+                return Some(Err(CodegenError::UnsupportedFeature(format!(
+                    "@inline call to '{}' here would evaluate '{}' {} times; assign the argument to a local first",
+                    name, param.name, count
+                ))));
             }
         }
+        let bindings: HashMap<&str, &Expr> = params.iter().map(|p| p.name.as_str()).zip(args).collect();
+        Some(self.generate_expression(&substitute_params(&body, &bindings)))
+    }
+
+    /// Resolve a call to a contract function, returning its Yul call expression
+    fn generate_internal_call(&self, name: &str, arg_codes: &[String]) -> CodegenResult<String> {
+        match self.functions.get(name) {
+            Some(symbol) if symbol.internal => Ok(format!("{}({})", name, arg_codes.join(", "))),
+            Some(_) if arg_codes.is_empty() => Ok(format!("{}()", name)),
+            Some(_) => Err(CodegenError::UnsupportedFeature(format!(
+                "Internal call to external function '{}' with arguments",
+                name
+            ))),
+            None => Err(CodegenError::Error(format!("call to undefined function '{}'", name))),
+        }
+    }
+
+    /// Allocate storage slots for state variables, including inherited ones
+    fn allocate_storage(&mut self, module: &Module, contract: &quorlin_parser::ContractDecl) -> CodegenResult<()> {
+        let contracts: Vec<_> = module
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                quorlin_parser::Item::Contract(c) => Some(c),
+                _ => None,
+            })
+            .collect();
+
+        let mut layout = storage_layout::StorageLayout::new();
+        layout
+            .allocate_inherited(contract, &contracts)
+            .map_err(CodegenError::Error)?;
+
+        for (name, info) in layout.slots {
+            self.storage_layout.insert(name.clone(), info.slot);
+            self.state_types.insert(name, info.var_type);
+        }
+        self.next_storage_slot = layout.next_slot;
         Ok(())
     }
 
+    /// Record the Yul value of each `const` declaration, module-level ones
+    /// first, and return a comment block documenting them
+    fn collect_constants(&mut self, module: &Module, members: &[quorlin_parser::ContractMember]) -> CodegenResult<String> {
+        let module_constants = module.items.iter().filter_map(|item| match item {
+            quorlin_parser::Item::Const(constant) => Some(constant),
+            _ => None,
+        });
+        let contract_constants = members.iter().filter_map(|member| match member {
+            quorlin_parser::ContractMember::Constant(constant) => Some(constant),
+            _ => None,
+        });
+        let mut comments = String::new();
+        for constant in module_constants.chain(contract_constants) {
+            let value = self.generate_expression(&constant.value)?;
+            comments.push_str(&format!(
+                "      // const {}: {} = {}\n",
+                constant.name,
+                canonical_display(&constant.type_annotation),
+                value
+            ));
+            self.constants.insert(constant.name.clone(), value);
+            self.constant_values.insert(constant.name.clone(), constant.value.clone());
+        }
+        if !comments.is_empty() {
+            comments.push('\n');
+        }
+        Ok(comments)
+    }
+
     /// Generate constructor code
-    fn generate_constructor(&self, members: &[quorlin_parser::ContractMember]) -> CodegenResult<String> {
+    fn generate_constructor(&mut self, members: &[quorlin_parser::ContractMember]) -> CodegenResult<String> {
         // Find constructor function
         let constructor = members.iter().find_map(|member| {
             if let quorlin_parser::ContractMember::Function(func) = member {
@@ -238,20 +733,22 @@ impl EvmCodegen {
                         offset, offset
                     ));
                     code.push_str(&format!(
-                        "    let {} := mload({})\n",
-                        param.name, offset
+                        "    let {} := {}\n",
+                        param.name,
+                        decode_word(&format!("mload({})", offset), &param.type_annotation)
                     ));
                 }
 
-                code.push_str("\n");
+                code.push('\n');
             }
 
             // Execute constructor body
+            code.push_str(&self.declare_locals(&ctor.params, &ctor.body, 4));
             for stmt in &ctor.body {
                 code.push_str(&self.generate_statement(stmt, 4)?);
             }
 
-            code.push_str("\n");
+            code.push('\n');
         }
 
         Ok(code)
@@ -261,18 +758,30 @@ impl EvmCodegen {
     fn generate_dispatcher(&self, members: &[quorlin_parser::ContractMember]) -> CodegenResult<String> {
         let mut code = String::new();
 
+        // Free memory pointer, used when decoding dynamic parameters
+        code.push_str("      mstore(0x40, 0x80)\n\n");
+
         code.push_str("      // Function dispatcher\n");
         code.push_str("      switch selector()\n");
 
+        // Every dispatched function needs a distinct selector
+        let mut dispatched: HashMap<u32, &str> = HashMap::new();
+
         for member in members {
             if let quorlin_parser::ContractMember::Function(func) = member {
-                // Skip constructor
-                if func.name == "__init__" {
+                // Skip constructor and internal functions
+                if func.name == "__init__" || is_internal(func) {
                     continue;
                 }
 
                 // Calculate function selector (first 4 bytes of keccak256 hash)
-                let selector = self.calculate_selector(&func.name, &func.params);
+                let selector = function_selector(&func.name, &func.params)?;
+                if let Some(other) = dispatched.insert(selector, &func.name) {
+                    return Err(CodegenError::Error(format!(
+                        "Function selector collision: '{}' and '{}' both have selector 0x{:08x}",
+                        other, func.name, selector
+                    )));
+                }
                 code.push_str(&format!("      case 0x{:08x} {{ {}() }}\n", selector, func.name));
             }
         }
@@ -288,13 +797,28 @@ impl EvmCodegen {
     }
 
     /// Generate function implementations
-    fn generate_functions(&self, members: &[quorlin_parser::ContractMember]) -> CodegenResult<String> {
+    fn generate_functions(&mut self, members: &[quorlin_parser::ContractMember]) -> CodegenResult<String> {
         let mut code = String::new();
+        let mut utilities = String::new();
 
         for member in members {
             if let quorlin_parser::ContractMember::Function(func) = member {
                 // Skip constructor for now
-                if func.name == "__init__" {
+                if func.name == "__init__" || self.inline_functions.contains_key(&func.name) {
+                    continue;
+                }
+
+                self.collect_string_locals(func);
+                self.collect_list_locals(func);
+                self.collect_local_types(func);
+
+                if is_internal(func) {
+                    let function_code = self.generate_internal_function(func, members)?;
+                    if self.is_utility(func) {
+                        utilities.push_str(&function_code);
+                    } else {
+                        code.push_str(&function_code);
+                    }
                     continue;
                 }
 
@@ -305,131 +829,908 @@ impl EvmCodegen {
                 // Each parameter is 32 bytes
                 for (i, param) in func.params.iter().enumerate() {
                     let offset = 4 + (i * 32);
-                    code.push_str(&format!(
-                        "        let {} := calldataload({})\n",
-                        param.name, offset
-                    ));
+                    if let Some(element_size) = dynamic_element_size(&param.type_annotation) {
+                        if param.default.is_some() {
+                            return Err(CodegenError::UnsupportedFeature(format!(
+                                "Default value for dynamic parameter '{}'",
+                                param.name
+                            )));
+                        }
+                        code.push_str(&Self::decode_dynamic_param(&param.name, offset, element_size));
+                        continue;
+                    }
+                    let word = decode_word(&format!("calldataload({})", offset), &param.type_annotation);
+                    match &param.default {
+                        // Callers may omit trailing defaulted arguments, so the
+                        // word is only read when calldata actually covers it
+                        Some(default) => {
+                            let default_code = self.generate_expression(default)?;
+                            code.push_str(&format!("        let {} := {}\n", param.name, default_code));
+                            code.push_str(&format!(
+                                "        if iszero(lt(calldatasize(), {})) {{ {} := {} }}\n",
+                                offset + 32,
+                                param.name,
+                                word
+                            ));
+                        }
+                        None => code.push_str(&format!("        let {} := {}\n", param.name, word)),
+                    }
                 }
 
                 if !func.params.is_empty() {
-                    code.push_str("\n");
+                    code.push('\n');
                 }
 
                 // Function body
+                self.return_type = func.return_type.clone();
+                code.push_str(&self.declare_locals(&func.params, &func.body, 8));
+                code.push_str(&self.begin_state_cache(func, members));
+                code.push_str(&self.generate_validations(func)?);
                 for stmt in &func.body {
                     code.push_str(&self.generate_statement(stmt, 8)?);
                 }
+                self.cached_state.clear();
 
                 code.push_str("      }\n\n");
             }
         }
 
+        if !utilities.is_empty() {
+            code.insert_str(
+                0,
+                &format!(
+                    "      // ========================================\n      // UTILITY FUNCTIONS\n      // Internal helpers that never touch contract state\n      // ========================================\n\n{}",
+                    utilities
+                ),
+            );
+        }
+
         Ok(code)
     }
 
-    /// Generate code for a statement
-    fn generate_statement(&self, stmt: &quorlin_parser::Stmt, indent: usize) -> CodegenResult<String> {
-        use quorlin_parser::{Stmt, Expr};
-
+    /// `a, b = value`. Every element is bound to a temporary before any
+    /// target is written, so `a, b = b, a` swaps. A value other than a tuple
+    /// literal must be a call to an internal function returning a tuple.
+    fn generate_unpacking(&mut self, assign: &quorlin_parser::AssignStmt, indent: usize) -> CodegenResult<String> {
+        let Expr::Tuple(targets) = &assign.target else {
+            unreachable!("only tuple targets are unpacked");
+        };
         let indent_str = " ".repeat(indent);
-        let mut code = String::new();
+        self.unpackings += 1;
+        let temps: Vec<String> = (0..targets.len())
+            .map(|i| format!("unpack_{}_{}", self.unpackings, i))
+            .collect();
 
-        match stmt {
-            Stmt::Return(expr) => {
-                if let Some(e) = expr {
-                    let expr_code = self.generate_expression(e)?;
-                    code.push_str(&format!("{}let ret := {}\n", indent_str, expr_code));
-                    code.push_str(&format!("{}mstore(0, ret)\n", indent_str));
-                    code.push_str(&format!("{}return(0, 32)\n", indent_str));
-                } else {
-                    code.push_str(&format!("{}return(0, 0)\n", indent_str));
+        let mut code = String::new();
+        match &assign.value {
+            Expr::Tuple(items) if items.len() == targets.len() => {
+                for (temp, item) in temps.iter().zip(items) {
+                    let item_code = self.generate_expression(item)?;
+                    code.push_str(&format!("{}let {} := {}\n", indent_str, temp, item_code));
                 }
             }
-            Stmt::Assign(assign) => {
-                let value_code = self.generate_expression(&assign.value)?;
+            Expr::Tuple(items) => {
+                return Err(CodegenError::Error(format!(
+                    "Cannot unpack {} values into {} targets",
+                    items.len(),
+                    targets.len()
+                )));
+            }
+            value => {
+                let Some(arity) = self.tuple_call_arity(value) else {
+                    return Err(CodegenError::UnsupportedFeature(
+                        "Unpacking a value other than a tuple or a call to an internal function returning one".to_string(),
+                    ));
+                };
+                if arity != targets.len() {
+                    return Err(CodegenError::Error(format!(
+                        "Cannot unpack {} values into {} targets",
+                        arity,
+                        targets.len()
+                    )));
+                }
+                let value_code = self.generate_expression(value)?;
+                code.push_str(&format!("{}let {} := {}\n", indent_str, temps.join(", "), value_code));
+            }
+        }
 
-                match &assign.target {
-                    Expr::Ident(name) => {
-                        // Simple identifier assignment
-                        if let Some(&slot) = self.storage_layout.get(name) {
-                            // State variable
-                            code.push_str(&format!("{}sstore({}, {})\n", indent_str, slot, value_code));
-                        } else {
-                            // Local variable
-                            code.push_str(&format!("{}let {} := {}\n", indent_str, name, value_code));
-                        }
-                    }
-                    Expr::Index(target, index) => {
-                        // Indexed assignment: self.balances[addr] = value
-                        // Generate: sstore(keccak256(key, slot), value)
+        for (target, temp) in targets.iter().zip(&temps) {
+            let element = Stmt::Assign(quorlin_parser::AssignStmt {
+                target: target.clone(),
+                value: Expr::Ident(temp.clone()),
+                type_annotation: None,
+            });
+            code.push_str(&self.generate_statement(&element, indent)?);
+        }
+        Ok(code)
+    }
 
-                        if let Expr::Attribute(base, attr) = &**target {
-                            if let Expr::Ident(base_name) = &**base {
-                                if base_name == "self" {
-                                    if let Some(&slot) = self.storage_layout.get(attr) {
-                                        let key_code = self.generate_expression(index)?;
-                                        code.push_str(&format!("{}mstore(0, {})\n", indent_str, key_code));
-                                        code.push_str(&format!("{}mstore(32, {})\n", indent_str, slot));
-                                        code.push_str(&format!("{}sstore(keccak256(0, 64), {})\n", indent_str, value_code));
-                                        return Ok(code);
-                                    }
-                                }
-                            }
-                        } else if let Expr::Index(nested_target, nested_index) = &**target {
-                            // Nested indexing: self.allowances[addr1][addr2] = value
-                            if let Expr::Attribute(base, attr) = &**nested_target {
-                                if let Expr::Ident(base_name) = &**base {
-                                    if base_name == "self" {
-                                        if let Some(&slot) = self.storage_layout.get(attr) {
-                                            let first_key = self.generate_expression(nested_index)?;
-                                            let second_key = self.generate_expression(index)?;
+    /// The contract function that `expr` calls, as `name(...)` or
+    /// `self.name(...)`
+    fn called_function(&self, expr: &Expr) -> Option<&FunctionSymbol> {
+        let Expr::Call(callee, _) = expr else {
+            return None;
+        };
+        let name = match &**callee {
+            Expr::Ident(name) => name,
+            Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => name,
+            _ => return None,
+        };
+        self.functions.get(name)
+    }
 
-                                            // Calculate nested mapping storage location
-                                            code.push_str(&format!("{}// Nested mapping assignment\n", indent_str));
-                                            code.push_str(&format!("{}mstore(0, {})\n", indent_str, first_key));
-                                            code.push_str(&format!("{}mstore(32, {})\n", indent_str, slot));
-                                            code.push_str(&format!("{}let first_slot := keccak256(0, 64)\n", indent_str));
-                                            code.push_str(&format!("{}mstore(0, {})\n", indent_str, second_key));
-                                            code.push_str(&format!("{}mstore(32, first_slot)\n", indent_str));
-                                            code.push_str(&format!("{}sstore(keccak256(0, 64), {})\n", indent_str, value_code));
-                                            return Ok(code);
-                                        }
-                                    }
-                                }
-                            }
-                        }
+    /// The number of values `expr` produces when it calls an internal
+    /// function returning a tuple
+    fn tuple_call_arity(&self, expr: &Expr) -> Option<usize> {
+        match self.called_function(expr)? {
+            FunctionSymbol { internal: true, return_type: Some(Type::Tuple(types)), .. } => Some(types.len()),
+            _ => None,
+        }
+    }
 
-                        return Err(CodegenError::UnsupportedFeature(format!("Indexed assignment {:?}", assign.target)));
-                    }
-                    Expr::Attribute(base, attr) => {
-                        // Assignment to attribute: self.state_var = value
-                        if let Expr::Ident(base_name) = &**base {
-                            if base_name == "self" {
-                                if let Some(&slot) = self.storage_layout.get(attr) {
-                                    code.push_str(&format!("{}sstore({}, {})\n", indent_str, slot, value_code));
-                                    return Ok(code);
-                                }
-                            }
-                        }
-                        return Err(CodegenError::UnsupportedFeature(format!("Assignment target {:?}", assign.target)));
-                    }
-                    _ => {
-                        return Err(CodegenError::UnsupportedFeature(format!("Assignment target {:?}", assign.target)));
-                    }
-                }
-            }
-            Stmt::Require(req) => {
-                let cond = self.generate_expression(&req.condition)?;
-                code.push_str(&format!("{}if iszero({}) {{ revert(0, 0) }}\n", indent_str, cond));
-            }
-            Stmt::Emit(emit) => {
-                // Generate event emission using LOG1
-                // LOG1(offset, size, topic0)
-                // topic0 = event signature
-                // data = abi.encode(args...)
+    /// Whether `func` is an internal helper that never touches contract
+    /// state, through `self` or a bare state variable name
+    fn is_utility(&self, func: &Function) -> bool {
+        if !is_internal(func) || func.references_self() {
+            return false;
+        }
+        let mut reads = StateReadCounter { state: &self.storage_layout, counts: HashMap::new() };
+        reads.visit_function(func);
+        let mut writes = StateWriteCounter::default();
+        writes.visit_function(func);
+        reads.counts.is_empty() && !writes.counts.keys().any(|name| self.storage_layout.contains_key(*name))
+    }
 
-                if let Some(sig) = self.event_signatures.get(&emit.event) {
-                    // Store event arguments in memory starting at position 0
+    /// Record which parameters and locals of `func` hold memory strings
+    fn collect_string_locals(&mut self, func: &Function) {
+        let mut collector = StringLocalCollector {
+            names: func
+                .params
+                .iter()
+                .filter(|p| is_string_type(&p.type_annotation))
+                .map(|p| p.name.clone())
+                .collect(),
+        };
+        for stmt in &func.body {
+            collector.visit_stmt(stmt);
+        }
+        self.string_locals = collector.names;
+    }
+
+    /// Record which parameters and locals of `func` hold memory lists
+    fn collect_list_locals(&mut self, func: &Function) {
+        let mut collector = ListLocalCollector {
+            names: func
+                .params
+                .iter()
+                .filter(|p| matches!(p.type_annotation, Type::List(_)))
+                .map(|p| p.name.clone())
+                .collect(),
+        };
+        for stmt in &func.body {
+            collector.visit_stmt(stmt);
+        }
+        self.list_locals = collector.names;
+    }
+
+    /// Record the declared types of the parameters and locals of `func`
+    fn collect_local_types(&mut self, func: &Function) {
+        let mut collector = LocalTypeCollector {
+            types: func
+                .params
+                .iter()
+                .map(|p| (p.name.clone(), p.type_annotation.clone()))
+                .collect(),
+        };
+        for stmt in &func.body {
+            collector.visit_stmt(stmt);
+        }
+        self.local_types = collector.types;
+    }
+
+    /// Declare every local that `body` assigns, once, ahead of the body. Yul
+    /// forbids redeclaring a visible name, and a name declared inside a loop
+    /// or branch would not outlive it, so assignments then update the one
+    /// variable wherever they appear.
+    fn declare_locals(&mut self, params: &[quorlin_parser::Param], body: &[Stmt], indent: usize) -> String {
+        let mut collector = AssignedLocalCollector::default();
+        for stmt in body {
+            collector.visit_stmt(stmt);
+        }
+        let names: Vec<String> = collector
+            .names
+            .into_iter()
+            .filter(|name| !self.storage_layout.contains_key(name) && !params.iter().any(|p| &p.name == name))
+            .collect();
+        self.declared_locals = names.iter().cloned().chain(params.iter().map(|p| p.name.clone())).collect();
+        if names.is_empty() {
+            return String::new();
+        }
+        format!("{}let {}\n", " ".repeat(indent), names.join(", "))
+    }
+
+    /// Copy a dynamic parameter from calldata into memory once, binding `name`
+    /// to the memory copy (length word followed by the data) so later uses
+    /// never re-read calldata
+    fn decode_dynamic_param(name: &str, head_offset: usize, element_size: usize) -> String {
+        let mut code = String::new();
+        code.push_str(&format!("        // Decode dynamic parameter '{}' into memory\n", name));
+        code.push_str(&format!("        let {} := mload(0x40)\n", name));
+        code.push_str(&format!("        let {}_src := add(4, calldataload({}))\n", name, head_offset));
+        code.push_str(&format!("        let {}_len := calldataload({}_src)\n", name, name));
+        let byte_len = if element_size == 1 {
+            format!("{}_len", name)
+        } else {
+            format!("mul({}_len, {})", name, element_size)
+        };
+        code.push_str(&format!("        mstore({}, {}_len)\n", name, name));
+        code.push_str(&format!(
+            "        calldatacopy(add({}, 32), add({}_src, 32), {})\n",
+            name, name, byte_len
+        ));
+        code.push_str(&format!(
+            "        mstore(0x40, add(add({}, 32), and(add({}, 31), not(31))))\n",
+            name, byte_len
+        ));
+        code
+    }
+
+    /// Generate an internal function, which takes its arguments on the stack
+    /// rather than from calldata
+    fn generate_internal_function(
+        &mut self,
+        func: &Function,
+        members: &[quorlin_parser::ContractMember],
+    ) -> CodegenResult<String> {
+        let params: Vec<_> = func.params.iter().map(|p| p.name.as_str()).collect();
+        let mut code = format!("      function {}({})", func.name, params.join(", "));
+        match &func.return_type {
+            // A tuple is returned as one Yul return variable per element
+            Some(Type::Tuple(types)) => {
+                let rets: Vec<String> = (0..types.len()).map(|i| format!("ret_{}", i)).collect();
+                code.push_str(&format!(" -> {}", rets.join(", ")));
+            }
+            Some(_) => code.push_str(" -> ret"),
+            None => {}
+        }
+        code.push_str(" {\n");
+
+        code.push_str(&self.declare_locals(&func.params, &func.body, 8));
+        code.push_str(&self.begin_state_cache(func, members));
+        code.push_str(&self.generate_validations(func)?);
+        self.in_internal_function = true;
+        let body: CodegenResult<Vec<_>> = func.body.iter().map(|stmt| self.generate_statement(stmt, 8)).collect();
+        self.in_internal_function = false;
+        self.cached_state.clear();
+        for stmt_code in body? {
+            code.push_str(&stmt_code);
+        }
+
+        code.push_str("      }\n\n");
+        Ok(code)
+    }
+
+    /// With optimizations on, load each value-typed state variable that
+    /// `func` reads more than once into a local at entry, so later reads
+    /// reuse it instead of repeating the `sload`. Writes in `func` store
+    /// through the local, keeping it current. Variables written by a function
+    /// that `func` calls, directly or not, are left alone, since the call
+    /// would change them behind the local's back. Nothing is cached when
+    /// `func` may call out of the contract, which could re-enter it and
+    /// change any variable. Locals use a `$` prefix, which Quorlin
+    /// identifiers cannot contain.
+    fn begin_state_cache(&mut self, func: &Function, members: &[quorlin_parser::ContractMember]) -> String {
+        use quorlin_parser::ContractMember;
+
+        if !self.optimize {
+            return String::new();
+        }
+
+        let mut reads = StateReadCounter { state: &self.storage_layout, counts: HashMap::new() };
+        reads.visit_function(func);
+
+        let find = |name: &str| {
+            members.iter().find_map(|member| match member {
+                ContractMember::Function(other) if other.name == name => Some(other),
+                _ => None,
+            })
+        };
+        let mut written_elsewhere = StateWriteCounter::default();
+        let mut callees = CalleeCollector { functions: &self.functions, names: Vec::new(), external: false };
+        callees.visit_function(func);
+        let mut visited = HashSet::new();
+        while let Some(name) = callees.names.pop() {
+            if name == func.name || !visited.insert(name) {
+                continue;
+            }
+            if let Some(callee) = find(name) {
+                written_elsewhere.visit_function(callee);
+                callees.visit_function(callee);
+            }
+        }
+        if callees.external {
+            return String::new();
+        }
+
+        let mut cached: Vec<(usize, &str)> = reads
+            .counts
+            .iter()
+            .filter(|(name, count)| {
+                **count > 1
+                    && !written_elsewhere.counts.contains_key(*name)
+                    && !self.constant_state.contains_key(**name)
+                    && matches!(self.state_types.get(**name), Some(ty @ Type::Simple(_)) if !is_string_type(ty))
+            })
+            .map(|(name, _)| (self.storage_layout[*name], *name))
+            .collect();
+        cached.sort();
+
+        let mut code = String::new();
+        for (slot, name) in cached {
+            let local = format!("$cached_{}", name);
+            code.push_str(&format!("        let {} := sload({})\n", local, slot));
+            self.cached_state.insert(name.to_string(), local);
+        }
+        code
+    }
+
+    /// Generate `left << right` or `left >> right`. Right shifts of signed
+    /// values are arithmetic, keeping the sign
+    fn generate_shift(&self, left: &Expr, op: &BinOp, right: &Expr) -> CodegenResult<String> {
+        let value_code = self.generate_expression(left)?;
+        let shift_code = self.generate_expression(right)?;
+        let (opcode, checked) = match op {
+            BinOp::Shl => ("shl", "checked_shl"),
+            _ if self.is_signed_operand(left) => ("sar", "checked_sar"),
+            _ => ("shr", "checked_shr"),
+        };
+
+        // Constant shift amounts are bounds checked at compile time
+        if let Expr::IntLiteral(amount) = right {
+            return match amount.parse::<u64>() {
+                Ok(bits) if bits < 256 => Ok(format!("{}({}, {})", opcode, shift_code, value_code)),
+                _ => Err(CodegenError::Error(format!(
+                    "Shift amount {} is out of range (must be less than 256)",
+                    amount
+                ))),
+            };
+        }
+        Ok(format!("{}({}, {})", checked, value_code, shift_code))
+    }
+
+    /// Declared type of a local, state variable or function call
+    fn declared_type(&self, expr: &Expr) -> Option<&Type> {
+        match expr {
+            Expr::Ident(name) => self.local_types.get(name).or_else(|| {
+                if self.declared_locals.contains(name) {
+                    None
+                } else {
+                    self.state_types.get(name)
+                }
+            }),
+            Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => {
+                self.state_types.get(name)
+            }
+            Expr::Call(_, _) => self.called_function(expr).and_then(|f| f.return_type.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Bit width of `expr` when it is an unsigned integer narrower than a
+    /// word, from the declared types of the names it uses
+    fn narrow_unsigned_bits(&self, expr: &Expr) -> Option<u32> {
+        match expr {
+            Expr::UnaryOp(UnaryOp::BitNot, operand) => self.narrow_unsigned_bits(operand),
+            Expr::BinOp(left, BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor, right) => {
+                match (self.narrow_unsigned_bits(left), self.narrow_unsigned_bits(right)) {
+                    (Some(l), Some(r)) => Some(l.max(r)),
+                    (bits, None) if matches!(**right, Expr::IntLiteral(_) | Expr::HexLiteral(_)) => bits,
+                    (None, bits) if matches!(**left, Expr::IntLiteral(_) | Expr::HexLiteral(_)) => bits,
+                    _ => None,
+                }
+            }
+            _ => self.declared_type(expr).and_then(narrow_unsigned_bits),
+        }
+    }
+
+    /// Whether `expr` has a signed integer type, from the declared types of
+    /// the locals, state variables and functions it uses
+    fn is_signed_operand(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Ident(_) | Expr::Attribute(_, _) | Expr::Call(_, _) => {
+                self.declared_type(expr).is_some_and(is_signed_type)
+            }
+            Expr::UnaryOp(UnaryOp::Neg, _) => true,
+            Expr::UnaryOp(_, operand) => self.is_signed_operand(operand),
+            Expr::BinOp(left, op, right) => match op {
+                BinOp::Shl | BinOp::Shr | BinOp::Pow => self.is_signed_operand(left),
+                BinOp::Add
+                | BinOp::Sub
+                | BinOp::Mul
+                | BinOp::Div
+                | BinOp::FloorDiv
+                | BinOp::Mod
+                | BinOp::BitAnd
+                | BinOp::BitOr
+                | BinOp::BitXor => self.is_signed_operand(left) || self.is_signed_operand(right),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Hoist the conditional expressions and `@inline` call arguments that
+    /// `stmt` evaluates on every run into `code`, returning `stmt` with each
+    /// replaced by its temporary. Expressions evaluated only on some paths
+    /// (elif conditions, require errors) are left in place, and the right
+    /// operand of `and`/`or` is lowered under a guard on the left one
+    fn lower_temporaries(&mut self, stmt: &Stmt, indent: usize, code: &mut String) -> CodegenResult<Stmt> {
+        Ok(match stmt {
+            Stmt::Assign(assign) => Stmt::Assign(quorlin_parser::AssignStmt {
+                value: self.hoist_expression(&assign.value, indent, code)?,
+                ..assign.clone()
+            }),
+            Stmt::Expr(expr) => Stmt::Expr(self.hoist_expression(expr, indent, code)?),
+            Stmt::Return(Some(value)) => Stmt::Return(Some(self.hoist_expression(value, indent, code)?)),
+            Stmt::Require(req) => Stmt::Require(quorlin_parser::RequireStmt {
+                condition: self.hoist_expression(&req.condition, indent, code)?,
+                ..req.clone()
+            }),
+            Stmt::Emit(emit) => Stmt::Emit(quorlin_parser::EmitStmt {
+                event: emit.event.clone(),
+                args: self.hoist_expressions(&emit.args, indent, code)?,
+            }),
+            Stmt::Raise(raise) => Stmt::Raise(quorlin_parser::RaiseStmt {
+                error: raise.error.clone(),
+                args: self.hoist_expressions(&raise.args, indent, code)?,
+            }),
+            Stmt::If(if_stmt) => Stmt::If(quorlin_parser::IfStmt {
+                condition: self.hoist_expression(&if_stmt.condition, indent, code)?,
+                ..if_stmt.clone()
+            }),
+            _ => stmt.clone(),
+        })
+    }
+
+    fn hoist_expression(&mut self, expr: &Expr, indent: usize, code: &mut String) -> CodegenResult<Expr> {
+        let bound = self.bind_chain_operands(expr, indent, code)?;
+        if let Expr::BinOp(left, op @ (BinOp::And | BinOp::Or), right) = &bound {
+            return self.hoist_short_circuit(left, op, right, indent, code);
+        }
+        let lowered = self.hoist_conditionals(&bound, indent, code)?;
+        self.bind_inline_calls(&lowered, indent, code)
+    }
+
+    /// Bind the operand shared by two adjacent comparisons of a desugared
+    /// chain such as `0 < f(x) <= cap` to a temporary declared in `code`, when
+    /// it contains calls, so they run once rather than once per comparison.
+    /// The right side of `and`/`or` may not run and is bound by
+    /// `hoist_short_circuit` inside its guard
+    fn bind_chain_operands(&mut self, expr: &Expr, indent: usize, code: &mut String) -> CodegenResult<Expr> {
+        let boxed = |this: &mut Self, e: &Expr, code: &mut String| this.bind_chain_operands(e, indent, code).map(Box::new);
+        let all = |this: &mut Self, es: &[Expr], code: &mut String| -> CodegenResult<Vec<Expr>> {
+            es.iter().map(|e| this.bind_chain_operands(e, indent, code)).collect()
+        };
+        Ok(match expr {
+            Expr::BinOp(left, BinOp::And, right) => {
+                let mut left = self.bind_chain_operands(left, indent, code)?;
+                let mut right = (**right).clone();
+                if let (Some(shared), Expr::BinOp(operand, op, _)) = (last_comparison_operand(&mut left), &mut right) {
+                    if is_comparison(op) && *shared == **operand && shared.contains_call() {
+                        let temp = format!("$chain_{}", self.chain_operands);
+                        self.chain_operands += 1;
+                        let value = self.hoist_expression(shared, indent, code)?;
+                        let value_code = self.generate_expression(&value)?;
+                        code.push_str(&format!("{}let {} := {}\n", " ".repeat(indent), temp, value_code));
+                        if let Some(ty) = self.declared_type(shared).cloned() {
+                            self.local_types.insert(temp.clone(), ty);
+                        }
+                        *shared = Expr::Ident(temp.clone());
+                        **operand = Expr::Ident(temp);
+                    }
+                }
+                Expr::BinOp(Box::new(left), BinOp::And, Box::new(right))
+            }
+            Expr::BinOp(left, BinOp::Or, right) => Expr::BinOp(boxed(self, left, code)?, BinOp::Or, right.clone()),
+            Expr::BinOp(left, op, right) => {
+                Expr::BinOp(boxed(self, left, code)?, op.clone(), boxed(self, right, code)?)
+            }
+            Expr::UnaryOp(op, operand) => Expr::UnaryOp(op.clone(), boxed(self, operand, code)?),
+            Expr::Call(func, args) => Expr::Call(func.clone(), all(self, args, code)?),
+            Expr::Attribute(base, attr) => Expr::Attribute(boxed(self, base, code)?, attr.clone()),
+            Expr::KeywordArg(name, value) => Expr::KeywordArg(name.clone(), boxed(self, value, code)?),
+            Expr::Index(base, index) => Expr::Index(boxed(self, base, code)?, boxed(self, index, code)?),
+            Expr::List(items) => Expr::List(all(self, items, code)?),
+            Expr::Tuple(items) => Expr::Tuple(all(self, items, code)?),
+            _ => expr.clone(),
+        })
+    }
+
+    /// Lower `left and right` (or `left or right`). When `right` needs
+    /// temporaries of its own they are declared inside an `if` on the value of
+    /// `left`, so they only run when `right` would be evaluated
+    fn hoist_short_circuit(
+        &mut self,
+        left: &Expr,
+        op: &BinOp,
+        right: &Expr,
+        indent: usize,
+        code: &mut String,
+    ) -> CodegenResult<Expr> {
+        let left = self.hoist_expression(left, indent, code)?;
+        let mut guarded = String::new();
+        let right = self.hoist_expression(right, indent + 4, &mut guarded)?;
+        if guarded.is_empty() {
+            return Ok(Expr::BinOp(Box::new(left), op.clone(), Box::new(right)));
+        }
+
+        let temp = format!("$cond_{}", self.conditionals);
+        self.conditionals += 1;
+        let indent_str = " ".repeat(indent);
+        let test = match op {
+            BinOp::Or => format!("iszero({})", temp),
+            _ => temp.clone(),
+        };
+        code.push_str(&format!("{}let {} := {}\n", indent_str, temp, self.generate_expression(&left)?));
+        code.push_str(&format!("{}if {} {{\n", indent_str, test));
+        code.push_str(&guarded);
+        code.push_str(&format!("{}    {} := {}\n", indent_str, temp, self.generate_expression(&right)?));
+        code.push_str(&format!("{}}}\n", indent_str));
+        Ok(Expr::Ident(temp))
+    }
+
+    fn hoist_expressions(&mut self, exprs: &[Expr], indent: usize, code: &mut String) -> CodegenResult<Vec<Expr>> {
+        exprs.iter().map(|e| self.hoist_expression(e, indent, code)).collect()
+    }
+
+    /// Expand each `@inline` call in `expr`, first binding every argument
+    /// the body does not use exactly once, unless it is a plain name or
+    /// literal, to a temporary declared in `code`, so it is evaluated once
+    /// however often the body uses it. The right side of `and`/`or` may not
+    /// run and is left to `generate_inline_call`
+    fn bind_inline_calls(&mut self, expr: &Expr, indent: usize, code: &mut String) -> CodegenResult<Expr> {
+        let boxed = |this: &mut Self, e: &Expr, code: &mut String| this.bind_inline_calls(e, indent, code).map(Box::new);
+        let all = |this: &mut Self, es: &[Expr], code: &mut String| -> CodegenResult<Vec<Expr>> {
+            es.iter().map(|e| this.bind_inline_calls(e, indent, code)).collect()
+        };
+        Ok(match expr {
+            Expr::Call(func, args) => {
+                let args = all(self, args, code)?;
+                let Some(name) = self.inline_callee(expr) else {
+                    return Ok(Expr::Call(func.clone(), args));
+                };
+                let (params, body) = self.inline_signature(name, &args)?;
+                let mut uses = IdentCounter::default();
+                uses.visit_expr(&body);
+
+                let mut bound = Vec::with_capacity(args.len());
+                for (param, arg) in params.iter().zip(args) {
+                    if is_inline_operand(&arg) || uses.counts.get(param.name.as_str()) == Some(&1) {
+                        bound.push(arg);
+                        continue;
+                    }
+                    let temp = format!("$arg_{}", self.inline_args);
+                    self.inline_args += 1;
+                    let arg_code = self.generate_expression(&arg)?;
+                    code.push_str(&format!("{}let {} := {}\n", " ".repeat(indent), temp, arg_code));
+                    // The temporary has the parameter's type, e.g. for signed division
+                    self.local_types.insert(temp.clone(), param.type_annotation.clone());
+                    bound.push(Expr::Ident(temp));
+                }
+                let bindings: HashMap<&str, &Expr> = params.iter().map(|p| p.name.as_str()).zip(&bound).collect();
+                // The body may call further `@inline` functions; cycles were
+                // rejected up front
+                self.bind_inline_calls(&substitute_params(&body, &bindings), indent, code)?
+            }
+            Expr::BinOp(left, op @ (BinOp::And | BinOp::Or), right) => {
+                Expr::BinOp(boxed(self, left, code)?, op.clone(), right.clone())
+            }
+            Expr::BinOp(left, op, right) => {
+                Expr::BinOp(boxed(self, left, code)?, op.clone(), boxed(self, right, code)?)
+            }
+            Expr::UnaryOp(op, operand) => Expr::UnaryOp(op.clone(), boxed(self, operand, code)?),
+            Expr::Attribute(base, attr) => Expr::Attribute(boxed(self, base, code)?, attr.clone()),
+            Expr::KeywordArg(name, value) => Expr::KeywordArg(name.clone(), boxed(self, value, code)?),
+            Expr::Index(base, index) => Expr::Index(boxed(self, base, code)?, boxed(self, index, code)?),
+            Expr::List(items) => Expr::List(all(self, items, code)?),
+            Expr::Tuple(items) => Expr::Tuple(all(self, items, code)?),
+            _ => expr.clone(),
+        })
+    }
+
+    /// Replace each `body if test else orelse` in `expr` with a temporary
+    /// set by a `switch` written to `code`, so only the taken branch is
+    /// evaluated. Each branch is lowered inside its own case
+    fn hoist_conditionals(&mut self, expr: &Expr, indent: usize, code: &mut String) -> CodegenResult<Expr> {
+        let boxed = |this: &mut Self, e: &Expr, code: &mut String| this.hoist_conditionals(e, indent, code).map(Box::new);
+        Ok(match expr {
+            Expr::IfExp { test, body, orelse } => {
+                let test = self.hoist_expression(test, indent, code)?;
+                let test_code = self.generate_expression(&test)?;
+                let temp = format!("$cond_{}", self.conditionals);
+                self.conditionals += 1;
+
+                let indent_str = " ".repeat(indent);
+                code.push_str(&format!("{}let {} := 0\n", indent_str, temp));
+                code.push_str(&format!("{}switch {}\n", indent_str, test_code));
+                for (label, branch) in [("case 0", orelse), ("default", body)] {
+                    code.push_str(&format!("{}{} {{\n", indent_str, label));
+                    let value = self.hoist_expression(branch, indent + 4, code)?;
+                    let value_code = self.generate_expression(&value)?;
+                    code.push_str(&format!("{}    {} := {}\n", indent_str, temp, value_code));
+                    code.push_str(&format!("{}}}\n", indent_str));
+                }
+                Expr::Ident(temp)
+            }
+            // The right side may not run; see `hoist_short_circuit`
+            Expr::BinOp(left, op @ (BinOp::And | BinOp::Or), right) => {
+                Expr::BinOp(boxed(self, left, code)?, op.clone(), right.clone())
+            }
+            Expr::BinOp(left, op, right) => {
+                Expr::BinOp(boxed(self, left, code)?, op.clone(), boxed(self, right, code)?)
+            }
+            Expr::UnaryOp(op, operand) => Expr::UnaryOp(op.clone(), boxed(self, operand, code)?),
+            Expr::Call(func, args) => Expr::Call(func.clone(), self.hoist_all(args, indent, code)?),
+            Expr::Attribute(base, attr) => Expr::Attribute(boxed(self, base, code)?, attr.clone()),
+            Expr::KeywordArg(name, value) => Expr::KeywordArg(name.clone(), boxed(self, value, code)?),
+            Expr::Index(base, index) => Expr::Index(boxed(self, base, code)?, boxed(self, index, code)?),
+            Expr::List(items) => Expr::List(self.hoist_all(items, indent, code)?),
+            Expr::Tuple(items) => Expr::Tuple(self.hoist_all(items, indent, code)?),
+            _ => expr.clone(),
+        })
+    }
+
+    fn hoist_all(&mut self, exprs: &[Expr], indent: usize, code: &mut String) -> CodegenResult<Vec<Expr>> {
+        exprs.iter().map(|e| self.hoist_conditionals(e, indent, code)).collect()
+    }
+
+    /// Store `value_code` to the state variable `name`, keeping its cached
+    /// local (if any) in step
+    fn store_state(&self, name: &str, slot: usize, value_code: &str, indent_str: &str) -> String {
+        match self.cached_state.get(name) {
+            Some(local) => format!(
+                "{}{} := {}\n{}sstore({}, {})\n",
+                indent_str, local, value_code, indent_str, slot, local
+            ),
+            None => format!("{}sstore({}, {})\n", indent_str, slot, value_code),
+        }
+    }
+
+    /// Generate the `require` for each `@validate` constraint of `func`, run
+    /// at function entry
+    fn generate_validations(&mut self, func: &Function) -> CodegenResult<String> {
+        let mut code = String::new();
+        for constraint in func.validations() {
+            let condition = quorlin_parser::parse_expression(&constraint).map_err(|e| {
+                CodegenError::Error(format!("Invalid @validate constraint '{}' on '{}': {}", constraint, func.name, e))
+            })?;
+            let require = Stmt::Require(quorlin_parser::RequireStmt {
+                condition,
+                message: Some(Expr::StringLiteral(format!("validate: {}", constraint))),
+                error: None,
+            });
+            code.push_str(&self.generate_statement(&require, 8)?);
+        }
+        Ok(code)
+    }
+
+    /// Generate code for a statement
+    fn generate_statement(&mut self, stmt: &quorlin_parser::Stmt, indent: usize) -> CodegenResult<String> {
+        use quorlin_parser::{Stmt, Expr};
+
+        let indent_str = " ".repeat(indent);
+        let mut code = String::new();
+
+        let lowered = self.lower_temporaries(stmt, indent, &mut code)?;
+        if !code.is_empty() {
+            code.push_str(&self.generate_statement(&lowered, indent)?);
+            return Ok(code);
+        }
+
+        match stmt {
+            Stmt::Return(Some(Expr::Tuple(items))) if self.in_internal_function => {
+                for (i, item) in items.iter().enumerate() {
+                    let item_code = self.generate_expression(item)?;
+                    code.push_str(&format!("{}ret_{} := {}\n", indent_str, i, item_code));
+                }
+                code.push_str(&format!("{}leave\n", indent_str));
+            }
+            Stmt::Return(Some(value)) if self.in_internal_function && self.tuple_call_arity(value).is_some() => {
+                // Forward every value of an internal tuple-returning call
+                let rets: Vec<String> = (0..self.tuple_call_arity(value).unwrap_or(0)).map(|i| format!("ret_{}", i)).collect();
+                let value_code = self.generate_expression(value)?;
+                code.push_str(&format!("{}{} := {}\n", indent_str, rets.join(", "), value_code));
+                code.push_str(&format!("{}leave\n", indent_str));
+            }
+            Stmt::Return(expr) if self.in_internal_function => {
+                if let Some(e) = expr {
+                    let expr_code = self.generate_expression(e)?;
+                    code.push_str(&format!("{}ret := {}\n", indent_str, expr_code));
+                }
+                code.push_str(&format!("{}leave\n", indent_str));
+            }
+            Stmt::Return(Some(value)) if matches!(value, Expr::Tuple(_)) || matches!(self.return_type, Some(Type::Tuple(_))) => {
+                // One ABI word per element. Every element is evaluated before
+                // any is stored, since evaluating one may use scratch memory.
+                let count = match value {
+                    Expr::Tuple(items) => {
+                        for (i, item) in items.iter().enumerate() {
+                            let item_code = self.generate_expression(item)?;
+                            code.push_str(&format!("{}let ret_{} := {}\n", indent_str, i, item_code));
+                        }
+                        items.len()
+                    }
+                    // A call to an internal function returning a tuple yields
+                    // all of its values at once
+                    value => {
+                        let Some(count) = self.tuple_call_arity(value) else {
+                            return Err(CodegenError::UnsupportedFeature(
+                                "Returning a tuple other than a tuple literal or a call to an internal function returning one"
+                                    .to_string(),
+                            ));
+                        };
+                        let rets: Vec<String> = (0..count).map(|i| format!("ret_{}", i)).collect();
+                        let value_code = self.generate_expression(value)?;
+                        code.push_str(&format!("{}let {} := {}\n", indent_str, rets.join(", "), value_code));
+                        count
+                    }
+                };
+                let types: Vec<Option<Type>> = match &self.return_type {
+                    Some(Type::Tuple(types)) if types.len() == count => types.iter().cloned().map(Some).collect(),
+                    _ => vec![None; count],
+                };
+                for (i, ty) in types.iter().enumerate() {
+                    let value = format!("ret_{}", i);
+                    let encoded = match ty {
+                        Some(ty) => abi_encode_word(&value, ty),
+                        None => value,
+                    };
+                    code.push_str(&format!("{}mstore({}, {})\n", indent_str, i * 32, encoded));
+                }
+                code.push_str(&format!("{}return(0, {})\n", indent_str, count * 32));
+            }
+            Stmt::Return(expr) => {
+                if let Some(e) = expr {
+                    let expr_code = self.generate_expression(e)?;
+                    code.push_str(&format!("{}let ret := {}\n", indent_str, expr_code));
+                    let encoded = match &self.return_type {
+                        Some(ty) => abi_encode_word("ret", ty),
+                        None => "ret".to_string(),
+                    };
+                    code.push_str(&format!("{}mstore(0, {})\n", indent_str, encoded));
+                    code.push_str(&format!("{}return(0, 32)\n", indent_str));
+                } else {
+                    code.push_str(&format!("{}return(0, 0)\n", indent_str));
+                }
+            }
+            Stmt::Assign(assign) if matches!(assign.target, Expr::Tuple(_)) => {
+                code.push_str(&self.generate_unpacking(assign, indent)?);
+            }
+            Stmt::Assign(assign) => {
+                let value_code = self.generate_expression(&assign.value)?;
+
+                match &assign.target {
+                    Expr::Ident(name) => {
+                        // Simple identifier assignment
+                        if let Some(&slot) = self.storage_layout.get(name) {
+                            // State variable
+                            code.push_str(&self.store_state(name, slot, &value_code, &indent_str));
+                        } else if self.declared_locals.contains(name) {
+                            code.push_str(&format!("{}{} := {}\n", indent_str, name, value_code));
+                        } else {
+                            return Err(CodegenError::Error(format!("Local '{}' was not declared", name)));
+                        }
+                    }
+                    Expr::Index(target, index) => {
+                        // Indexed assignment: self.balances[addr] = value
+                        // Generate: sstore(keccak256(key, slot), value)
+
+                        if let Expr::Attribute(base, attr) = &**target {
+                            if let Expr::Ident(base_name) = &**base {
+                                if base_name == "self" {
+                                    if let Some(&slot) = self.storage_layout.get(attr) {
+                                        let key_code = self.generate_expression(index)?;
+                                        code.push_str(&format!("{}mstore(0, {})\n", indent_str, key_code));
+                                        code.push_str(&format!("{}mstore(32, {})\n", indent_str, slot));
+                                        code.push_str(&format!("{}sstore(keccak256(0, 64), {})\n", indent_str, value_code));
+                                        return Ok(code);
+                                    }
+                                }
+                            }
+                        } else if let Expr::Index(nested_target, nested_index) = &**target {
+                            // Nested indexing: self.allowances[addr1][addr2] = value
+                            if let Expr::Attribute(base, attr) = &**nested_target {
+                                if let Expr::Ident(base_name) = &**base {
+                                    if base_name == "self" {
+                                        if let Some(&slot) = self.storage_layout.get(attr) {
+                                            let first_key = self.generate_expression(nested_index)?;
+                                            let second_key = self.generate_expression(index)?;
+
+                                            // Calculate nested mapping storage location
+                                            code.push_str(&format!("{}// Nested mapping assignment\n", indent_str));
+                                            code.push_str(&format!("{}mstore(0, {})\n", indent_str, first_key));
+                                            code.push_str(&format!("{}mstore(32, {})\n", indent_str, slot));
+                                            code.push_str(&format!("{}let first_slot := keccak256(0, 64)\n", indent_str));
+                                            code.push_str(&format!("{}mstore(0, {})\n", indent_str, second_key));
+                                            code.push_str(&format!("{}mstore(32, first_slot)\n", indent_str));
+                                            code.push_str(&format!("{}sstore(keccak256(0, 64), {})\n", indent_str, value_code));
+                                            return Ok(code);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        return Err(CodegenError::UnsupportedFeature(format!("Indexed assignment {:?}", assign.target)));
+                    }
+                    Expr::Attribute(base, attr) => {
+                        // Assignment to attribute: self.state_var = value
+                        if let Expr::Ident(base_name) = &**base {
+                            if base_name == "self" {
+                                if let Some(&slot) = self.storage_layout.get(attr) {
+                                    code.push_str(&self.store_state(attr, slot, &value_code, &indent_str));
+                                    return Ok(code);
+                                }
+                            }
+                        }
+                        return Err(CodegenError::UnsupportedFeature(format!("Assignment target {:?}", assign.target)));
+                    }
+                    _ => {
+                        return Err(CodegenError::UnsupportedFeature(format!("Assignment target {:?}", assign.target)));
+                    }
+                }
+            }
+            Stmt::Require(req) => {
+                // Split conjunctions into sequential guards so each operand is
+                // only evaluated once the previous ones have passed
+                let mut conjuncts = Vec::new();
+                Self::flatten_conjunction(&req.condition, &mut conjuncts);
+                for conjunct in conjuncts {
+                    let cond = self.generate_expression(conjunct)?;
+                    match &req.error {
+                        Some(error) => {
+                            code.push_str(&format!("{}if iszero({}) {{\n", indent_str, cond));
+                            code.push_str(&self.generate_error_revert(error, indent + 4)?);
+                            code.push_str(&format!("{}}}\n", indent_str));
+                        }
+                        None => match &req.message {
+                            Some(message) => {
+                                let message = match message {
+                                    Expr::FString(parts) => self.fold_fstring(parts)?,
+                                    Expr::StringLiteral(text) => text.clone(),
+                                    other => {
+                                        return Err(CodegenError::UnsupportedFeature(format!(
+                                            "Require message {:?}",
+                                            other
+                                        )))
+                                    }
+                                };
+                                code.push_str(&format!("{}if iszero({}) {{\n", indent_str, cond));
+                                let inner = format!("{}    ", indent_str);
+                                code.push_str(&self.generate_reason_revert(&message, &inner));
+                                code.push_str(&format!("{}}}\n", indent_str));
+                            }
+                            None => {
+                                code.push_str(&format!("{}if iszero({}) {{ revert(0, 0) }}\n", indent_str, cond));
+                            }
+                        },
+                    }
+                }
+            }
+            Stmt::Raise(raise) => {
+                // Own block so the argument bindings don't clash with later raises
+                code.push_str(&format!("{}{{\n", indent_str));
+                code.push_str(&self.generate_error_revert(raise, indent + 4)?);
+                code.push_str(&format!("{}}}\n", indent_str));
+            }
+            Stmt::Emit(emit) => {
+                // Generate event emission using LOG1
+                // LOG1(offset, size, topic0)
+                // topic0 = event signature
+                // data = abi.encode(args...)
+
+                if let Some(sig) = self.event_signatures.get(&emit.event) {
+                    // Store event arguments in memory starting at position 0
                     let mut mem_offset = 0;
                     for arg in &emit.args {
                         let arg_code = self.generate_expression(arg)?;
@@ -447,6 +1748,19 @@ impl EvmCodegen {
             Stmt::Pass => {
                 code.push_str(&format!("{}// pass\n", indent_str));
             }
+            Stmt::Expr(expr @ Expr::Call(func, _)) => {
+                let call_code = self.generate_expression(expr)?;
+                let name = match &**func {
+                    Expr::Ident(name) | Expr::Attribute(_, name) => Some(name),
+                    _ => None,
+                };
+                // Values left on the stack must be discarded explicitly
+                if name.and_then(|n| self.functions.get(n)).is_some_and(|f| f.return_type.is_none()) {
+                    code.push_str(&format!("{}{}\n", indent_str, call_code));
+                } else {
+                    code.push_str(&format!("{}pop({})\n", indent_str, call_code));
+                }
+            }
             Stmt::If(if_stmt) => {
                 // Generate if statement
                 let cond_code = self.generate_expression(&if_stmt.condition)?;
@@ -482,6 +1796,7 @@ impl EvmCodegen {
             Stmt::While(while_stmt) => {
                 // Generate while loop (using Yul's for loop with no init/post)
                 let cond_code = self.generate_expression(&while_stmt.condition)?;
+                self.enter_loop(while_stmt.else_branch.is_some(), &indent_str, &mut code);
                 code.push_str(&format!("{}for {{}} {} {{}}\n", indent_str, cond_code));
                 code.push_str(&format!("{}{{\n", indent_str));
 
@@ -490,11 +1805,26 @@ impl EvmCodegen {
                 }
 
                 code.push_str(&format!("{}}}\n", indent_str));
+                self.exit_loop(while_stmt.else_branch.as_deref(), indent, &mut code)?;
             }
             Stmt::For(for_stmt) => {
                 // Generate for loop: for i in range(n):  →  Yul for loop
                 // ✅ Properly implemented for loop code generation
 
+                // Mappings have no key enumeration on EVM
+                if let Expr::Attribute(base, attr) = &for_stmt.iterable {
+                    if matches!(&**base, Expr::Ident(b) if b == "self")
+                        && matches!(self.state_types.get(attr), Some(Type::Mapping(_, _)))
+                    {
+                        return Err(CodegenError::UnsupportedFeature(format!(
+                            "Cannot iterate over mapping 'self.{}': EVM storage does not record \
+                             mapping keys. Keep an auxiliary list of keys alongside the mapping \
+                             and iterate over that instead",
+                            attr
+                        )));
+                    }
+                }
+
                 // Check if iterable is range() call
                 if let Expr::Call(func, args) = &for_stmt.iterable {
                     if let Expr::Ident(func_name) = &**func {
@@ -530,9 +1860,12 @@ impl EvmCodegen {
                             };
 
                             // Generate Yul for loop
+                            // A loop variable also assigned elsewhere is a declared local
+                            let init = if self.declared_locals.contains(&for_stmt.variable) { "" } else { "let " };
+                            self.enter_loop(for_stmt.else_branch.is_some(), &indent_str, &mut code);
                             code.push_str(&format!(
-                                "{}for {{ let {} := {} }} lt({}, {}) {{ {} := add({}, {}) }}\n",
-                                indent_str, for_stmt.variable, start, for_stmt.variable, end,
+                                "{}for {{ {}{} := {} }} lt({}, {}) {{ {} := add({}, {}) }}\n",
+                                indent_str, init, for_stmt.variable, start, for_stmt.variable, end,
                                 for_stmt.variable, for_stmt.variable, step
                             ));
                             code.push_str(&format!("{}{{\n", indent_str));
@@ -543,6 +1876,7 @@ impl EvmCodegen {
                             }
 
                             code.push_str(&format!("{}}}\n", indent_str));
+                            self.exit_loop(for_stmt.else_branch.as_deref(), indent, &mut code)?;
                         } else {
                             return Err(CodegenError::UnsupportedFeature(
                                 format!("For loop over {} not supported (use range())", func_name)
@@ -559,6 +1893,15 @@ impl EvmCodegen {
                     ));
                 }
             }
+            Stmt::Break => {
+                if let Some(Some(flag)) = self.break_flags.last() {
+                    code.push_str(&format!("{}{} := 0\n", indent_str, flag));
+                }
+                code.push_str(&format!("{}break\n", indent_str));
+            }
+            Stmt::Continue => {
+                code.push_str(&format!("{}continue\n", indent_str));
+            }
             _ => {
                 return Err(CodegenError::UnsupportedFeature(format!("{:?}", stmt)));
             }
@@ -567,6 +1910,34 @@ impl EvmCodegen {
         Ok(code)
     }
 
+    /// Enter a loop; one with an `else` block gets a "no break" flag that
+    /// `break` statements in its body clear
+    fn enter_loop(&mut self, has_else: bool, indent_str: &str, code: &mut String) {
+        let flag = has_else.then(|| {
+            self.flagged_loops += 1;
+            format!("no_break_{}", self.flagged_loops)
+        });
+        if let Some(flag) = &flag {
+            code.push_str(&format!("{}let {} := 1\n", indent_str, flag));
+        }
+        self.break_flags.push(flag);
+    }
+
+    /// Leave the innermost loop, running its `else` block only when the
+    /// loop finished without `break`
+    fn exit_loop(&mut self, else_branch: Option<&[Stmt]>, indent: usize, code: &mut String) -> CodegenResult<()> {
+        let flag = self.break_flags.pop().flatten();
+        if let (Some(flag), Some(else_body)) = (flag, else_branch) {
+            let indent_str = " ".repeat(indent);
+            code.push_str(&format!("{}if {} {{\n", indent_str, flag));
+            for stmt in else_body {
+                code.push_str(&self.generate_statement(stmt, indent + 2)?);
+            }
+            code.push_str(&format!("{}}}\n", indent_str));
+        }
+        Ok(())
+    }
+
     /// Generate code for an expression
     fn generate_expression(&self, expr: &quorlin_parser::Expr) -> CodegenResult<String> {
         use quorlin_parser::{Expr, BinOp};
@@ -591,44 +1962,91 @@ impl EvmCodegen {
             }
             Expr::Ident(name) => {
                 // Check if it's a state variable
-                if let Some(&slot) = self.storage_layout.get(name) {
+                if let Some(literal) = self.constant_state.get(name).or_else(|| self.constants.get(name)) {
+                    Ok(literal.clone())
+                } else if let Some(local) = self.cached_state.get(name) {
+                    Ok(local.clone())
+                } else if let Some(&slot) = self.storage_layout.get(name) {
                     Ok(format!("sload({})", slot))
                 } else {
                     // Assume it's a local variable or parameter
                     Ok(name.clone())
                 }
             }
+            Expr::BinOp(left, BinOp::Add, right)
+                if self.is_string_operand(left)? || self.is_string_operand(right)? =>
+            {
+                Ok(format!(
+                    "concat_memory({}, {})",
+                    self.generate_memory_string(left)?,
+                    self.generate_memory_string(right)?
+                ))
+            }
+            // Literals fit in a word and compare like one; locals and
+            // concatenations are memory pointers
+            Expr::BinOp(left, op, right)
+                if !matches!(op, BinOp::In)
+                    && [left, right].into_iter().any(|operand| {
+                    !matches!(**operand, Expr::StringLiteral(_) | Expr::FString(_))
+                        && is_memory_string(operand, &self.string_locals)
+                }) =>
+            {
+                Err(CodegenError::UnsupportedFeature(format!(
+                    "operator {:?} on str/bytes operands (only + concatenation is supported)",
+                    op
+                )))
+            }
             Expr::BinOp(left, op, right) => {
-                let left_code = self.generate_expression(left)?;
-                let right_code = self.generate_expression(right)?;
+                let operands = || -> CodegenResult<(String, String)> {
+                    Ok((self.generate_expression(left)?, self.generate_expression(right)?))
+                };
 
                 // Use checked arithmetic for overflow-prone operations
                 let op_code = match op {
+                    BinOp::Shl | BinOp::Shr => return self.generate_shift(left, op, right),
+                    BinOp::In => return self.generate_membership(left, right),
                     BinOp::Add => "checked_add",  // ✅ Overflow protected
                     BinOp::Sub => "checked_sub",  // ✅ Underflow protected
                     BinOp::Mul => "checked_mul",  // ✅ Overflow protected
-                    BinOp::Div => "checked_div",  // ✅ Division by zero protected
+                    // EVM division truncates, which is floor division for unsigned values
+                    BinOp::FloorDiv if self.is_signed_operand(left) || self.is_signed_operand(right) => "checked_floor_sdiv",
+                    BinOp::Div | BinOp::FloorDiv => "checked_div",  // ✅ Division by zero protected
                     BinOp::Mod => "checked_mod",  // ✅ Modulo by zero protected
-                    // FloorDiv removed - use regular div
-                    BinOp::Pow => "exp",  // Exponentiation
+                    BinOp::Pow => "checked_exp",  // ✅ Overflow protected
                     BinOp::Eq => "eq",
-                    BinOp::NotEq => "iszero(eq",
                     BinOp::Lt => "lt",
                     BinOp::Gt => "gt",
-                    BinOp::LtEq => "iszero(gt",
-                    BinOp::GtEq => "iszero(lt",
-                    BinOp::And => "and",
-                    BinOp::Or => "or",
-                    _ => return Err(CodegenError::UnsupportedFeature(format!("BinOp {:?}", op))),
+                    BinOp::And | BinOp::BitAnd => "and",
+                    BinOp::Or | BinOp::BitOr => "or",
+                    BinOp::BitXor => "xor",
+                    // Negated comparisons wrap their complement in iszero
+                    BinOp::NotEq => return operands().map(|(l, r)| format!("iszero(eq({}, {}))", l, r)),
+                    BinOp::LtEq => return operands().map(|(l, r)| format!("iszero(gt({}, {}))", l, r)),
+                    BinOp::GtEq => return operands().map(|(l, r)| format!("iszero(lt({}, {}))", l, r)),
                 };
 
-                if matches!(op, BinOp::NotEq | BinOp::LtEq | BinOp::GtEq) {
-                    Ok(format!("{}({}, {}))", op_code, left_code, right_code))
-                } else {
-                    Ok(format!("{}({}, {})", op_code, left_code, right_code))
-                }
+                let (left_code, right_code) = operands()?;
+                Ok(format!("{}({}, {})", op_code, left_code, right_code))
             }
             Expr::Call(func, args) => {
+                // Calls to the contract's own functions may name arguments or
+                // leave defaulted ones out; put them in parameter order
+                let callee = match &**func {
+                    Expr::Ident(name) => Some(name),
+                    Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => Some(name),
+                    _ => None,
+                };
+                let bound;
+                let args = match callee.and_then(|name| Some((name, self.functions.get(name)?))) {
+                    Some((name, symbol))
+                        if args.len() < symbol.params.len() || args.iter().any(|a| matches!(a, Expr::KeywordArg(..))) =>
+                    {
+                        bound = quorlin_parser::bind_args(name, &symbol.params, args).map_err(CodegenError::Error)?;
+                        &bound
+                    }
+                    _ => args,
+                };
+
                 // Generate argument code
                 let arg_codes: Vec<_> = args
                     .iter()
@@ -678,17 +2096,16 @@ impl EvmCodegen {
                                 Err(CodegenError::UnsupportedFeature("safe_div requires 2 arguments".to_string()))
                             }
                         }
-                        _ => {
-                            // Regular function call
-                            Ok(format!("{}({})", func_name, arg_codes.join(", ")))
-                        }
+                        _ => self
+                            .generate_inline_call(func_name, args)
+                            .unwrap_or_else(|| self.generate_internal_call(func_name, &arg_codes)),
                     }
                 } else if let Expr::Attribute(base, method_name) = &**func {
                     // Handle method calls like self.method_name()
                     if let Expr::Ident(base_name) = &**base {
                         if base_name == "self" {
-                            // Internal function call
-                            Ok(format!("{}({})", method_name, arg_codes.join(", ")))
+                            self.generate_inline_call(method_name, args)
+                                .unwrap_or_else(|| self.generate_internal_call(method_name, &arg_codes))
                         } else {
                             Err(CodegenError::UnsupportedFeature(format!("Method calls on {}", base_name)))
                         }
@@ -712,6 +2129,12 @@ impl EvmCodegen {
                         return Ok("number()".to_string());
                     } else if base_name == "self" {
                         // self.state_variable - look up storage slot and load it
+                        if let Some(literal) = self.constant_state.get(attr).or_else(|| self.constants.get(attr)) {
+                            return Ok(literal.clone());
+                        }
+                        if let Some(local) = self.cached_state.get(attr) {
+                            return Ok(local.clone());
+                        }
                         if let Some(&slot) = self.storage_layout.get(attr) {
                             return Ok(format!("sload({})", slot));
                         }
@@ -750,58 +2173,547 @@ impl EvmCodegen {
                     }
                 }
 
+                // Local lists and tuples live in memory
+                if is_memory_list(target, &self.list_locals) {
+                    let list_code = self.generate_expression(target)?;
+                    let index_code = self.generate_expression(index)?;
+                    return Ok(format!("list_at({}, {})", list_code, index_code));
+                }
+
                 Err(CodegenError::UnsupportedFeature(format!("Index {:?}", expr)))
             }
             Expr::UnaryOp(op, expr) => {
                 use quorlin_parser::UnaryOp;
-                let expr_code = self.generate_expression(&**expr)?;
+                let expr_code = self.generate_expression(expr)?;
 
                 match op {
                     UnaryOp::Neg => Ok(format!("sub(0, {})", expr_code)),
                     UnaryOp::Pos => Ok(expr_code), // Unary + is a no-op
                     UnaryOp::Not => Ok(format!("iszero({})", expr_code)),
+                    // Clear the bits above a narrow unsigned type so the
+                    // result stays in its range
+                    UnaryOp::BitNot => match self.narrow_unsigned_bits(expr) {
+                        Some(bits) => Ok(format!("and(not({}), 0x{})", expr_code, "f".repeat(bits as usize / 4))),
+                        None => Ok(format!("not({})", expr_code)),
+                    },
                 }
             }
-            Expr::List(_items) => {
-                // Not supported in Yul efficiently without memory management
-                // Simplified: return 0
-                Ok("0".to_string())
+            Expr::List(items) | Expr::Tuple(items) => {
+                // Allocate the list, then store each element in turn
+                let mut code = format!("memory_list({})", items.len());
+                for (i, item) in items.iter().enumerate() {
+                    let item_code = self.generate_expression(item)?;
+                    code = format!("list_set({}, {}, {})", code, i, item_code);
+                }
+                Ok(code)
             }
-            Expr::Tuple(_items) => {
-                // Not supported in Yul efficiently
-                // Simplified: return 0
-                Ok("0".to_string())
+            // Statements lower the conditionals they evaluate unconditionally
+            // before reaching here; see `lower_temporaries`
+            Expr::IfExp { .. } => Err(CodegenError::UnsupportedFeature(
+                "conditional expression in a loop condition, elif condition, require error or nested and/or operand"
+                    .to_string(),
+            )),
+            Expr::ListComp { .. } => Err(CodegenError::UnsupportedFeature(
+                "List comprehensions; build the list with a for loop".to_string(),
+            )),
+            Expr::FString(parts) => self.generate_expression(&Expr::StringLiteral(self.fold_fstring(parts)?)),
+            _ => Err(CodegenError::UnsupportedFeature(format!("Expression {:?}", expr))),
+        }
+    }
+
+    /// Collect the operands of a (possibly nested) `and` chain, left to right
+    fn flatten_conjunction<'a>(expr: &'a quorlin_parser::Expr, out: &mut Vec<&'a quorlin_parser::Expr>) {
+        use quorlin_parser::{BinOp, Expr};
+
+        if let Expr::BinOp(left, BinOp::And, right) = expr {
+            Self::flatten_conjunction(left, out);
+            Self::flatten_conjunction(right, out);
+        } else {
+            out.push(expr);
+        }
+    }
+
+    /// Generate `key in self.mapping` (or `key in self.mapping[outer]`).
+    ///
+    /// EVM storage has no notion of an absent key, so this reports whether
+    /// the stored value is non-zero: keys explicitly set to zero count as
+    /// absent.
+    fn generate_membership(&self, key: &Expr, mapping: &Expr) -> CodegenResult<String> {
+        let key_code = self.generate_expression(key)?;
+        let self_mapping = |expr: &Expr| match expr {
+            Expr::Attribute(base, attr) if matches!(&**base, Expr::Ident(b) if b == "self") => {
+                self.storage_layout.get(attr).copied()
             }
-            Expr::IfExp { test, body, orelse } => {
-                // Yul doesn't have ternary expression? Yul has `if cond { ... }`.
-                // Yul doesn't support `val := if cond { x } else { y }`.
-                // We need to implement it as a function or complex expression.
-                // Or: switch cond case 0 { val := y } default { val := x }
-                // But generate_expression needs to return a string of expression.
-                // We can use a switch wrapper if Yul allowed it inside expression context, but Yul statements vs expressions are strict.
-                // A helper function `select(cond, a, b)` can be defined.
-                // Let's assume we add `select` helper.
-                let test_code = self.generate_expression(test)?;
-                let body_code = self.generate_expression(body)?;
-                let orelse_code = self.generate_expression(orelse)?;
-                Ok(format!("select({}, {}, {})", test_code, body_code, orelse_code))
+            _ => None,
+        };
+
+        let value = if let Some(slot) = self_mapping(mapping) {
+            format!("get_mapping({}, {})", key_code, slot)
+        } else if let Some((slot, outer)) = match mapping {
+            Expr::Index(inner, outer) => self_mapping(inner).map(|slot| (slot, outer)),
+            _ => None,
+        } {
+            let outer_code = self.generate_expression(outer)?;
+            format!("get_nested_mapping({}, {}, {})", outer_code, key_code, slot)
+        } else {
+            return Err(CodegenError::UnsupportedFeature(format!(
+                "membership test on {:?} (only contract mappings are supported)",
+                mapping
+            )));
+        };
+        Ok(format!("iszero(iszero({}))", value))
+    }
+
+    /// Whether `expr` is a str/bytes value held in memory. String state
+    /// variables are stored as single words and cannot be concatenated.
+    fn is_string_operand(&self, expr: &Expr) -> CodegenResult<bool> {
+        let state_var = match expr {
+            Expr::Attribute(base, attr) if matches!(&**base, Expr::Ident(b) if b == "self") => Some(attr),
+            Expr::Ident(name) if !self.string_locals.contains(name) => Some(name),
+            _ => None,
+        };
+        if let Some(name) = state_var {
+            if self.state_types.get(name).is_some_and(is_string_type) {
+                return Err(CodegenError::UnsupportedFeature(format!(
+                    "str/bytes state variable '{}' used as an operand",
+                    name
+                )));
+            }
+        }
+        Ok(is_memory_string(expr, &self.string_locals))
+    }
+
+    /// Generate a memory string for `expr`, copying literals into memory
+    fn generate_memory_string(&self, expr: &Expr) -> CodegenResult<String> {
+        match expr {
+            Expr::StringLiteral(s) if s.len() > 32 => Err(CodegenError::UnsupportedFeature(
+                "concatenating string literals longer than 32 bytes".to_string(),
+            )),
+            Expr::StringLiteral(s) => Ok(format!("memory_string({}, {})", self.generate_expression(expr)?, s.len())),
+            Expr::FString(parts) => self.generate_memory_string(&Expr::StringLiteral(self.fold_fstring(parts)?)),
+            _ => self.generate_expression(expr),
+        }
+    }
+
+    /// Format an f-string at compile time; formatting runtime values would
+    /// cost more gas than the message is worth
+    fn fold_fstring(&self, parts: &[Expr]) -> CodegenResult<String> {
+        fold_fstring(parts, |name| self.constant_values.get(name)).map_err(|_| {
+            CodegenError::UnsupportedFeature(
+                "f-string interpolating a runtime value; only literals and constants can be formatted".to_string(),
+            )
+        })
+    }
+}
+
+/// Calculate the function selector: the first 4 bytes of
+/// `keccak256("name(type1,type2,...)")`
+pub(crate) fn function_selector(name: &str, params: &[quorlin_parser::Param]) -> CodegenResult<u32> {
+    let hash = signature_hash(name, params.iter().map(|param| &param.type_annotation))?;
+    Ok(u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]))
+}
+
+/// `keccak256("name(type1,type2,...)")` over the canonical ABI types, as
+/// used for selectors and event topics
+fn signature_hash<'a>(name: &str, types: impl Iterator<Item = &'a Type>) -> CodegenResult<[u8; 32]> {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let types = types
+        .map(canonical_abi_type)
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| CodegenError::Error(format!("Parameter of '{}': {}", name, e)))?;
+    let signature = format!("{}({})", name, types.join(","));
+
+    let mut hash = [0u8; 32];
+    let mut keccak = Keccak::v256();
+    keccak.update(signature.as_bytes());
+    keccak.finalize(&mut hash);
+    Ok(hash)
+}
+
+/// Clean a loaded argument word of type `ty`. Addresses are masked to 160
+/// bits so dirty high bits from the caller never reach comparisons or
+/// storage keys.
+fn decode_word(load: &str, ty: &Type) -> String {
+    match ty {
+        Type::Simple(name) if name == "address" => abi_encode_word(load, ty),
+        _ => load.to_string(),
+    }
+}
+
+/// Clean a value of type `ty` into its ABI word: booleans become 0/1,
+/// addresses and narrow unsigned integers are masked to their width, and
+/// narrow signed integers are sign-extended
+fn abi_encode_word(value: &str, ty: &Type) -> String {
+    let Type::Simple(name) = ty else {
+        return value.to_string();
+    };
+    let width = |prefix: &str| name.strip_prefix(prefix).and_then(|bits| bits.parse::<u32>().ok());
+
+    match name.as_str() {
+        "bool" => format!("iszero(iszero({}))", value),
+        "address" => format!("and({}, 0x{})", value, "f".repeat(40)),
+        _ => {
+            if let Some(bits) = width("uint").filter(|bits| *bits < 256 && bits % 8 == 0) {
+                format!("and({}, 0x{})", value, "f".repeat(bits as usize / 4))
+            } else if let Some(bits) = width("int").filter(|bits| *bits < 256 && bits % 8 == 0) {
+                format!("signextend({}, {})", bits / 8 - 1, value)
+            } else {
+                value.to_string()
+            }
+        }
+    }
+}
+
+/// The ABI encoding of `Error(message)`: selector, offset, length and the
+/// message padded to whole words
+fn error_string_payload(message: &str) -> Vec<u8> {
+    let mut payload = vec![0x08, 0xc3, 0x79, 0xa0];
+    let mut word = [0u8; 32];
+    word[31] = 0x20;
+    payload.extend_from_slice(&word);
+    word[24..].copy_from_slice(&(message.len() as u64).to_be_bytes());
+    payload.extend_from_slice(&word);
+    payload.extend_from_slice(message.as_bytes());
+    payload.resize(4 + 32 * (2 + message.len().div_ceil(32)), 0);
+    payload
+}
+
+/// Lowercase hex digits of `bytes`, without a `0x` prefix
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whether `ty` is a signed integer type
+fn is_signed_type(ty: &Type) -> bool {
+    matches!(ty, Type::Simple(name) if name.strip_prefix("int").is_some_and(|bits| bits.parse::<u32>().is_ok()))
+}
+
+/// Bit width of `uintN` for N below 256
+fn narrow_unsigned_bits(ty: &Type) -> Option<u32> {
+    match ty {
+        Type::Simple(name) => name
+            .strip_prefix("uint")?
+            .parse::<u32>()
+            .ok()
+            .filter(|bits| *bits < 256 && bits % 8 == 0),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is a byte string held in memory as length + data
+fn is_string_type(ty: &Type) -> bool {
+    dynamic_element_size(ty) == Some(1)
+}
+
+/// Whether `expr` evaluates to a memory string, given the string-typed names in scope
+fn is_memory_string(expr: &Expr, strings: &HashSet<String>) -> bool {
+    match expr {
+        Expr::StringLiteral(_) | Expr::FString(_) => true,
+        Expr::Ident(name) => strings.contains(name),
+        Expr::BinOp(left, quorlin_parser::BinOp::Add, right) => {
+            is_memory_string(left, strings) || is_memory_string(right, strings)
+        }
+        _ => false,
+    }
+}
+
+/// The returned expression of an internal `@inline` function that can be
+/// spliced into call sites: a single `return` with no conditional
+/// expression, which needs statements to lower. Other `@inline` functions
+/// are emitted as ordinary Yul functions.
+fn inline_body(func: &Function) -> Option<&Expr> {
+    if !is_internal(func) || !func.has_decorator("inline") || !func.validations().is_empty() {
+        return None;
+    }
+    let [Stmt::Return(Some(body))] = func.body.as_slice() else {
+        return None;
+    };
+
+    let mut uses = IdentCounter::default();
+    uses.visit_expr(body);
+    (!uses.conditional).then_some(body)
+}
+
+/// Right operand of the last comparison in a (possibly nested) `and`, the
+/// operand a following comparison of the same chain starts from
+fn last_comparison_operand(expr: &mut Expr) -> Option<&mut Expr> {
+    match expr {
+        Expr::BinOp(_, BinOp::And, right) => last_comparison_operand(right),
+        Expr::BinOp(_, op, right) if is_comparison(op) => Some(right),
+        _ => None,
+    }
+}
+
+fn is_comparison(op: &BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::Eq | BinOp::NotEq | BinOp::Lt | BinOp::Gt | BinOp::LtEq | BinOp::GtEq | BinOp::In
+    )
+}
+
+/// Whether an `@inline` argument can be substituted wherever the body uses
+/// it without a temporary: a name or a literal, cheap and free of effects
+fn is_inline_operand(expr: &Expr) -> bool {
+    matches!(expr, Expr::Ident(_)) || is_literal(expr)
+}
+
+/// Replace parameter identifiers in an inlined body with the call's arguments
+fn substitute_params(expr: &Expr, bindings: &HashMap<&str, &Expr>) -> Expr {
+    let sub = |e: &Expr| Box::new(substitute_params(e, bindings));
+    match expr {
+        Expr::Ident(name) => bindings.get(name.as_str()).map_or_else(|| expr.clone(), |arg| (*arg).clone()),
+        Expr::BinOp(left, op, right) => Expr::BinOp(sub(left), op.clone(), sub(right)),
+        Expr::UnaryOp(op, operand) => Expr::UnaryOp(op.clone(), sub(operand)),
+        Expr::Call(func, args) => {
+            let func = match &**func {
+                // A callee name is not a parameter reference
+                Expr::Ident(_) => func.clone(),
+                other => sub(other),
+            };
+            Expr::Call(func, args.iter().map(|a| substitute_params(a, bindings)).collect())
+        }
+        Expr::Attribute(base, attr) => Expr::Attribute(sub(base), attr.clone()),
+        Expr::KeywordArg(name, value) => Expr::KeywordArg(name.clone(), sub(value)),
+        Expr::ListComp { element, variable, iterable, condition } => {
+            // The comprehension variable shadows a parameter of the same name
+            let mut inner = bindings.clone();
+            inner.remove(variable.as_str());
+            Expr::ListComp {
+                element: Box::new(substitute_params(element, &inner)),
+                variable: variable.clone(),
+                iterable: sub(iterable),
+                condition: condition.as_ref().map(|c| Box::new(substitute_params(c, &inner))),
             }
-            _ => Err(CodegenError::UnsupportedFeature(format!("Expression {:?}", expr))),
+        }
+        Expr::Index(base, index) => Expr::Index(sub(base), sub(index)),
+        Expr::List(items) => Expr::List(items.iter().map(|i| substitute_params(i, bindings)).collect()),
+        Expr::Tuple(items) => Expr::Tuple(items.iter().map(|i| substitute_params(i, bindings)).collect()),
+        Expr::FString(parts) => Expr::FString(parts.iter().map(|p| substitute_params(p, bindings)).collect()),
+        Expr::IfExp { test, body, orelse } => Expr::IfExp {
+            test: sub(test),
+            body: sub(body),
+            orelse: sub(orelse),
+        },
+        Expr::IntLiteral(_) | Expr::HexLiteral(_) | Expr::StringLiteral(_) | Expr::BoolLiteral(_) | Expr::NoneLiteral => {
+            expr.clone()
         }
     }
+}
 
-    /// Calculate function selector (simplified version)
-    fn calculate_selector(&self, name: &str, params: &[quorlin_parser::Param]) -> u32 {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
+/// Counts references to each identifier
+#[derive(Default)]
+struct IdentCounter<'ast> {
+    counts: HashMap<&'ast str, usize>,
+    /// Whether a conditional expression was seen
+    conditional: bool,
+}
 
-        let mut hasher = DefaultHasher::new();
-        name.hash(&mut hasher);
-        for param in params {
-            param.name.hash(&mut hasher);
+impl<'ast> Visitor<'ast> for IdentCounter<'ast> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        match expr {
+            Expr::Ident(name) => *self.counts.entry(name).or_default() += 1,
+            Expr::IfExp { .. } => self.conditional = true,
+            _ => {}
         }
+        visit::walk_expr(self, expr);
+    }
+}
 
-        (hasher.finish() as u32) & 0xFFFFFFFF
+/// Collects the names assigned as plain identifiers, including unpacking
+/// targets, in order of first assignment
+#[derive(Default)]
+struct AssignedLocalCollector {
+    names: Vec<String>,
+}
+
+impl<'ast> Visitor<'ast> for AssignedLocalCollector {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        if let Stmt::Assign(assign) = stmt {
+            let targets = match &assign.target {
+                Expr::Tuple(targets) => targets.as_slice(),
+                target => std::slice::from_ref(target),
+            };
+            for target in targets {
+                if let Expr::Ident(name) = target {
+                    if !self.names.contains(name) {
+                        self.names.push(name.clone());
+                    }
+                }
+            }
+        }
+        visit::walk_stmt(self, stmt);
+    }
+}
+
+/// Collects locals that hold strings, by annotation or by initial value
+struct StringLocalCollector {
+    names: HashSet<String>,
+}
+
+impl<'ast> Visitor<'ast> for StringLocalCollector {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        if let Stmt::Assign(assign) = stmt {
+            if let Expr::Ident(name) = &assign.target {
+                let is_string = match &assign.type_annotation {
+                    Some(ty) => is_string_type(ty),
+                    None => is_memory_string(&assign.value, &self.names),
+                };
+                if is_string {
+                    self.names.insert(name.clone());
+                }
+            }
+        }
+        visit::walk_stmt(self, stmt);
+    }
+}
+
+/// Collects the annotated types of locals
+struct LocalTypeCollector {
+    types: HashMap<String, Type>,
+}
+
+impl<'ast> Visitor<'ast> for LocalTypeCollector {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        if let Stmt::Assign(assign) = stmt {
+            if let (Expr::Ident(name), Some(ty)) = (&assign.target, &assign.type_annotation) {
+                self.types.insert(name.clone(), ty.clone());
+            }
+        }
+        visit::walk_stmt(self, stmt);
+    }
+}
+
+/// Collects locals that hold memory lists, by annotation or by initial value
+struct ListLocalCollector {
+    names: HashSet<String>,
+}
+
+impl<'ast> Visitor<'ast> for ListLocalCollector {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        if let Stmt::Assign(assign) = stmt {
+            if let Expr::Ident(name) = &assign.target {
+                let is_list = match &assign.type_annotation {
+                    Some(ty) => matches!(ty, Type::List(_) | Type::Tuple(_)),
+                    None => is_memory_list(&assign.value, &self.names),
+                };
+                if is_list {
+                    self.names.insert(name.clone());
+                }
+            }
+        }
+        visit::walk_stmt(self, stmt);
+    }
+}
+
+/// Whether `expr` evaluates to a memory list, given the list-typed names in scope
+fn is_memory_list(expr: &Expr, lists: &HashSet<String>) -> bool {
+    match expr {
+        Expr::List(_) | Expr::Tuple(_) => true,
+        Expr::Ident(name) => lists.contains(name),
+        _ => false,
+    }
+}
+
+/// For ABI-dynamic parameter types, the size in bytes of one element
+fn dynamic_element_size(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::Simple(name) if matches!(name.as_str(), "bytes" | "str" | "string") => Some(1),
+        Type::List(_) => Some(32),
+        _ => None,
+    }
+}
+
+/// Whether `expr` is a literal that can be inlined
+fn is_literal(expr: &Expr) -> bool {
+    matches!(expr, Expr::IntLiteral(_) | Expr::BoolLiteral(_) | Expr::HexLiteral(_) | Expr::StringLiteral(_))
+}
+
+/// The state variable written by an assignment target, if any
+fn assigned_state_var(target: &Expr) -> Option<&str> {
+    match target {
+        Expr::Attribute(base, attr) if matches!(&**base, Expr::Ident(b) if b == "self") => Some(attr),
+        Expr::Ident(name) => Some(name),
+        Expr::Index(base, _) | Expr::Attribute(base, _) => assigned_state_var(base),
+        _ => None,
+    }
+}
+
+/// Counts reads of each state variable, as `self.name` or a bare `name`.
+/// The target of a plain assignment is a write, not a read.
+struct StateReadCounter<'ast, 's> {
+    state: &'s HashMap<String, usize>,
+    counts: HashMap<&'ast str, usize>,
+}
+
+impl<'ast> Visitor<'ast> for StateReadCounter<'ast, '_> {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        match stmt {
+            Stmt::Assign(assign) if matches!(&assign.target, Expr::Ident(_) | Expr::Attribute(_, _)) => {
+                self.visit_expr(&assign.value);
+            }
+            _ => visit::walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        let read = match expr {
+            Expr::Attribute(base, attr) if matches!(&**base, Expr::Ident(b) if b == "self") => Some(attr),
+            Expr::Ident(name) => Some(name),
+            _ => None,
+        };
+        match read {
+            Some(name) if self.state.contains_key(name) => *self.counts.entry(name).or_default() += 1,
+            Some(_) => {}
+            None => visit::walk_expr(self, expr),
+        }
+    }
+}
+
+/// Collects calls to the contract's own functions
+struct CalleeCollector<'ast, 'f> {
+    functions: &'f HashMap<String, FunctionSymbol>,
+    names: Vec<&'ast str>,
+    /// Whether a call on something other than `self` was seen, which may
+    /// leave the contract
+    external: bool,
+}
+
+impl<'ast> Visitor<'ast> for CalleeCollector<'ast, '_> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Expr::Call(callee, _) = expr {
+            let name = match &**callee {
+                Expr::Ident(name) => Some(name),
+                Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => Some(name),
+                _ => {
+                    self.external = true;
+                    None
+                }
+            };
+            if let Some(name) = name.filter(|name| self.functions.contains_key(name.as_str())) {
+                self.names.push(name);
+            }
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Counts assignments to each name across function bodies
+#[derive(Default)]
+struct StateWriteCounter<'ast> {
+    counts: HashMap<&'ast str, usize>,
+}
+
+impl<'ast> Visitor<'ast> for StateWriteCounter<'ast> {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        let written = match stmt {
+            Stmt::Assign(assign) => assigned_state_var(&assign.target),
+            _ => None,
+        };
+        if let Some(name) = written {
+            *self.counts.entry(name).or_default() += 1;
+        }
+        visit::walk_stmt(self, stmt);
     }
 }
 
@@ -814,9 +2726,997 @@ impl Default for EvmCodegen {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quorlin_lexer::Lexer;
+
+    fn compile(source: &str) -> CodegenResult<String> {
+        let tokens = Lexer::new(source).tokenize().expect("lexing failed");
+        let module = quorlin_parser::parse_module(tokens).expect("parsing failed");
+        EvmCodegen::new().generate(&module)
+    }
 
     #[test]
     fn test_codegen_creation() {
         let _codegen = EvmCodegen::new();
     }
+
+    #[test]
+    fn test_require_conjunction_short_circuits() {
+        let source = r#"
+contract Bounded:
+    _supply: uint256
+
+    @external
+    fn check(x: uint256):
+        require(0 <= x and x < self._supply, "range")
+"#;
+        let yul = compile(source).unwrap();
+
+        let lower = yul.find("if iszero(iszero(gt(0, x))) {").expect("lower bound guard");
+        let upper = yul.find("if iszero(lt(x, sload(0))) {").expect("upper bound guard");
+        assert!(lower < upper, "upper bound must be guarded by the lower bound");
+        assert!(!yul.contains("and("), "conjunction should not be evaluated eagerly");
+    }
+
+    #[test]
+    fn test_internal_helper_is_emitted_as_yul_function() {
+        let source = r#"
+contract Doubler:
+    @external
+    fn run(x: uint256) -> uint256:
+        let y: uint256 = self._double(x)
+        return y
+
+    fn _double(n: uint256) -> uint256:
+        return n * 2
+"#;
+        let yul = compile(source).unwrap();
+
+        assert!(yul.contains("function _double(n) -> ret {"));
+        assert!(yul.contains("ret := checked_mul(n, 2)"));
+        assert!(yul.contains("        let y\n"), "{}", yul);
+        assert!(yul.contains("        y := _double(x)\n"), "{}", yul);
+        assert!(!yul.contains("{ _double() }"), "internal functions must not be dispatched");
+    }
+
+    #[test]
+    fn test_unknown_function_call_is_rejected() {
+        let source = r#"
+contract Broken:
+    @external
+    fn run(x: uint256) -> uint256:
+        return missing(x)
+"#;
+        let err = compile(source).unwrap_err();
+        assert!(err.to_string().contains("call to undefined function 'missing'"));
+    }
+
+    #[test]
+    fn test_for_over_mapping_has_targeted_error() {
+        let source = r#"
+contract Ledger:
+    balances: mapping[address, uint256]
+
+    @external
+    fn total() -> uint256:
+        let sum: uint256 = 0
+        for holder in self.balances:
+            sum = sum + 1
+        return sum
+"#;
+        let err = compile(source).unwrap_err().to_string();
+        assert!(err.contains("Cannot iterate over mapping 'self.balances'"), "{}", err);
+        assert!(err.contains("auxiliary list of keys"), "{}", err);
+    }
+
+    #[test]
+    fn test_never_reassigned_state_is_inlined() {
+        let source = r#"
+contract Versioned:
+    _version: uint256 = 1
+    _count: uint256 = 0
+
+    @external
+    fn bump():
+        self._count = self._count + 1
+
+    @view
+    fn version() -> uint256:
+        return self._version
+"#;
+        let yul = compile(source).unwrap();
+
+        assert!(yul.contains("let ret := 1\n"), "{}", yul);
+        assert!(yul.contains("sstore(1, checked_add(sload(1), 1))"), "written state must still be loaded");
+    }
+
+    #[test]
+    fn test_dynamic_shift_is_bounds_checked() {
+        let source = r#"
+contract Bits:
+    @external
+    fn shift(x: uint256, n: uint256) -> uint256:
+        return (x << n) + (x >> 8) + x ** n
+"#;
+        let yul = compile(source).unwrap();
+
+        assert!(yul.contains("checked_shl(x, n)"), "{}", yul);
+        assert!(yul.contains("if iszero(lt(shift, 256)) { revert(0, 0) }"));
+        assert!(yul.contains("shr(8, x)"), "constant shifts need no runtime guard");
+        assert!(yul.contains("checked_exp(x, n)"));
+    }
+
+    #[test]
+    fn test_signed_right_shift_is_arithmetic() {
+        let source = r#"
+contract Bits:
+    offset: int256
+
+    @external
+    fn halve(x: int256, n: uint256) -> int256:
+        y: int256 = x >> n
+        return (y >> 1) + (self.offset >> 2)
+
+    @external
+    fn halve_unsigned(x: uint256) -> uint256:
+        return x >> 1
+"#;
+        let yul = compile(source).unwrap();
+
+        assert!(yul.contains("checked_sar(x, n)"), "{}", yul);
+        assert!(yul.contains("sar(1, y)"), "{}", yul);
+        assert!(yul.contains("sar(2, sload(0))"), "{}", yul);
+        assert!(yul.contains("shr(1, x)"), "{}", yul);
+    }
+
+    #[test]
+    fn test_signed_floor_division_rounds_down() {
+        let source = r#"
+contract Ratio:
+    @external
+    fn signed(a: int256, b: int256) -> int256:
+        return a // b
+
+    @external
+    fn unsigned(a: uint256, b: uint256) -> uint256:
+        return a // b
+"#;
+        let yul = compile(source).unwrap();
+
+        assert!(yul.contains("let ret := checked_floor_sdiv(a, b)"), "{}", yul);
+        assert!(yul.contains("let ret := checked_div(a, b)"), "{}", yul);
+        assert!(yul.contains("if smod(a, b) { if slt(xor(a, b), 0) { result := sub(result, 1) } }"), "{}", yul);
+    }
+
+    #[test]
+    fn test_dynamic_param_is_copied_to_memory_once() {
+        let source = r#"
+event Logged(data: bytes)
+
+contract Logger:
+    @external
+    fn log_twice(data: bytes, amounts: list[uint256]):
+        emit Logged(data)
+        emit Logged(data)
+"#;
+        let yul = compile(source).unwrap();
+
+        // Length word from the offset in the head, then the data rounded up to words
+        let data = [
+            "let data := mload(0x40)",
+            "let data_src := add(4, calldataload(4))",
+            "let data_len := calldataload(data_src)",
+            "mstore(data, data_len)",
+            "calldatacopy(add(data, 32), add(data_src, 32), data_len)",
+            "mstore(0x40, add(add(data, 32), and(add(data_len, 31), not(31))))",
+        ];
+        assert!(yul.contains(&data.join("\n        ")), "{}", yul);
+
+        // Word-sized elements copy 32 bytes each, from the second head slot
+        let amounts = [
+            "let amounts_src := add(4, calldataload(36))",
+            "let amounts_len := calldataload(amounts_src)",
+            "mstore(amounts, amounts_len)",
+            "calldatacopy(add(amounts, 32), add(amounts_src, 32), mul(amounts_len, 32))",
+            "mstore(0x40, add(add(amounts, 32), and(add(mul(amounts_len, 32), 31), not(31))))",
+        ];
+        assert!(yul.contains(&amounts.join("\n        ")), "{}", yul);
+
+        // Both uses read the memory copy rather than calldata
+        assert_eq!(yul.matches("calldatacopy(").count(), 2, "{}", yul);
+        let body = &yul[yul.find("function log_twice()").unwrap()..];
+        let uses = &body[body.find("\n\n").unwrap()..body.find("\n      }").unwrap()];
+        assert_eq!(uses.matches("log1(").count(), 2, "{}", uses);
+        assert!(!uses.contains("calldata"), "{}", uses);
+    }
+
+    #[test]
+    fn test_selector_is_keccak_of_signature() {
+        let param = |ty: &str| quorlin_parser::Param {
+            name: "x".to_string(),
+            type_annotation: Type::Simple(ty.to_string()),
+            default: None,
+        };
+        // transfer(address,uint256)
+        assert_eq!(function_selector("transfer", &[param("address"), param("uint256")]).unwrap(), 0xa9059cbb);
+    }
+
+    #[test]
+    fn test_event_topic_is_keccak_of_signature() {
+        let source = r#"
+event Transfer(sender: address, receiver: address, amount: uint256)
+
+contract Token:
+    @external
+    fn send(to: address, amount: uint256):
+        emit Transfer(msg.sender, to, amount)
+"#;
+        let yul = compile(source).unwrap();
+        assert!(
+            yul.contains("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"),
+            "{}",
+            yul
+        );
+    }
+
+    #[test]
+    fn test_selector_collision_is_reported() {
+        // burn(uint256) and collate_propagate_storage(bytes16) share 0x42966c68
+        let source = r#"
+contract Clash:
+    @external
+    fn burn(amount: uint256):
+        pass
+
+    @external
+    fn collate_propagate_storage(data: bytes16):
+        pass
+"#;
+        let err = compile(source).unwrap_err().to_string();
+        assert!(err.contains("'burn'"), "{}", err);
+        assert!(err.contains("'collate_propagate_storage'"), "{}", err);
+        assert!(err.contains("0x42966c68"), "{}", err);
+    }
+
+    #[test]
+    fn test_str_concatenation_copies_memory() {
+        let source = r#"
+contract Greeter:
+    @external
+    fn greet(name: str):
+        greeting: str = "Hello, " + name
+        shout: str = greeting + "!"
+"#;
+        let yul = compile(source).unwrap();
+
+        assert!(yul.contains("        let greeting, shout\n"), "{}", yul);
+        assert!(yul.contains("        greeting := concat_memory(memory_string(0x48656c6c6f2c20"), "{}", yul);
+        assert!(yul.contains(", 7), name)"), "{}", yul);
+        assert!(yul.contains("        shout := concat_memory(greeting, memory_string(0x21"), "{}", yul);
+        assert!(!yul.contains("checked_add(greeting") && !yul.contains("checked_add(0x48"), "{}", yul);
+
+        let err = compile(&source.replace("greeting + \"!\"", "greeting * 2")).unwrap_err();
+        assert!(err.to_string().contains("only + concatenation"), "{}", err);
+    }
+
+    #[test]
+    fn test_str_state_comparison_is_not_concatenation() {
+        let source = r#"
+contract Token:
+    symbol: str
+
+    @external
+    fn check():
+        require(self.symbol != "")
+        require(self.symbol == "QRL")
+"#;
+        let yul = compile(source).unwrap();
+
+        assert!(yul.contains("if iszero(iszero(eq(sload(0), 0x0000"), "{}", yul);
+        assert!(yul.contains("if iszero(eq(sload(0), 0x51524c00"), "{}", yul);
+        assert!(!yul.contains("memory_string(0x51524c"), "{}", yul);
+    }
+
+    #[test]
+    fn test_augmented_assignment_compiles_to_checked_arithmetic() {
+        let source = r#"
+contract Counter:
+    _count: uint256
+
+    @external
+    fn bump(by: uint256):
+        self._count += by
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("sstore(0, checked_add(sload(0), by))"), "{}", yul);
+    }
+
+    #[test]
+    fn test_mapping_membership_is_a_presence_check() {
+        let source = r#"
+contract Registry:
+    _balances: mapping[address, uint256]
+    _allowances: mapping[address, mapping[address, uint256]]
+
+    @external
+    fn check(x: address) -> bool:
+        require(x in self._balances, "unknown")
+        return x in self._allowances[msg.sender]
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("if iszero(iszero(iszero(get_mapping(x, 0)))) {"), "{}", yul);
+        assert!(yul.contains("iszero(iszero(get_nested_mapping(caller(), x, 1)))"), "{}", yul);
+    }
+
+    #[test]
+    fn test_return_values_are_abi_cleaned() {
+        let source = r#"
+contract Cleaner:
+    @view
+    fn flag(x: uint256) -> bool:
+        return x
+
+    @view
+    fn owner(x: uint256) -> address:
+        return x
+
+    @view
+    fn small(x: uint256) -> uint8:
+        return x
+
+    @view
+    fn total(x: uint256) -> uint256:
+        return x
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("mstore(0, iszero(iszero(ret)))"), "{}", yul);
+        assert!(yul.contains("mstore(0, and(ret, 0xffffffffffffffffffffffffffffffffffffffff))"), "{}", yul);
+        assert!(yul.contains("mstore(0, and(ret, 0xff))"), "{}", yul);
+        assert!(yul.contains("mstore(0, ret)"), "{}", yul);
+    }
+
+    #[test]
+    fn test_tuple_return_writes_one_word_per_element() {
+        let source = r#"
+contract Pair:
+    @view
+    fn get_pair() -> (uint256, uint256):
+        return (1, 2)
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("mstore(0, ret_0)"), "{}", yul);
+        assert!(yul.contains("mstore(32, ret_1)"), "{}", yul);
+        assert!(yul.contains("return(0, 64)"), "{}", yul);
+    }
+
+    #[test]
+    fn test_returning_a_tuple_call_encodes_every_value() {
+        let source = r#"
+contract Pair:
+    fn _pair() -> (uint256, bool):
+        return (1, True)
+
+    fn _forward() -> (uint256, bool):
+        return self._pair()
+
+    @view
+    fn get_pair() -> (uint256, bool):
+        return self._forward()
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("ret_0, ret_1 := _pair()\n"), "{}", yul);
+        assert!(yul.contains("let ret_0, ret_1 := _forward()\n"), "{}", yul);
+        assert!(yul.contains("mstore(0, ret_0)"), "{}", yul);
+        assert!(yul.contains("mstore(32, iszero(iszero(ret_1)))"), "{}", yul);
+        assert!(yul.contains("return(0, 64)"), "{}", yul);
+        assert!(!yul.contains("return(0, 32)"), "{}", yul);
+
+        let source = r#"
+contract Pair:
+    pair: (uint256, uint256)
+
+    @view
+    fn get_pair() -> (uint256, uint256):
+        return self.pair
+"#;
+        let err = compile(source).unwrap_err();
+        assert!(err.to_string().contains("Returning a tuple other than a tuple literal"), "{}", err);
+    }
+
+    #[test]
+    fn test_break_and_continue_compile_in_for_and_while() {
+        let source = r#"
+contract Looper:
+    @view
+    fn first_five() -> uint256:
+        total: uint256 = 0
+        for i in range(10):
+            if i == 5:
+                break
+            total = total + i
+        while total > 100:
+            total = total - 1
+            continue
+        return total
+"#;
+        // `compile` runs the generated Yul through `yul_check`, which rejects
+        // redeclaring `total` inside the loops
+        let yul = compile(source).unwrap();
+        let body = &yul[yul.find("function first_five() {").unwrap()..];
+        assert!(body.contains("        let total\n        total := 0\n"), "{}", yul);
+        let for_loop = body.find("for { let i := 0 } lt(i, 10) { i := add(i, 1) }").unwrap();
+        let while_loop = body.find("for {} gt(total, 100) {}").unwrap();
+        let for_body = &body[for_loop..while_loop];
+        assert!(for_body.contains("if eq(i, 5) {\n           break\n"), "{}", yul);
+        assert!(for_body.contains("         total := checked_add(total, i)\n"), "{}", yul);
+        let while_body = &body[while_loop..body.find("let ret := total").unwrap()];
+        assert!(while_body.contains("          total := checked_sub(total, 1)\n          continue\n"), "{}", yul);
+        assert!(!body.contains("let total :="), "{}", yul);
+    }
+
+    #[test]
+    fn test_comparisons_are_balanced() {
+        let cases = [
+            ("==", "eq(a, b)"),
+            ("!=", "iszero(eq(a, b))"),
+            ("<", "lt(a, b)"),
+            (">", "gt(a, b)"),
+            ("<=", "iszero(gt(a, b))"),
+            (">=", "iszero(lt(a, b))"),
+        ];
+        for (op, expected) in cases {
+            let source = format!(
+                "contract Cmp:\n    @view\n    fn cmp(a: uint256, b: uint256) -> bool:\n        return a {} b\n",
+                op
+            );
+            let yul = compile(&source).unwrap();
+            assert!(yul.contains(&format!("let ret := {}\n", expected)), "{}: {}", op, yul);
+            assert_eq!(yul.matches('(').count(), yul.matches(')').count(), "{}", op);
+        }
+    }
+
+    #[test]
+    fn test_address_parameters_are_masked_on_load() {
+        let source = r#"
+contract Owned:
+    owner: address
+
+    fn __init__(initial: address, start: uint256):
+        self.owner = initial
+
+    @external
+    fn transfer_ownership(next: address):
+        self.owner = next
+"#;
+        let yul = compile(source).unwrap();
+        let mask = "f".repeat(40);
+        assert!(yul.contains(&format!("let initial := and(mload(0), 0x{})", mask)), "{}", yul);
+        assert!(yul.contains("let start := mload(32)"), "{}", yul);
+        assert!(yul.contains(&format!("let next := and(calldataload(4), 0x{})", mask)), "{}", yul);
+    }
+
+    #[test]
+    fn test_conditional_expression_selects_a_branch() {
+        let source = r#"
+contract Picker:
+    @view
+    fn pick(flag: bool, balance: uint256) -> uint256:
+        return balance if flag else 0
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("switch flag\n"), "{}", yul);
+        assert!(yul.contains("let ret := $cond_0"), "{}", yul);
+    }
+
+    #[test]
+    fn test_conditional_expression_evaluates_only_the_taken_branch() {
+        let source = r#"
+contract Ratio:
+    @view
+    fn ratio(x: uint256, y: uint256) -> uint256:
+        return x / y if y != 0 else 0
+"#;
+        let yul = compile(source).unwrap();
+        let body = yul.split("function ratio() {").nth(1).unwrap();
+        let lines: Vec<&str> = body.lines().map(str::trim).collect();
+        let switch = lines.iter().position(|line| line.starts_with("switch ")).unwrap();
+        assert_eq!(lines[switch - 1], "let $cond_0 := 0", "{}", yul);
+        assert_eq!(
+            lines[switch..switch + 7],
+            [
+                "switch iszero(eq(y, 0))",
+                "case 0 {",
+                "$cond_0 := 0",
+                "}",
+                "default {",
+                "$cond_0 := checked_div(x, y)",
+                "}",
+            ],
+            "{}",
+            yul
+        );
+        assert!(lines[switch + 7..].contains(&"let ret := $cond_0"), "{}", yul);
+    }
+
+    #[test]
+    fn test_conditional_expression_right_of_and_runs_after_the_left_operand() {
+        let source = r#"
+contract Guarded:
+    @external
+    fn check(i: uint256, flag: bool):
+        require(i < 3 and (self.g(i) if flag else 0) > 0, "bad")
+
+    fn g(i: uint256) -> uint256:
+        return 10 / i
+"#;
+        let yul = compile(source).unwrap();
+        let body = yul.split("function check() {").nth(1).unwrap();
+        let guard = body.find("lt(i, 3)").unwrap();
+        let call = body.find("g(i)").unwrap();
+        assert!(guard < call, "{}", yul);
+    }
+
+    #[test]
+    fn test_tuple_unpacking_binds_every_element_first() {
+        let source = r#"
+contract Splitter:
+    fn split(v: uint256) -> (uint256, uint256):
+        return (v / 2, v % 2)
+
+    @external
+    fn run(v: uint256) -> uint256:
+        half, rest = self.split(v)
+        a, b = rest, half
+        return a
+
+    @external
+    fn swap(a: uint256, b: uint256) -> uint256:
+        a, b = b, a
+        return a - b
+"#;
+        // `compile` runs the generated Yul through `yul_check`, which rejects
+        // redeclaring a parameter
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("function split(v) -> ret_0, ret_1"), "{}", yul);
+        assert!(yul.contains("let unpack_1_0, unpack_1_1 := split(v)"), "{}", yul);
+        assert!(yul.contains("        let half, rest, a, b\n"), "{}", yul);
+        assert!(yul.contains("        half := unpack_1_0\n"), "{}", yul);
+        // Both sides are read before either target is written
+        let swap = yul.find("let unpack_2_1 := half").unwrap();
+        assert!(swap < yul.find("        a := unpack_2_0\n").unwrap(), "{}", yul);
+        // Parameters are assigned in place
+        let swap = yul.find("let unpack_3_1 := a").unwrap();
+        assert!(swap < yul.find("        a := unpack_3_0\n").unwrap(), "{}", yul);
+        assert!(yul.contains("        b := unpack_3_1\n"), "{}", yul);
+
+        let source = r#"
+contract Splitter:
+    @external
+    fn run(v: uint256) -> uint256:
+        a, b = v
+        return a
+"#;
+        let err = compile(source).unwrap_err();
+        assert!(err.to_string().contains("call to an internal function returning one"), "{}", err);
+    }
+
+    #[test]
+    fn test_contract_constants_are_inlined_and_documented() {
+        let source = r#"
+contract Capped:
+    const CAP: uint256 = 1000
+    minted: uint256
+
+    @external
+    fn mint(amount: uint256):
+        require(self.minted + amount <= CAP)
+        self.minted = self.minted + amount
+"#;
+        let yul = compile(source).unwrap();
+        let runtime = yul.split("object \"runtime\" {\n    code {\n").nth(1).unwrap();
+        assert!(runtime.starts_with("      // const CAP: uint256 = 1000\n"), "{}", yul);
+        assert!(yul.contains("if iszero(iszero(gt(checked_add(sload(0), amount), 1000)))"), "{}", yul);
+    }
+
+    #[test]
+    fn test_module_constants_are_inlined() {
+        let source = r#"
+const CAP: uint256 = 1000
+
+contract Capped:
+    minted: uint256
+
+    @external
+    fn mint(amount: uint256):
+        require(self.minted + amount <= CAP)
+        self.minted = self.minted + amount
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("      // const CAP: uint256 = 1000\n"), "{}", yul);
+        assert!(yul.contains("if iszero(iszero(gt(checked_add(sload(0), amount), 1000)))"), "{}", yul);
+    }
+
+    #[test]
+    fn test_local_list_index_reads_memory() {
+        let source = r#"
+contract Picker:
+    @external
+    fn pick() -> uint256:
+        options = [10, 20, 30]
+        return options[2]
+"#;
+        let yul = compile(source).unwrap();
+        assert!(
+            yul.contains("        options := list_set(list_set(list_set(memory_list(3), 0, 10), 1, 20), 2, 30)"),
+            "{}",
+            yul
+        );
+        assert!(yul.contains("let ret := list_at(options, 2)"), "{}", yul);
+        assert!(yul.contains("if iszero(lt(index, mload(list))) { revert(0, 0) }"), "{}", yul);
+    }
+
+    #[test]
+    fn test_self_free_helper_is_a_utility_without_selector() {
+        let source = r#"
+contract Fees:
+    rate: uint256
+
+    @external
+    fn quote(amount: uint256) -> uint256:
+        return percent_of(amount, self.rate)
+
+    fn percent_of(amount: uint256, pct: uint256) -> uint256:
+        return amount * pct / 100
+"#;
+        let yul = compile(source).unwrap();
+        let utilities = yul.split("// UTILITY FUNCTIONS").nth(1).expect("utility section");
+        assert!(utilities.contains("function percent_of(amount, pct) -> ret {"), "{}", yul);
+        let dispatcher = yul.split("switch selector()").nth(1).unwrap().split("default").next().unwrap();
+        assert!(dispatcher.contains("quote()"), "{}", dispatcher);
+        assert!(!dispatcher.contains("percent_of"), "{}", dispatcher);
+    }
+
+    #[test]
+    fn test_default_parameter_is_used_when_calldata_is_short() {
+        let source = r#"
+contract Minter:
+    minted: uint256
+
+    @external
+    fn mint(to: address, amount: uint256 = 100):
+        self.minted = self.minted + amount
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("let amount := 100\n"), "{}", yul);
+        assert!(yul.contains("if iszero(lt(calldatasize(), 68)) { amount := calldataload(36) }"), "{}", yul);
+    }
+
+    #[test]
+    fn test_keyword_and_default_arguments_are_bound_in_order() {
+        let source = r#"
+contract Vault:
+    _total: uint256
+
+    fn scaled(amount: uint256, factor: uint256 = 2) -> uint256:
+        return amount * factor
+
+    @external
+    fn deposit(amount: uint256):
+        self._total = self.scaled(factor=3, amount=amount) + self.scaled(amount)
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("checked_add(scaled(amount, 3), scaled(amount, 2))"), "{}", yul);
+    }
+
+    #[test]
+    fn test_bitwise_operators_map_to_opcodes() {
+        let source = r#"
+contract Permissions:
+    const MASK: uint256 = 4
+
+    @view
+    fn check(flags: uint256, other: uint256) -> uint256:
+        return (flags & MASK) | (other ^ ~flags)
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("let ret := or(and(flags, 4), xor(other, not(flags)))"), "{}", yul);
+    }
+
+    #[test]
+    fn test_complement_of_narrow_unsigned_is_masked() {
+        let source = r#"
+contract Flags:
+    _flags: uint8
+
+    @view
+    fn inverted(level: int8) -> uint8:
+        return ~(self._flags & 15) + ~level
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("and(not(and(sload(0), 15)), 0xff)"), "{}", yul);
+        assert!(yul.contains("not(level)"), "{}", yul);
+        assert!(!yul.contains("and(not(level)"), "{}", yul);
+    }
+
+    #[test]
+    fn test_comparison_chain_checks_each_pair() {
+        let source = r#"
+contract Range:
+    @view
+    fn within(x: uint256, cap: uint256) -> bool:
+        return 0 < x <= cap
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("let ret := and(lt(0, x), iszero(gt(x, cap)))"), "{}", yul);
+    }
+
+    #[test]
+    fn test_comparison_chain_binds_shared_call_once() {
+        let source = r#"
+contract Range:
+    @view
+    fn within(x: uint256, cap: uint256) -> bool:
+        return 0 < self._double(x) <= cap < self._double(cap)
+
+    fn _double(n: uint256) -> uint256:
+        return n * 2
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("let $chain_0 := _double(x)"), "{}", yul);
+        assert!(
+            yul.contains("let ret := and(and(lt(0, $chain_0), iszero(gt($chain_0, cap))), lt(cap, _double(cap)))"),
+            "{}",
+            yul
+        );
+    }
+
+    #[test]
+    fn test_comparison_chain_right_of_and_binds_its_operand_under_the_guard() {
+        let source = r#"
+contract Range:
+    @external
+    fn check(i: uint256, flag: bool, cap: uint256):
+        require(flag and 0 < self.g(i) < cap, "range")
+
+    fn g(i: uint256) -> uint256:
+        return 10 / i
+"#;
+        let yul = compile(source).unwrap();
+        let body = yul.split("function check() {").nth(1).unwrap();
+        let lines: Vec<&str> = body.lines().map(str::trim).collect();
+        let guard = lines.iter().position(|line| *line == "if $cond_0 {").unwrap();
+        assert_eq!(lines[guard - 1], "let $cond_0 := flag", "{}", yul);
+        assert_eq!(lines[guard + 1], "let $chain_0 := g(i)", "{}", yul);
+        assert_eq!(lines[guard + 2], "$cond_0 := and(lt(0, $chain_0), lt($chain_0, cap))", "{}", yul);
+        assert_eq!(body.matches("g(i)").count(), 1, "{}", yul);
+    }
+
+    #[test]
+    fn test_fstring_of_constants_is_folded() {
+        let source = r#"
+contract Labels:
+    const CAP: uint256 = 1_000
+
+    @view
+    fn label() -> str:
+        return f"cap {CAP} {{units}}"
+
+    fn check(x: uint256):
+        require(x <= CAP, f"x above {self.CAP}")
+"#;
+        let yul = compile(source).unwrap();
+        let label = "cap 1000 {units}".bytes().map(|b| format!("{:02x}", b)).collect::<String>();
+        assert!(yul.contains(&format!("let ret := 0x{}", label)), "{}", yul);
+        assert!(yul.contains("mstore(36, 12)"), "{}", yul);
+
+        let source = r#"
+contract Labels:
+    @view
+    fn label(x: uint256) -> str:
+        return f"x is {x}"
+"#;
+        let err = compile(source).unwrap_err();
+        assert!(err.to_string().contains("f-string interpolating a runtime value"), "{}", err);
+
+        let source = r#"
+const FLOOR: uint256 = 7
+
+contract Labels:
+    fn check(x: uint256):
+        require(x >= FLOOR, f"x below {FLOOR}")
+
+    fn check_runtime(x: uint256):
+        require(x >= FLOOR, f"x is {x}")
+"#;
+        let err = compile(source).unwrap_err();
+        assert!(err.to_string().contains("f-string interpolating a runtime value"), "{}", err);
+        let yul = compile(&source.replace(", f\"x is {x}\"", "")).unwrap();
+        assert!(yul.contains("mstore(36, 9)"), "{}", yul);
+    }
+
+    #[test]
+    fn test_loop_else_runs_only_without_break() {
+        let source = r#"
+contract Search:
+    found: uint256
+
+    fn find(n: uint256):
+        for i in range(n):
+            if i == 7:
+                break
+        else:
+            self.found = 1
+"#;
+        let yul = compile(source).unwrap();
+        assert!(yul.contains("let no_break_1 := 1"), "{}", yul);
+        assert!(yul.contains("no_break_1 := 0\n"), "{}", yul);
+        let clear = yul.find("no_break_1 := 0").unwrap();
+        assert!(yul[clear..].trim_start_matches("no_break_1 := 0").trim_start().starts_with("break"), "{}", yul);
+        assert!(yul.contains("if no_break_1 {"), "{}", yul);
+    }
+
+    #[test]
+    fn test_require_with_custom_error_reverts_with_selector() {
+        let source = r#"
+error InsufficientBalance(have: uint256, need: uint256)
+
+contract Wallet:
+    balance: uint256
+
+    @external
+    fn withdraw(amount: uint256):
+        have: uint256 = self.balance
+        require(have >= amount, InsufficientBalance(have, amount))
+        self.balance = have - amount
+"#;
+        let yul = compile(source).unwrap();
+        let uint = quorlin_parser::Param {
+            name: "x".to_string(),
+            type_annotation: Type::Simple("uint256".to_string()),
+            default: None,
+        };
+        let selector = function_selector("InsufficientBalance", &[uint.clone(), uint]).unwrap();
+        assert!(yul.contains(&format!("mstore(0, shl(224, 0x{:08x}))", selector)), "{}", yul);
+        assert!(yul.contains("mstore(36, err_1)"), "{}", yul);
+        assert!(yul.contains("revert(0, 68)"), "{}", yul);
+    }
+
+    #[test]
+    fn test_repeated_state_reads_share_one_sload_when_optimizing() {
+        let source = r#"
+contract Ledger:
+    x: uint256
+
+    @external
+    fn bump():
+        self.x = self.x + 1
+
+    @view
+    fn triple() -> uint256:
+        return self.x + self.x + self.x
+
+    @view
+    fn shadow() -> uint256:
+        cached_x: uint256 = self.x
+        return cached_x + self.x
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        let function_body = |yul: &str| yul.split("function triple() {").nth(1).unwrap().split("\n      }").next().unwrap().to_string();
+
+        let plain = function_body(&EvmCodegen::new().generate(&module).unwrap());
+        assert_eq!(plain.matches("sload(0)").count(), 3, "{}", plain);
+
+        let optimized = function_body(&EvmCodegen::new().with_optimize(true).generate(&module).unwrap());
+        assert_eq!(optimized.matches("sload(0)").count(), 1, "{}", optimized);
+        assert!(optimized.contains("checked_add(checked_add($cached_x, $cached_x), $cached_x)"), "{}", optimized);
+
+        // The cache's local never collides with a user's
+        let optimized = EvmCodegen::new().with_optimize(true).generate(&module).unwrap();
+        assert!(optimized.contains("\n        cached_x := $cached_x\n"), "{}", optimized);
+    }
+
+    #[test]
+    fn test_identical_require_messages_share_one_reason_when_optimizing() {
+        let source = r#"
+contract Wallet:
+    balance: uint256
+
+    @external
+    fn withdraw(amount: uint256):
+        require(self.balance >= amount, "insufficient balance")
+        self.balance = self.balance - amount
+
+    @external
+    fn burn(amount: uint256):
+        require(self.balance >= amount, "insufficient balance")
+        self.balance = self.balance - amount
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        let encoded = to_hex(b"insufficient balance");
+
+        let plain = EvmCodegen::new().generate(&module).unwrap();
+        assert_eq!(plain.matches(&encoded).count(), 2, "{}", plain);
+
+        let optimized = EvmCodegen::new().with_optimize(true).generate(&module).unwrap();
+        assert_eq!(optimized.matches(&encoded).count(), 1, "{}", optimized);
+        assert_eq!(optimized.matches("revert(0, datasize(\"reason_0\"))").count(), 2, "{}", optimized);
+        assert!(!optimized.contains("reason_1"), "{}", optimized);
+    }
+
+    #[test]
+    fn test_validate_decorator_injects_require_at_entry() {
+        let source = r#"
+contract Vault:
+    total: uint256
+
+    @external
+    @validate("amount > 0")
+    fn deposit(amount: uint256):
+        self.total = self.total + amount
+"#;
+        let yul = compile(source).unwrap();
+        let body = yul.split("function deposit() {").nth(1).unwrap();
+        let first = body.lines().map(str::trim).find(|line| !line.is_empty() && !line.starts_with("let ")).unwrap();
+        assert_eq!(first, "if iszero(gt(amount, 0)) {", "{}", yul);
+        // "validate: amount > 0" as the Error(string) reason
+        assert!(body.contains("mstore(36, 20)"), "{}", yul);
+    }
+
+    #[test]
+    fn test_inline_function_is_spliced_into_caller() {
+        let source = r#"
+contract Doubler:
+    @external
+    fn run(x: uint256) -> uint256:
+        return self._double(x + 1)
+
+    @inline
+    fn _double(n: uint256) -> uint256:
+        return n * 2
+"#;
+        let yul = compile(source).unwrap();
+        assert!(!yul.contains("function _double"), "{}", yul);
+        assert!(yul.contains("checked_mul(checked_add(x, 1), 2)"), "{}", yul);
+    }
+
+    #[test]
+    fn test_inline_arguments_are_evaluated_once() {
+        let source = r#"
+contract Squares:
+    @external
+    fn run(x: uint256, y: uint256) -> uint256:
+        return self._square(x + 1, y * 2)
+
+    @inline
+    fn _square(n: uint256, unused: uint256) -> uint256:
+        return n * n
+"#;
+        let yul = compile(source).unwrap();
+        assert!(!yul.contains("function _square"), "{}", yul);
+        assert!(yul.contains("let $arg_0 := checked_add(x, 1)\n"), "{}", yul);
+        assert!(yul.contains("let $arg_1 := checked_mul(y, 2)\n"), "{}", yul);
+        assert!(yul.contains("checked_mul($arg_0, $arg_0)"), "{}", yul);
+        assert_eq!(yul.matches("checked_add(x, 1)").count(), 1, "{}", yul);
+    }
+
+    #[test]
+    fn test_inline_cycle_is_rejected() {
+        let source = r#"
+contract Loop:
+    @external
+    fn run(x: uint256) -> uint256:
+        return self._ping(x)
+
+    @inline
+    fn _pong(n: uint256) -> uint256:
+        return self._ping(n) + 1
+
+    @inline
+    fn _ping(n: uint256) -> uint256:
+        return self._pong(n) + 1
+"#;
+        let err = compile(source).unwrap_err().to_string();
+        assert!(err.contains("_ping -> _pong -> _ping"), "{}", err);
+    }
 }
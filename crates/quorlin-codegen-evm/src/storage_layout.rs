@@ -2,8 +2,8 @@
 //!
 //! Calculates and manages storage slot assignments for contract state variables.
 
-use quorlin_parser::{ContractMember, StateVar, Type};
-use std::collections::HashMap;
+use quorlin_parser::{ContractDecl, ContractMember, StateVar, Type};
+use std::collections::{HashMap, HashSet};
 
 /// Storage layout information for a contract
 #[derive(Debug, Clone)]
@@ -44,8 +44,70 @@ impl StorageLayout {
         Ok(())
     }
 
+    /// Allocate storage for `contract`, laying out the state variables of its
+    /// bases first (depth-first, in declaration order) so a derived contract
+    /// never shifts the slots of the contracts it inherits from.
+    ///
+    /// `contracts` holds every contract a base name may refer to.
+    pub fn allocate_inherited(
+        &mut self,
+        contract: &ContractDecl,
+        contracts: &[&ContractDecl],
+    ) -> Result<(), String> {
+        let mut laid_out = HashSet::new();
+        let mut owners = HashMap::new();
+        self.allocate_with_bases(contract, contracts, &mut laid_out, &mut Vec::new(), &mut owners)
+    }
+
+    fn allocate_with_bases<'a>(
+        &mut self,
+        contract: &'a ContractDecl,
+        contracts: &[&'a ContractDecl],
+        laid_out: &mut HashSet<&'a str>,
+        chain: &mut Vec<&'a str>,
+        owners: &mut HashMap<String, &'a str>,
+    ) -> Result<(), String> {
+        // A base shared by several parents is laid out once
+        if laid_out.contains(contract.name.as_str()) {
+            return Ok(());
+        }
+        if chain.contains(&contract.name.as_str()) {
+            return Err(format!("Contract '{}' inherits from itself", contract.name));
+        }
+        chain.push(&contract.name);
+
+        for base in &contract.bases {
+            let base_contract = contracts
+                .iter()
+                .find(|c| c.name == *base)
+                .ok_or_else(|| format!("Base contract '{}' of '{}' not found", base, contract.name))?;
+            self.allocate_with_bases(base_contract, contracts, laid_out, chain, owners)?;
+        }
+
+        for member in &contract.body {
+            if let ContractMember::StateVar(var) = member {
+                if let Some(owner) = owners.get(&var.name) {
+                    return Err(format!(
+                        "State variable '{}' in '{}' collides with the one inherited from '{}'",
+                        var.name, contract.name, owner
+                    ));
+                }
+                owners.insert(var.name.clone(), &contract.name);
+                self.allocate_variable(var)?;
+            }
+        }
+
+        chain.pop();
+        laid_out.insert(&contract.name);
+        Ok(())
+    }
+
     /// Allocate storage for a single variable
     fn allocate_variable(&mut self, var: &StateVar) -> Result<(), String> {
+        if self.slots.contains_key(&var.name) {
+            return Err(format!("State variable '{}' is already allocated", var.name));
+        }
+
         let size = self.calculate_type_size(&var.type_annotation);
 
         self.slots.insert(
@@ -76,6 +138,10 @@ impl StorageLayout {
                 size * self.calculate_type_size(inner)
             }
 
+            // Constant-sized arrays are resolved to FixedArray by semantic analysis;
+            // an unresolved one can only be addressed through its base slot
+            Type::ConstArray(_, _) => 1,
+
             // Optional types same as inner type
             Type::Optional(inner) => self.calculate_type_size(inner),
 
@@ -175,12 +241,14 @@ mod tests {
             name: "balance".to_string(),
             type_annotation: Type::Simple("uint256".to_string()),
             initial_value: None,
+            span: Default::default(),
         };
 
         let var2 = StateVar {
             name: "owner".to_string(),
             type_annotation: Type::Simple("address".to_string()),
             initial_value: None,
+            span: Default::default(),
         };
 
         layout.allocate_variable(&var1).unwrap();
@@ -190,4 +258,48 @@ mod tests {
         assert_eq!(layout.get_slot("owner"), Some(1));
         assert_eq!(layout.next_slot, 2);
     }
+
+    fn contract(name: &str, bases: &[&str], vars: &[&str]) -> ContractDecl {
+        ContractDecl {
+            name: name.to_string(),
+            bases: bases.iter().map(|b| b.to_string()).collect(),
+            body: vars
+                .iter()
+                .map(|v| {
+                    ContractMember::StateVar(StateVar {
+                        name: v.to_string(),
+                        type_annotation: Type::Simple("uint256".to_string()),
+                        initial_value: None,
+                        span: Default::default(),
+                    })
+                })
+                .collect(),
+            docstring: None,
+            span: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_inherited_variables_are_laid_out_first() {
+        let parent = contract("Ownable", &[], &["_owner", "_pending_owner"]);
+        let child = contract("Vault", &["Ownable"], &["_total"]);
+
+        let mut layout = StorageLayout::new();
+        layout.allocate_inherited(&child, &[&parent, &child]).unwrap();
+
+        assert_eq!(layout.get_slot("_owner"), Some(0));
+        assert_eq!(layout.get_slot("_pending_owner"), Some(1));
+        assert_eq!(layout.get_slot("_total"), Some(2));
+    }
+
+    #[test]
+    fn test_inherited_variable_collision_is_rejected() {
+        let parent = contract("Ownable", &[], &["_owner"]);
+        let child = contract("Vault", &["Ownable"], &["_owner"]);
+
+        let err = StorageLayout::new()
+            .allocate_inherited(&child, &[&parent, &child])
+            .unwrap_err();
+        assert!(err.contains("collides"), "{}", err);
+    }
 }
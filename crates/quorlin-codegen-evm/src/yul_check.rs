@@ -0,0 +1,336 @@
+//! Well-formedness check for generated Yul
+//!
+//! Code generation builds Yul from strings, so an unbalanced parenthesis or
+//! a stray brace only surfaces once solc rejects the output. This pass checks
+//! bracket balance, that every `if` is followed by a condition and a block,
+//! and that no `let` declares a name that is already visible, so malformed
+//! output is reported as a codegen error pointing at the offending construct.
+
+use crate::{CodegenError, CodegenResult};
+use std::collections::HashSet;
+
+/// A position in the Yul source, 1-based
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Character cursor that skips string literals and comments
+struct Scanner<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    position: Position,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.chars().peekable(),
+            position: Position { line: 1, column: 1 },
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        Some(c)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    /// Skip whitespace, comments and string literals
+    fn skip_trivia(&mut self) -> CodegenResult<()> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') => {
+                    let start = self.position;
+                    let mut rest = self.chars.clone();
+                    rest.next();
+                    match rest.next() {
+                        Some('/') => {
+                            while self.peek().is_some_and(|c| c != '\n') {
+                                self.bump();
+                            }
+                        }
+                        Some('*') => {
+                            self.bump();
+                            self.bump();
+                            let mut previous = ' ';
+                            loop {
+                                match self.bump() {
+                                    Some('/') if previous == '*' => break,
+                                    Some(c) => previous = c,
+                                    None => return Err(malformed(start, "unterminated comment")),
+                                }
+                            }
+                        }
+                        _ => return Ok(()),
+                    }
+                }
+                Some('"') => {
+                    let start = self.position;
+                    self.bump();
+                    loop {
+                        match self.bump() {
+                            Some('\\') => {
+                                self.bump();
+                            }
+                            Some('"') => break,
+                            Some('\n') | None => return Err(malformed(start, "unterminated string literal")),
+                            Some(_) => {}
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Consume an identifier, keyword or literal
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.peek().filter(|c| is_word_char(*c)) {
+            word.push(c);
+            self.bump();
+        }
+        word
+    }
+}
+
+/// An `if` whose condition or block has not been seen yet
+#[derive(Clone, Copy)]
+struct PendingIf {
+    /// Bracket depth of the `if` keyword
+    depth: usize,
+    position: Position,
+    condition_done: bool,
+}
+
+/// Names declared in one `{ ... }` block
+#[derive(Default)]
+struct Scope {
+    names: HashSet<String>,
+    /// A function body, which cannot see the variables around it
+    function: bool,
+}
+
+/// Whether `name` is visible from the innermost scope
+fn is_visible(scopes: &[Scope], name: &str) -> bool {
+    for scope in scopes.iter().rev() {
+        if scope.names.contains(name) {
+            return true;
+        }
+        if scope.function {
+            break;
+        }
+    }
+    false
+}
+
+/// Read `a, b, c` up to the first token that is not a name or a comma
+fn name_list(scanner: &mut Scanner) -> CodegenResult<Vec<String>> {
+    let mut names = Vec::new();
+    loop {
+        scanner.skip_trivia()?;
+        let name = scanner.word();
+        if name.is_empty() {
+            break;
+        }
+        names.push(name);
+        scanner.skip_trivia()?;
+        if scanner.peek() != Some(',') {
+            break;
+        }
+        scanner.bump();
+    }
+    Ok(names)
+}
+
+/// Consume `name(params) -> returns` after the `function` keyword, returning
+/// the variables the body starts with
+fn function_signature(scanner: &mut Scanner) -> CodegenResult<Vec<String>> {
+    scanner.skip_trivia()?;
+    scanner.word();
+    scanner.skip_trivia()?;
+    let position = scanner.position;
+    if scanner.bump() != Some('(') {
+        return Err(malformed(position, "function name is not followed by '('"));
+    }
+    let mut names = name_list(scanner)?;
+    let position = scanner.position;
+    if scanner.bump() != Some(')') {
+        return Err(malformed(position, "function parameters are not closed by ')'"));
+    }
+    scanner.skip_trivia()?;
+    if scanner.peek() == Some('-') {
+        scanner.bump();
+        scanner.bump();
+        names.extend(name_list(scanner)?);
+    }
+    Ok(names)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$')
+}
+
+fn malformed(position: Position, message: &str) -> CodegenError {
+    CodegenError::Error(format!("Malformed Yul at {}: {}", position, message))
+}
+
+/// Check that `yul` has balanced brackets and well-formed `if` statements
+pub fn validate(yul: &str) -> CodegenResult<()> {
+    let mut scanner = Scanner::new(yul);
+    let mut open: Vec<(char, Position)> = Vec::new();
+    let mut pending_if: Option<PendingIf> = None;
+    let mut scopes: Vec<Scope> = Vec::new();
+    // Parameters and return variables of the function whose body is next
+    let mut pending_function: Option<Vec<String>> = None;
+
+    loop {
+        scanner.skip_trivia()?;
+        let position = scanner.position;
+        let Some(c) = scanner.peek() else { break };
+
+        if let Some(pending) = pending_if.filter(|pending| pending.condition_done) {
+            if c != '{' {
+                return Err(malformed(pending.position, "'if' condition is not followed by a block"));
+            }
+            pending_if = None;
+        }
+
+        match c {
+            '(' | '{' | '[' => {
+                scanner.bump();
+                open.push((c, position));
+                if c == '{' {
+                    let function = pending_function.take();
+                    scopes.push(Scope {
+                        function: function.is_some(),
+                        names: function.unwrap_or_default().into_iter().collect(),
+                    });
+                }
+            }
+            ')' | '}' | ']' => {
+                scanner.bump();
+                let expected = match c {
+                    ')' => '(',
+                    '}' => '{',
+                    _ => '[',
+                };
+                match open.pop() {
+                    Some((opener, _)) if opener == expected => {}
+                    Some((opener, opened_at)) => {
+                        return Err(malformed(
+                            position,
+                            &format!("'{}' does not close '{}' opened at {}", c, opener, opened_at),
+                        ));
+                    }
+                    None => return Err(malformed(position, &format!("unmatched '{}'", c))),
+                }
+                if c == '}' {
+                    scopes.pop();
+                }
+                // A call closing back at the `if`'s depth ends the condition
+                if let Some(pending) = pending_if.as_mut() {
+                    pending.condition_done |= open.len() == pending.depth;
+                }
+            }
+            c if is_word_char(c) => {
+                let word = scanner.word();
+                scanner.skip_trivia()?;
+                if let Some(pending) = pending_if.as_mut().filter(|pending| open.len() == pending.depth) {
+                    // An identifier or literal not followed by a call ends the condition
+                    pending.condition_done = scanner.peek() != Some('(');
+                } else if word == "if" {
+                    if !scanner.peek().is_some_and(is_word_char) {
+                        return Err(malformed(position, "'if' without a condition"));
+                    }
+                    pending_if = Some(PendingIf { depth: open.len(), position, condition_done: false });
+                } else if word == "let" {
+                    for name in name_list(&mut scanner)? {
+                        if is_visible(&scopes, &name) {
+                            return Err(malformed(position, &format!("'{}' is already declared", name)));
+                        }
+                        if let Some(scope) = scopes.last_mut() {
+                            scope.names.insert(name);
+                        }
+                    }
+                } else if word == "function" {
+                    pending_function = Some(function_signature(&mut scanner)?);
+                }
+            }
+            _ => {
+                scanner.bump();
+            }
+        }
+    }
+
+    if let Some(pending) = pending_if {
+        return Err(malformed(pending.position, "'if' condition is not followed by a block"));
+    }
+    if let Some((opener, opened_at)) = open.pop() {
+        return Err(malformed(opened_at, &format!("'{}' is never closed", opener)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EvmCodegen;
+    use quorlin_lexer::Lexer;
+
+    #[test]
+    fn test_unbalanced_comparison_is_reported() {
+        let yul = "{\n    if iszero(lt(a, b) { revert(0, 0) }\n}\n";
+        let err = validate(yul).unwrap_err().to_string();
+        assert!(err.contains("'}' does not close '(' opened at line 2, column 14"), "{}", err);
+    }
+
+    #[test]
+    fn test_if_without_block_is_reported() {
+        let err = validate("{\n    if iszero(x) revert(0, 0)\n}\n").unwrap_err().to_string();
+        assert!(err.contains("line 2, column 5: 'if' condition is not followed by a block"), "{}", err);
+    }
+
+    #[test]
+    fn test_brackets_in_strings_and_comments_are_ignored() {
+        validate("{\n    // a ( comment\n    let s := \"} {\" /* ) */\n}\n").unwrap();
+    }
+
+    #[test]
+    fn test_redeclaration_is_reported() {
+        let err = validate("{\n    let a := 1\n    if a {\n        let a := 2\n    }\n}\n").unwrap_err().to_string();
+        assert!(err.contains("line 4, column 9: 'a' is already declared"), "{}", err);
+        let err = validate("{\n    function f(a) -> r {\n        let r := a\n    }\n}\n").unwrap_err().to_string();
+        assert!(err.contains("'r' is already declared"), "{}", err);
+        // Sibling blocks and function bodies have scopes of their own
+        validate("{\n    let a := 1\n    { let b := 1 }\n    { let b := 2 }\n    function f() { let a := 3 }\n}\n").unwrap();
+    }
+
+    #[test]
+    fn test_token_contract_output_is_well_formed() {
+        let source = include_str!("../../../examples/token.ql");
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        let yul = EvmCodegen::new().generate(&module).unwrap();
+        validate(&yul).unwrap();
+    }
+}
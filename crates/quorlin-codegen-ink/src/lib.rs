@@ -4,6 +4,7 @@
 //!
 //! This crate generates ink! Rust code for Polkadot contracts from Quorlin AST.
 
+use quorlin_parser::type_names::canonical_name;
 use quorlin_parser::{Module, ContractMember, Expr, Stmt, BinOp, Type};
 use std::collections::HashMap;
 
@@ -16,7 +17,7 @@ pub enum CodegenError {
     #[error("Unsupported feature: {0}")]
     UnsupportedFeature(String),
 
-    #[error("Contract not found")]
+    #[error("No contract to compile")]
     ContractNotFound,
 }
 
@@ -53,6 +54,8 @@ impl InkCodegen {
 
     /// Generate ink! Rust code from a module
     pub fn generate(&mut self, module: &Module) -> CodegenResult<String> {
+        let module = &module.for_target("ink");
+
         // Find the contract
         let contract = module
             .items
@@ -149,7 +152,7 @@ impl InkCodegen {
     /// Map Quorlin types to ink! types
     fn map_type(&self, ty: &Type) -> String {
         match ty {
-            Type::Simple(s) => match s.as_str() {
+            Type::Simple(s) => match canonical_name(s) {
                 "uint8" => "u8".to_string(),
                 "uint16" => "u16".to_string(),
                 "uint32" => "u32".to_string(),
@@ -164,7 +167,7 @@ impl InkCodegen {
                 "int256" => "I256".to_string(),
                 "bool" => "bool".to_string(),
                 "address" => "AccountId".to_string(),
-                "str" => "String".to_string(),
+                "string" => "String".to_string(),
                 "bytes" => "Vec<u8>".to_string(),
                 _ => s.clone(),
             },
@@ -186,6 +189,9 @@ impl InkCodegen {
             Type::FixedArray(inner, size) => {
                 format!("[{}; {}]", self.map_type(inner), size)
             }
+            Type::ConstArray(inner, size) => {
+                format!("[{}; {}]", self.map_type(inner), size)
+            }
             Type::Optional(inner) => {
                 format!("Option<{}>", self.map_type(inner))
             }
@@ -267,7 +273,7 @@ impl InkCodegen {
 
             // Initialize storage fields
             code.push_str("            let mut instance = Self {\n");
-            for (name, _) in &self.storage_fields {
+            for name in self.storage_fields.keys() {
                 code.push_str(&format!("                {}: Default::default(),\n", name));
             }
             code.push_str("            };\n\n");
@@ -296,15 +302,10 @@ impl InkCodegen {
                 }
 
                 // Determine if it's a view function
-                let is_view = func.decorators.contains(&"view".to_string());
+                let is_view = func.has_decorator("view");
 
                 // Function signature
-                code.push_str("        #[ink(message");
-                if !is_view {
-                    code.push_str(")]\n");
-                } else {
-                    code.push_str(")]\n");
-                }
+                code.push_str("        #[ink(message)]\n");
 
                 code.push_str(&format!("        pub fn {}(", func.name));
 
@@ -321,7 +322,7 @@ impl InkCodegen {
                     code.push_str(&format!(", {}: {}", param.name, rust_type));
                 }
 
-                code.push_str(")");
+                code.push(')');
 
                 // Return type
                 if let Some(ret_type) = &func.return_type {
@@ -416,6 +417,9 @@ impl InkCodegen {
             Stmt::Require(req) => {
                 let cond = self.generate_expression(&req.condition, in_constructor)?;
                 if let Some(msg) = &req.message {
+                    let Expr::StringLiteral(msg) = msg else {
+                        return Err(CodegenError::UnsupportedFeature(format!("Require message {:?}", msg)));
+                    };
                     code.push_str(&format!("{}assert!({}, \"{}\");\n", indent_str, cond, msg));
                 } else {
                     code.push_str(&format!("{}assert!({});\n", indent_str, cond));
@@ -465,6 +469,9 @@ impl InkCodegen {
                 code.push_str(&format!("{}}}\n", indent_str));
             }
             Stmt::While(while_stmt) => {
+                if while_stmt.else_branch.is_some() {
+                    return Err(CodegenError::UnsupportedFeature("else clause on while loops".to_string()));
+                }
                 let cond = self.generate_expression(&while_stmt.condition, in_constructor)?;
                 code.push_str(&format!("{}while {} {{\n", indent_str, cond));
 
@@ -494,7 +501,7 @@ impl InkCodegen {
                 Ok(n.clone())
             }
             Expr::BoolLiteral(b) => Ok(b.to_string()),
-            Expr::StringLiteral(s) => Ok(format!("String::from(\"{}\")", s)),
+            Expr::StringLiteral(s) => Ok(format!("String::from({:?})", s)),
             Expr::Ident(name) => {
                 // Check if it's a state variable
                 if self.storage_fields.contains_key(name) {
@@ -519,7 +526,15 @@ impl InkCodegen {
                     BinOp::Sub => Ok(format!("{}.checked_sub({}).expect(\"arithmetic underflow\")", left_code, right_code)),
                     BinOp::Mul => Ok(format!("{}.checked_mul({}).expect(\"arithmetic overflow\")", left_code, right_code)),
                     BinOp::Div => Ok(format!("{}.checked_div({}).expect(\"division by zero\")", left_code, right_code)),
+                    // Integer division truncates; floor steps down when a remainder's sign differs from the divisor's
+                    BinOp::FloorDiv => Ok(format!(
+                        "{{ let (a, b) = ({}, {}); let q = a.checked_div(b).expect(\"division by zero\"); let r = a % b; if r != 0 && (r > 0) != (b > 0) {{ q - 1 }} else {{ q }} }}",
+                        left_code, right_code
+                    )),
                     BinOp::Mod => Ok(format!("{}.checked_rem({}).expect(\"modulo by zero\")", left_code, right_code)),
+                    // A shift amount beyond u32 is an overflow, not a truncated shift
+                    BinOp::Shl => Ok(format!("u32::try_from({}).ok().and_then(|shift| {}.checked_shl(shift)).expect(\"shift overflow\")", right_code, left_code)),
+                    BinOp::Shr => Ok(format!("u32::try_from({}).ok().and_then(|shift| {}.checked_shr(shift)).expect(\"shift overflow\")", right_code, left_code)),
                     BinOp::Eq => Ok(format!("({} == {})", left_code, right_code)),
                     BinOp::NotEq => Ok(format!("({} != {})", left_code, right_code)),
                     BinOp::Lt => Ok(format!("({} < {})", left_code, right_code)),
@@ -528,6 +543,10 @@ impl InkCodegen {
                     BinOp::GtEq => Ok(format!("({} >= {})", left_code, right_code)),
                     BinOp::And => Ok(format!("({} && {})", left_code, right_code)),
                     BinOp::Or => Ok(format!("({} || {})", left_code, right_code)),
+                    BinOp::BitAnd => Ok(format!("({} & {})", left_code, right_code)),
+                    BinOp::BitOr => Ok(format!("({} | {})", left_code, right_code)),
+                    BinOp::BitXor => Ok(format!("({} ^ {})", left_code, right_code)),
+                    BinOp::In => Ok(format!("{}.contains({})", right_code, left_code)),
                     _ => Err(CodegenError::UnsupportedFeature(format!("BinOp {:?}", op))),
                 }
             }
@@ -620,6 +639,8 @@ impl InkCodegen {
                     quorlin_parser::UnaryOp::Not => Ok(format!("!{}", operand_code)),
                     quorlin_parser::UnaryOp::Neg => Ok(format!("-{}", operand_code)),
                     quorlin_parser::UnaryOp::Pos => Ok(format!("+{}", operand_code)),
+                    // `!` on an integer is its bitwise complement in Rust
+                    quorlin_parser::UnaryOp::BitNot => Ok(format!("!{}", operand_code)),
                 }
             }
             _ => Err(CodegenError::UnsupportedFeature(format!("Expression {:?}", expr))),
@@ -645,7 +666,8 @@ mod tests {
     #[test]
     fn test_type_mapping() {
         let codegen = InkCodegen::new();
-        assert_eq!(codegen.map_type(&Type::Simple("uint256".to_string())), "U256");
+        // ink! v5 storage has no U256 layout, so uint256 maps to u128
+        assert_eq!(codegen.map_type(&Type::Simple("uint256".to_string())), "u128");
         assert_eq!(codegen.map_type(&Type::Simple("address".to_string())), "AccountId");
         assert_eq!(codegen.map_type(&Type::Simple("bool".to_string())), "bool");
     }
@@ -14,6 +14,8 @@ impl QuorlinCodegen {
     }
 
     pub fn generate(&mut self, module: &Module) -> Result<Vec<u8>, String> {
+        let module = &module.for_target("quorlin");
+
         // Generate header
         self.output.push_str("# Quorlin Bytecode\n");
         self.output.push_str("# Magic: QBC\\0\n");
@@ -47,7 +49,7 @@ impl QuorlinCodegen {
                 self.output.push_str(&format!("# State: {} : {:?}\n", var.name, var.type_annotation));
             }
         }
-        self.output.push_str("\n");
+        self.output.push('\n');
 
         // Generate functions
         for member in &contract.body {
@@ -122,7 +124,7 @@ impl QuorlinCodegen {
         for param in &event.params {
             self.output.push_str(&format!("#   {} : {:?}\n", param.name, param.type_annotation));
         }
-        self.output.push_str("\n");
+        self.output.push('\n');
         Ok(())
     }
 }
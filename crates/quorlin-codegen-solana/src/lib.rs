@@ -4,6 +4,7 @@
 //!
 //! This crate generates Rust/Anchor code from Quorlin AST for Solana programs.
 
+use quorlin_parser::type_names::canonical_name;
 use quorlin_parser::{Module, ContractMember, Expr, Stmt, BinOp, Type};
 use std::collections::HashMap;
 
@@ -16,7 +17,7 @@ pub enum CodegenError {
     #[error("Unsupported feature: {0}")]
     UnsupportedFeature(String),
 
-    #[error("Contract not found")]
+    #[error("No contract to compile")]
     ContractNotFound,
 }
 
@@ -53,6 +54,8 @@ impl SolanaCodegen {
 
     /// Generate Anchor/Rust code from a module
     pub fn generate(&mut self, module: &Module) -> CodegenResult<String> {
+        let module = &module.for_target("solana");
+
         // Find the contract
         let contract = module
             .items
@@ -145,7 +148,7 @@ impl SolanaCodegen {
     /// Map Quorlin types to Solana/Rust types
     fn map_type(&self, ty: &Type) -> String {
         match ty {
-            Type::Simple(s) => match s.as_str() {
+            Type::Simple(s) => match canonical_name(s) {
                 "uint8" => "u8".to_string(),
                 "uint16" => "u16".to_string(),
                 "uint32" => "u32".to_string(),
@@ -160,7 +163,7 @@ impl SolanaCodegen {
                 "int256" => "i128".to_string(),
                 "bool" => "bool".to_string(),
                 "address" => "Pubkey".to_string(),
-                "str" => "String".to_string(),
+                "string" => "String".to_string(),
                 "bytes" => "Vec<u8>".to_string(),
                 _ => s.clone(),
             },
@@ -174,6 +177,9 @@ impl SolanaCodegen {
             Type::FixedArray(inner, size) => {
                 format!("[{}; {}]", self.map_type(inner), size)
             }
+            Type::ConstArray(inner, size) => {
+                format!("[{}; {}]", self.map_type(inner), size)
+            }
             Type::Optional(inner) => {
                 format!("Option<{}>", self.map_type(inner))
             }
@@ -258,7 +264,7 @@ impl SolanaCodegen {
                 code.push_str("> {\n");
 
                 code.push_str("        let contract = &mut ctx.accounts.contract;\n");
-                code.push_str(&format!("        let signer = ctx.accounts.signer.key();\n\n"));
+                code.push_str("        let signer = ctx.accounts.signer.key();\n\n");
 
                 // Function body
                 for stmt in &func.body {
@@ -401,6 +407,9 @@ impl SolanaCodegen {
             Stmt::Require(req) => {
                 let cond = self.generate_expression(&req.condition)?;
                 if let Some(msg) = &req.message {
+                    let Expr::StringLiteral(msg) = msg else {
+                        return Err(CodegenError::UnsupportedFeature(format!("Require message {:?}", msg)));
+                    };
                     // Map error messages to ErrorCode variants
                     let error_code = match msg.as_str() {
                         "Insufficient balance" => "ErrorCode::InsufficientBalance",
@@ -458,6 +467,9 @@ impl SolanaCodegen {
                 code.push_str(&format!("{}}}\n", indent_str));
             }
             Stmt::While(while_stmt) => {
+                if while_stmt.else_branch.is_some() {
+                    return Err(CodegenError::UnsupportedFeature("else clause on while loops".to_string()));
+                }
                 let cond = self.generate_expression(&while_stmt.condition)?;
                 code.push_str(&format!("{}while {} {{\n", indent_str, cond));
 
@@ -483,7 +495,7 @@ impl SolanaCodegen {
         match expr {
             Expr::IntLiteral(n) => Ok(n.clone()),
             Expr::BoolLiteral(b) => Ok(b.to_string()),
-            Expr::StringLiteral(s) => Ok(format!("\"{}\"", s)),
+            Expr::StringLiteral(s) => Ok(format!("{:?}", s)),
             Expr::Ident(name) => {
                 // Check if it's a state variable
                 if self.account_fields.contains_key(name) {
@@ -492,10 +504,31 @@ impl SolanaCodegen {
                     Ok(name.clone())
                 }
             }
+            Expr::BinOp(key, BinOp::In, mapping) => {
+                let key_code = self.generate_expression(key)?;
+                let mapping_code = self.generate_expression(mapping)?;
+                Ok(format!("{}.iter().any(|(k, _)| k == &{})", mapping_code, key_code))
+            }
             Expr::BinOp(left, op, right) => {
                 let left_code = self.generate_expression(left)?;
                 let right_code = self.generate_expression(right)?;
 
+                // Integer division truncates; floor steps down when a remainder's sign differs from the divisor's
+                if matches!(op, BinOp::FloorDiv) {
+                    return Ok(format!(
+                        "{{ let (a, b) = ({}, {}); let r = a % b; if r != 0 && (r > 0) != (b > 0) {{ a / b - 1 }} else {{ a / b }} }}",
+                        left_code, right_code
+                    ));
+                }
+                // A shift amount beyond u32 is an overflow, not a truncated shift
+                if let BinOp::Shl | BinOp::Shr = op {
+                    let method = if matches!(op, BinOp::Shl) { "checked_shl" } else { "checked_shr" };
+                    return Ok(format!(
+                        "u32::try_from({}).ok().and_then(|shift| {}.{}(shift)).expect(\"shift overflow\")",
+                        right_code, left_code, method
+                    ));
+                }
+
                 let op_str = match op {
                     BinOp::Add => "+",
                     BinOp::Sub => "-",
@@ -510,6 +543,9 @@ impl SolanaCodegen {
                     BinOp::GtEq => ">=",
                     BinOp::And => "&&",
                     BinOp::Or => "||",
+                    BinOp::BitAnd => "&",
+                    BinOp::BitOr => "|",
+                    BinOp::BitXor => "^",
                     _ => return Err(CodegenError::UnsupportedFeature(format!("BinOp {:?}", op))),
                 };
 
@@ -600,6 +636,8 @@ impl SolanaCodegen {
                     quorlin_parser::UnaryOp::Not => Ok(format!("!{}", operand_code)),
                     quorlin_parser::UnaryOp::Neg => Ok(format!("-{}", operand_code)),
                     quorlin_parser::UnaryOp::Pos => Ok(format!("+{}", operand_code)),
+                    // `!` on an integer is its bitwise complement in Rust
+                    quorlin_parser::UnaryOp::BitNot => Ok(format!("!{}", operand_code)),
                 }
             }
             _ => Err(CodegenError::UnsupportedFeature(format!("Expression {:?}", expr))),
@@ -607,7 +645,7 @@ impl SolanaCodegen {
     }
 
     /// Check if value expression references the assignment target (to avoid borrow checker issues)
-    fn value_references_target(&self, value: &Expr, target: &Expr, index: &Expr) -> bool {
+    fn value_references_target(&self, value: &Expr, _target: &Expr, _index: &Expr) -> bool {
         // For now, conservatively return true for any Call expression (safe_add, safe_sub)
         // that might reference the target mapping
         match value {
@@ -752,4 +790,31 @@ mod tests {
         assert_eq!(codegen.map_type(&Type::Simple("address".to_string())), "Pubkey");
         assert_eq!(codegen.map_type(&Type::Simple("bool".to_string())), "bool");
     }
+
+    #[test]
+    fn test_token_emit_produces_anchor_event() {
+        let source = include_str!("../../../examples/token.ql");
+        let tokens = quorlin_lexer::Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        let code = SolanaCodegen::new().generate(&module).unwrap();
+
+        assert!(code.contains("emit!(TransferEvent {"));
+        assert!(code.contains("#[event]\npub struct TransferEvent {"));
+    }
+
+    #[test]
+    fn test_shift_rejects_out_of_range_amounts() {
+        let source = "contract Bits:\n    @external\n    fn shift(x: uint256, n: uint256) -> uint256:\n        return (x << n) >> 1\n";
+        let tokens = quorlin_lexer::Lexer::new(source).tokenize().unwrap();
+        let module = quorlin_parser::parse_module(tokens).unwrap();
+        let code = SolanaCodegen::new().generate(&module).unwrap();
+
+        let shl = "u32::try_from(n).ok().and_then(|shift| x.checked_shl(shift)).expect(\"shift overflow\")";
+        assert!(code.contains(shl), "{}", code);
+        assert!(
+            code.contains(&format!("u32::try_from(1).ok().and_then(|shift| {}.checked_shr(shift))", shl)),
+            "{}",
+            code
+        );
+    }
 }
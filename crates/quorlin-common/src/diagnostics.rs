@@ -57,9 +57,7 @@ impl Diagnostic {
             );
 
             // Extract the relevant line
-            let lines: Vec<&str> = source.lines().collect();
-            if span.line > 0 && span.line <= lines.len() {
-                let line = lines[span.line - 1];
+            if let Some((line, column_offset, length)) = underline(source, &span) {
                 eprintln!("   {}", "|".blue().bold());
                 eprintln!(
                     "{:>3} {} {}",
@@ -69,8 +67,6 @@ impl Diagnostic {
                 );
 
                 // Add underline
-                let column_offset = span.column.saturating_sub(1);
-                let length = (span.end - span.start).max(1);
                 eprintln!(
                     "   {} {}{}",
                     "|".blue().bold(),
@@ -87,3 +83,44 @@ impl Diagnostic {
         eprintln!();
     }
 }
+
+/// The source line containing `span`, with the underline's offset and width
+/// in characters. Spans carry byte columns; they are converted without ever
+/// slicing inside a multi-byte character, so a span whose edges fall
+/// mid-character still yields a best-effort underline.
+pub fn underline<'a>(source: &'a str, span: &Span) -> Option<(&'a str, usize, usize)> {
+    let line = source.lines().nth(span.line.checked_sub(1)?)?;
+
+    let start = span.column.saturating_sub(1).min(line.len());
+    let end = start.saturating_add(span.end.saturating_sub(span.start));
+    let offset = line.char_indices().take_while(|(i, _)| *i < start).count();
+    let length = line
+        .char_indices()
+        .filter(|(i, _)| (start..end).contains(i))
+        .count()
+        .max(1);
+
+    Some((line, offset, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_underline_after_multibyte_character() {
+        let source = "let s = \"h\u{e9}llo\" + oops";
+        // `oops` starts at byte 19 (column 20) since é is two bytes wide
+        let span = Span::new(19, 23, 1, 20);
+
+        let (line, offset, length) = underline(source, &span).unwrap();
+        assert_eq!(line, source);
+        assert_eq!(offset, 18);
+        assert_eq!(length, 4);
+
+        // A span that starts inside é must not panic
+        let misaligned = Span::new(10, 12, 1, 11);
+        assert!(underline(source, &misaligned).is_some());
+        Diagnostic::error("misaligned").with_span(misaligned).display(source, "test.ql");
+    }
+}
@@ -3,6 +3,7 @@
 //! Common utilities shared across the Quorlin compiler.
 
 pub mod diagnostics;
+pub mod severity;
 pub mod span;
 
 // Re-export commonly used types
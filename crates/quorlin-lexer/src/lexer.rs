@@ -1,5 +1,5 @@
 use crate::indent::IndentProcessor;
-use crate::token::{Span, Token, TokenType};
+use crate::token::{FStringPart, Span, Token, TokenType};
 use logos::Logos;
 
 /// Errors that can occur during lexical analysis
@@ -13,6 +13,16 @@ pub enum LexerError {
 
     #[error("Unterminated string at line {line}, column {column}")]
     UnterminatedString { line: usize, column: usize },
+
+    #[error("Invalid escape sequence '{sequence}' at line {line}, column {column}")]
+    InvalidEscape {
+        sequence: String,
+        line: usize,
+        column: usize,
+    },
+
+    #[error("Unbalanced braces in f-string at line {line}, column {column}")]
+    InvalidFString { line: usize, column: usize },
 }
 
 /// The main lexer for Quorlin source code
@@ -47,17 +57,20 @@ impl<'source> Lexer<'source> {
             let start = span.start;
             let end = span.end;
 
-            // Calculate line and column
-            let source_before = &self.source[line_start..start];
+            // Calculate line and column (a byte column within the line).
+            // `get` keeps a misaligned offset from panicking mid-character.
+            let source_before = self.source.get(line_start..start).unwrap_or("");
             let newlines_before = source_before.matches('\n').count();
             if newlines_before > 0 {
                 line += newlines_before;
-                line_start = self.source[..start]
-                    .rfind('\n')
+                line_start = self
+                    .source
+                    .get(..start)
+                    .and_then(|before| before.rfind('\n'))
                     .map(|pos| pos + 1)
                     .unwrap_or(0);
             }
-            let column = start - line_start + 1;
+            let column = start.saturating_sub(line_start) + 1;
 
             let token_span = Span::new(start, end, line, column);
 
@@ -68,34 +81,55 @@ impl<'source> Lexer<'source> {
                         TokenType::LParen | TokenType::LBracket | TokenType::LBrace => {
                             nesting_level += 1;
                         }
-                        TokenType::RParen | TokenType::RBracket | TokenType::RBrace => {
-                            if nesting_level > 0 {
-                                nesting_level -= 1;
-                            }
+                        TokenType::RParen | TokenType::RBracket | TokenType::RBrace
+                            if nesting_level > 0 =>
+                        {
+                            nesting_level -= 1;
                         }
-                        TokenType::Newline => {
-                            if nesting_level > 0 {
-                                // Skip newlines inside brackets/parentheses
-                                continue;
-                            }
+                        TokenType::Newline if nesting_level > 0 => {
+                            // Skip newlines inside brackets/parentheses
+                            continue;
                         }
                         _ => {}
                     }
 
-                    // Filter out certain tokens or process them
-                    match &token_type {
-                        TokenType::Ident(name) => {
-                            // Check if identifier is a keyword that logos missed
-                            let actual_type = match name.as_str() {
-                                // This is a fallback in case logos doesn't catch everything
-                                _ => token_type,
-                            };
-                            tokens.push(Token::new(actual_type, token_span));
-                        }
-                        _ => {
-                            tokens.push(Token::new(token_type, token_span));
-                        }
-                    }
+                    // Interpret escape sequences in string literals
+                    let token_type = match token_type {
+                        TokenType::StringLiteral(raw) => TokenType::StringLiteral(
+                            unescape(&raw).map_err(|sequence| LexerError::InvalidEscape {
+                                sequence,
+                                line,
+                                column,
+                            })?,
+                        ),
+                        TokenType::StringLiteralSingle(raw) => TokenType::StringLiteralSingle(
+                            unescape(&raw).map_err(|sequence| LexerError::InvalidEscape {
+                                sequence,
+                                line,
+                                column,
+                            })?,
+                        ),
+                        TokenType::FString(parts) => TokenType::FString(
+                            parts
+                                .into_iter()
+                                .map(|part| match part {
+                                    FStringPart::Literal(raw) => unescape(&raw).map(FStringPart::Literal),
+                                    hole => Ok(hole),
+                                })
+                                .collect::<Result<_, _>>()
+                                .map_err(|sequence| LexerError::InvalidEscape {
+                                    sequence,
+                                    line,
+                                    column,
+                                })?,
+                        ),
+                        other => other,
+                    };
+
+                    tokens.push(Token::new(token_type, token_span));
+                }
+                Err(_) if lexer.slice().starts_with("f\"") => {
+                    return Err(LexerError::InvalidFString { line, column });
                 }
                 Err(_) => {
                     return Err(LexerError::InvalidToken { line, column });
@@ -107,6 +141,126 @@ impl<'source> Lexer<'source> {
     }
 }
 
+/// Split an f-string body into literal text and `{expr}` holes.
+///
+/// `{{` and `}}` stand for literal braces. A hole ends at the `}` that
+/// balances its opening brace, so holes may contain braces of their own.
+/// Literal text keeps its escape sequences for [`unescape`]. Returns `None`
+/// for a stray `}`, an empty hole or a hole that is never closed.
+pub(crate) fn split_fstring(body: &str) -> Option<Vec<FStringPart>> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                literal.push(c);
+                literal.extend(chars.next());
+            }
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return None,
+            '{' => {
+                let mut hole = String::new();
+                let mut depth = 1;
+                loop {
+                    let d = chars.next()?;
+                    match d {
+                        '{' => depth += 1,
+                        '}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    hole.push(d);
+                }
+                if hole.trim().is_empty() {
+                    return None;
+                }
+                if !literal.is_empty() {
+                    parts.push(FStringPart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(FStringPart::Hole(hole));
+            }
+            _ => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        parts.push(FStringPart::Literal(literal));
+    }
+    Some(parts)
+}
+
+/// Interpret the escape sequences of a string literal body.
+///
+/// Supports `\n`, `\t`, `\r`, `\0`, `\\`, `\"`, `\'`, `\xNN` (ASCII only) and `\u{...}`.
+/// On failure the offending sequence is returned.
+fn unescape(raw: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('0') => result.push('\0'),
+            Some('\\') => result.push('\\'),
+            Some('"') => result.push('"'),
+            Some('\'') => result.push('\''),
+            Some('x') => {
+                let digits: String = chars.by_ref().take(2).collect();
+                let value = u8::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|_| digits.len() == 2 && digits.chars().all(|d| d.is_ascii_hexdigit()))
+                    .filter(|value| value.is_ascii())
+                    .ok_or_else(|| format!("\\x{}", digits))?;
+                result.push(value as char);
+            }
+            Some('u') => {
+                if chars.next() != Some('{') {
+                    return Err("\\u".to_string());
+                }
+                let mut digits = String::new();
+                let mut closed = false;
+                for d in chars.by_ref() {
+                    if d == '}' {
+                        closed = true;
+                        break;
+                    }
+                    digits.push(d);
+                }
+                let value = Some(&digits)
+                    .filter(|digits| closed && (1..=6).contains(&digits.len()))
+                    .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("\\u{{{}", digits))?;
+                result.push(value);
+            }
+            Some(other) => return Err(format!("\\{}", other)),
+            None => return Err("\\".to_string()),
+        }
+    }
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,6 +316,36 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_string_escapes() {
+        let source = r#""a\nb" '\x41\u{1F600}\''"#;
+        let tokens = Lexer::new(source).raw_tokenize().unwrap();
+
+        match &tokens[0].token_type {
+            TokenType::StringLiteral(s) => {
+                assert_eq!(s.chars().count(), 3);
+                assert_eq!(s, "a\nb");
+            }
+            other => panic!("Expected string literal, got {:?}", other),
+        }
+        assert_eq!(
+            tokens[1].token_type,
+            TokenType::StringLiteralSingle("A\u{1F600}'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_string_escape() {
+        for source in [r#""\q""#, r#""\xZZ""#, r#""\u{110000}""#, r#""\u{41""#] {
+            let result = Lexer::new(source).raw_tokenize();
+            assert!(
+                matches!(result, Err(LexerError::InvalidEscape { .. })),
+                "{} should be rejected",
+                source
+            );
+        }
+    }
+
     #[test]
     fn test_type_annotations() {
         let source = "amount: uint256";
@@ -193,6 +377,40 @@ mod tests {
         assert_eq!(tokens[10].token_type, TokenType::Not);
     }
 
+    #[test]
+    fn test_bitwise_operators() {
+        let tokens = Lexer::new("& | ^ ~ << >>").raw_tokenize().unwrap();
+
+        let types: Vec<_> = tokens.iter().map(|t| t.token_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                TokenType::Ampersand,
+                TokenType::Pipe,
+                TokenType::Caret,
+                TokenType::Tilde,
+                TokenType::ShiftLeft,
+                TokenType::ShiftRight,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_floor_div_is_not_a_comment() {
+        let source = "a // b # comment";
+        let tokens = Lexer::new(source).raw_tokenize().unwrap();
+
+        let types: Vec<_> = tokens.iter().map(|t| t.token_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                TokenType::Ident("a".to_string()),
+                TokenType::SlashSlash,
+                TokenType::Ident("b".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_full_function() {
         let source = r#"
@@ -212,4 +430,46 @@ def transfer(to: address, amount: uint256) -> bool:
         assert!(has_indent, "Should have INDENT token");
         assert!(has_dedent, "Should have DEDENT token");
     }
+
+    #[test]
+    fn test_error_after_multibyte_character() {
+        let source = "x = \"h\u{e9}llo\" \u{a7}";
+        match Lexer::new(source).tokenize() {
+            Err(LexerError::InvalidToken { line, column }) => {
+                assert_eq!(line, 1);
+                // Byte column: the string literal's é takes two bytes
+                assert_eq!(column, 14);
+            }
+            other => panic!("expected invalid token, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fstring_splits_literals_and_holes() {
+        let tokens = Lexer::new(r#"f"{{total}}: {amounts[{i}]} of {cap}\n""#).raw_tokenize().unwrap();
+        assert_eq!(
+            tokens[0].token_type,
+            TokenType::FString(vec![
+                FStringPart::Literal("{total}: ".to_string()),
+                FStringPart::Hole("amounts[{i}]".to_string()),
+                FStringPart::Literal(" of ".to_string()),
+                FStringPart::Hole("cap".to_string()),
+                FStringPart::Literal("\n".to_string()),
+            ])
+        );
+        // A plain `f` is still an identifier
+        let tokens = Lexer::new("f(x)").raw_tokenize().unwrap();
+        assert_eq!(tokens[0].token_type, TokenType::Ident("f".to_string()));
+    }
+
+    #[test]
+    fn test_fstring_rejects_unbalanced_braces() {
+        for source in [r#"f"{x""#, r#"f"x}""#, r#"f"{}""#, r#"f"{a{b}""#] {
+            assert!(
+                matches!(Lexer::new(source).raw_tokenize(), Err(LexerError::InvalidFString { line: 1, column: 1 })),
+                "{} should be rejected",
+                source
+            );
+        }
+    }
 }
@@ -28,4 +28,4 @@ pub mod token;
 
 // Re-export main types for convenience
 pub use lexer::{Lexer, LexerError};
-pub use token::{Span, Token, TokenType};
+pub use token::{FStringPart, Span, Token, TokenType};
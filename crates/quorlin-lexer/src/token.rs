@@ -193,6 +193,14 @@ pub enum TokenType {
     })]
     StringLiteralSingle(String),
 
+    // Formatted strings: f"total {amount}". Longer than the `f` identifier,
+    // so logos prefers it. Unbalanced braces make the token invalid.
+    #[regex(r#"f"([^"\\]|\\.)*""#, |lex| {
+        let s = lex.slice();
+        crate::lexer::split_fstring(&s[2..s.len()-1])
+    })]
+    FString(Vec<FStringPart>),
+
     // ═══════════════════════════════════════════════════════════
     // OPERATORS & PUNCTUATION (Python-compatible)
     // ═══════════════════════════════════════════════════════════
@@ -210,10 +218,8 @@ pub enum TokenType {
     #[token("/")]
     Slash,
 
-    // Note: // is used for comments, not floor division
-    // For floor division, use explicit div() function
-    // #[token("//")]
-    // FloorDiv,
+    #[token("//")]
+    SlashSlash,
 
     #[token("%")]
     Percent,
@@ -221,6 +227,25 @@ pub enum TokenType {
     #[token("**")]
     DoubleStar,
 
+    #[token("<<")]
+    ShiftLeft,
+
+    #[token(">>")]
+    ShiftRight,
+
+    // Bitwise operators
+    #[token("&")]
+    Ampersand,
+
+    #[token("|")]
+    Pipe,
+
+    #[token("^")]
+    Caret,
+
+    #[token("~")]
+    Tilde,
+
     // Comparison operators
     #[token("==")]
     EqEq,
@@ -299,9 +324,8 @@ pub enum TokenType {
     #[regex(r"\r?\n")]
     Newline,
 
-    // Comments (skip) - supports both # and //
+    // Comments (skip) - `#` only, `//` is floor division
     #[regex(r"#[^\n]*", logos::skip)]
-    #[regex(r"//[^\n]*", logos::skip)]
     Comment,
 
     // Indentation tokens (generated by preprocessor, not by lexer)
@@ -312,6 +336,13 @@ pub enum TokenType {
     Eof,
 }
 
+/// A piece of an f-string: literal text, or the source of an `{expr}` hole
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FStringPart {
+    Literal(String),
+    Hole(String),
+}
+
 /// A token with location information
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Token {
@@ -320,7 +351,7 @@ pub struct Token {
 }
 
 /// Source code location
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Span {
     pub start: usize,
     pub end: usize,
@@ -2,6 +2,8 @@
 //!
 //! This module defines the structure of parsed Quorlin programs.
 
+use crate::visit::{self, Visitor};
+use quorlin_lexer::Span;
 use serde::{Deserialize, Serialize};
 
 /// A complete Quorlin source file
@@ -10,6 +12,22 @@ pub struct Module {
     pub items: Vec<Item>,
 }
 
+impl Module {
+    /// Copy of this module without the functions gated to other backends by `@target(...)`
+    pub fn for_target(&self, backend: &str) -> Module {
+        let mut module = self.clone();
+        for item in &mut module.items {
+            if let Item::Contract(contract) = item {
+                contract.body.retain(|member| match member {
+                    ContractMember::Function(func) => func.is_enabled_for(backend),
+                    _ => true,
+                });
+            }
+        }
+        module
+    }
+}
+
 /// Top-level items in a Quorlin file
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Item {
@@ -20,13 +38,31 @@ pub enum Item {
     Interface(InterfaceDecl),
     Event(EventDecl),
     Error(ErrorDecl),
+    Const(Constant),
 }
 
 /// Import statement: `from std.math import safe_add, safe_sub`
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportStmt {
+    /// Dotted module path without the leading dots of a relative import
     pub module: String,
     pub items: Vec<String>,
+    /// Leading dots: 0 for absolute imports, 1 for the importing file's
+    /// directory (`from .utils`), 2 for its parent (`from ..utils`), ...
+    #[serde(default)]
+    pub level: usize,
+}
+
+impl ImportStmt {
+    /// Whether the module is resolved against the importing file's directory
+    pub fn is_relative(&self) -> bool {
+        self.level > 0
+    }
+
+    /// The module path as written, including leading dots
+    pub fn source_path(&self) -> String {
+        format!("{}{}", ".".repeat(self.level), self.module)
+    }
 }
 
 /// Contract declaration
@@ -36,6 +72,9 @@ pub struct ContractDecl {
     pub bases: Vec<String>,
     pub body: Vec<ContractMember>,
     pub docstring: Option<String>,
+    /// Where the name is declared
+    #[serde(default)]
+    pub span: Span,
 }
 
 /// Contract member (state variables, functions, etc.)
@@ -52,17 +91,171 @@ pub struct StateVar {
     pub name: String,
     pub type_annotation: Type,
     pub initial_value: Option<Expr>,
+    /// Where the name is declared
+    #[serde(default)]
+    pub span: Span,
+}
+
+/// Decorator on a function: `@view` or `@target("evm", "ink")`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decorator {
+    pub name: String,
+    /// Arguments given in parentheses, if any
+    pub args: Vec<Expr>,
+}
+
+impl Decorator {
+    /// The arguments that are string literals, in order
+    pub fn string_args(&self) -> impl Iterator<Item = &str> {
+        self.args.iter().filter_map(|arg| match arg {
+            Expr::StringLiteral(value) => Some(value.as_str()),
+            _ => None,
+        })
+    }
 }
 
 /// Function definition
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Function {
     pub name: String,
-    pub decorators: Vec<String>,
+    pub decorators: Vec<Decorator>,
     pub params: Vec<Param>,
     pub return_type: Option<Type>,
     pub body: Vec<Stmt>,
     pub docstring: Option<String>,
+    /// Where the name is declared
+    #[serde(default)]
+    pub span: Span,
+}
+
+impl Function {
+    /// Whether the function carries the decorator `@name`
+    pub fn has_decorator(&self, name: &str) -> bool {
+        self.decorators.iter().any(|decorator| decorator.name == name)
+    }
+
+    /// Backends named by a `@target("evm", ...)` decorator, if present
+    pub fn target_backends(&self) -> Option<Vec<&str>> {
+        self.decorators
+            .iter()
+            .find(|decorator| decorator.name == "target")
+            .map(|decorator| decorator.string_args().collect())
+    }
+
+    /// Constraint sources from `@validate("amount > 0", ...)` decorators, in
+    /// declaration order
+    pub fn validations(&self) -> Vec<String> {
+        self.decorators
+            .iter()
+            .filter(|decorator| decorator.name == "validate")
+            .flat_map(Decorator::string_args)
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Whether the body mentions `self` anywhere: reading or writing state or
+    /// calling another member through it. Functions that don't are static
+    /// utilities.
+    pub fn references_self(&self) -> bool {
+        struct SelfFinder(bool);
+
+        impl<'ast> Visitor<'ast> for SelfFinder {
+            fn visit_expr(&mut self, expr: &'ast Expr) {
+                if matches!(expr, Expr::Ident(name) if name == "self") {
+                    self.0 = true;
+                }
+                visit::walk_expr(self, expr);
+            }
+        }
+
+        let mut finder = SelfFinder(false);
+        for stmt in &self.body {
+            finder.visit_stmt(stmt);
+        }
+        finder.0
+    }
+
+    /// Whether this function is emitted by `backend` (canonical backend name)
+    pub fn is_enabled_for(&self, backend: &str) -> bool {
+        self.target_backends()
+            .is_none_or(|targets| targets.iter().any(|t| canonical_backend(t) == Some(backend)))
+    }
+}
+
+/// Arguments of a call to `function` in parameter order: keyword arguments
+/// are placed by name and omitted parameters take their defaults
+pub fn bind_args(function: &str, params: &[Param], args: &[Expr]) -> Result<Vec<Expr>, String> {
+    let mut bound: Vec<Option<Expr>> = vec![None; params.len()];
+    let mut positional = 0;
+    for arg in args {
+        let (index, value) = match arg {
+            Expr::KeywordArg(name, value) => {
+                let index = params
+                    .iter()
+                    .position(|param| &param.name == name)
+                    .ok_or_else(|| format!("Function '{}' has no parameter named '{}'", function, name))?;
+                (index, &**value)
+            }
+            value => {
+                positional += 1;
+                (positional - 1, value)
+            }
+        };
+        let Some(slot) = bound.get_mut(index) else {
+            return Err(format!(
+                "Function '{}' takes {} argument(s) but {} were given",
+                function,
+                params.len(),
+                args.len()
+            ));
+        };
+        if slot.is_some() {
+            return Err(format!("Argument '{}' given more than once in call to '{}'", params[index].name, function));
+        }
+        *slot = Some(value.clone());
+    }
+
+    bound
+        .into_iter()
+        .zip(params)
+        .map(|(value, param)| {
+            value
+                .or_else(|| param.default.clone())
+                .ok_or_else(|| format!("Missing argument '{}' in call to '{}'", param.name, function))
+        })
+        .collect()
+}
+
+/// The text of an f-string whose holes are all known at compile time.
+/// Holes may be literals or names (bare or `self.NAME`) that `resolve` maps
+/// to a literal; the first other hole is returned as the error.
+pub fn fold_fstring<'a, 'c>(parts: &'a [Expr], resolve: impl Fn(&str) -> Option<&'c Expr>) -> Result<String, &'a Expr> {
+    let mut text = String::new();
+    for part in parts {
+        let value = match part {
+            Expr::Ident(name) => resolve(name),
+            Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => resolve(name),
+            _ => None,
+        };
+        match value.unwrap_or(part) {
+            Expr::StringLiteral(s) | Expr::IntLiteral(s) | Expr::HexLiteral(s) => text.push_str(s),
+            Expr::BoolLiteral(b) => text.push_str(if *b { "True" } else { "False" }),
+            _ => return Err(part),
+        }
+    }
+    Ok(text)
+}
+
+/// Map a backend name or alias to its canonical name
+pub fn canonical_backend(name: &str) -> Option<&'static str> {
+    match name {
+        "evm" | "ethereum" => Some("evm"),
+        "solana" => Some("solana"),
+        "ink" | "polkadot" => Some("ink"),
+        "aptos" | "move" => Some("aptos"),
+        "quorlin" | "bytecode" => Some("quorlin"),
+        _ => None,
+    }
 }
 
 /// Function parameter
@@ -82,6 +275,8 @@ pub enum Type {
     List(Box<Type>),
     /// Fixed array: T[N]
     FixedArray(Box<Type>, usize),
+    /// Fixed array sized by a named constant: T[SIZE] (resolved during semantic analysis)
+    ConstArray(Box<Type>, String),
     /// Mapping: mapping[K, V]
     Mapping(Box<Type>, Box<Type>),
     /// Optional: Optional[T]
@@ -94,7 +289,6 @@ pub enum Type {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Stmt {
     Assign(AssignStmt),
-    AugAssign(AugAssignStmt),
     Expr(Expr),
     Return(Option<Expr>),
     Pass,
@@ -117,22 +311,6 @@ pub struct AssignStmt {
     pub value: Expr,
 }
 
-/// Augmented assignment: `x += 10` (NOTE: Currently unused - parser desugars to Assign)
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct AugAssignStmt {
-    pub target: String,
-    pub op: AugAssignOp,
-    pub value: Expr,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum AugAssignOp {
-    Add,
-    Sub,
-    Mul,
-    Div,
-}
-
 /// If statement
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IfStmt {
@@ -148,6 +326,8 @@ pub struct ForStmt {
     pub variable: String,
     pub iterable: Expr,
     pub body: Vec<Stmt>,
+    /// Runs when the loop finishes without `break`
+    pub else_branch: Option<Vec<Stmt>>,
 }
 
 /// While loop
@@ -155,13 +335,18 @@ pub struct ForStmt {
 pub struct WhileStmt {
     pub condition: Expr,
     pub body: Vec<Stmt>,
+    /// Runs when the loop finishes without `break`
+    pub else_branch: Option<Vec<Stmt>>,
 }
 
 /// Require statement: `require(condition, "message")`
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RequireStmt {
     pub condition: Expr,
-    pub message: Option<String>,
+    /// A string literal or f-string
+    pub message: Option<Expr>,
+    /// Custom error to revert with instead of a message: `require(ok, Err(x))`
+    pub error: Option<RaiseStmt>,
 }
 
 /// Emit statement: `emit Transfer(from, to, amount)`
@@ -218,6 +403,50 @@ pub enum Expr {
         body: Box<Expr>,
         orelse: Box<Expr>,
     },
+
+    /// Keyword argument `amount=100`; only appears among a call's arguments
+    KeywordArg(String, Box<Expr>),
+
+    /// List comprehension: `[x * 2 for x in items if x > 0]`
+    ListComp {
+        element: Box<Expr>,
+        variable: String,
+        iterable: Box<Expr>,
+        condition: Option<Box<Expr>>,
+    },
+
+    /// Formatted string `f"total {amount}"`: string literals and
+    /// interpolated expressions, in order
+    FString(Vec<Expr>),
+}
+
+impl Expr {
+    /// The state variable written when this is an assignment target:
+    /// `self.x`, or a part of it such as `self.x[key]` or `self.x.field`
+    pub fn assigned_state_var(&self) -> Option<&str> {
+        match self {
+            Expr::Attribute(base, attr) if matches!(&**base, Expr::Ident(name) if name == "self") => Some(attr),
+            Expr::Index(base, _) | Expr::Attribute(base, _) => base.assigned_state_var(),
+            _ => None,
+        }
+    }
+
+    /// Whether evaluating this expression may call a function
+    pub fn contains_call(&self) -> bool {
+        match self {
+            Expr::Call(_, _) => true,
+            Expr::Attribute(base, _) => base.contains_call(),
+            Expr::Index(base, index) => base.contains_call() || index.contains_call(),
+            Expr::BinOp(left, _, right) => left.contains_call() || right.contains_call(),
+            Expr::UnaryOp(_, operand) | Expr::KeywordArg(_, operand) => operand.contains_call(),
+            Expr::IfExp { test, body, orelse } => test.contains_call() || body.contains_call() || orelse.contains_call(),
+            Expr::List(items) | Expr::Tuple(items) | Expr::FString(items) => items.iter().any(Expr::contains_call),
+            Expr::ListComp { element, iterable, condition, .. } => {
+                element.contains_call() || iterable.contains_call() || condition.as_ref().is_some_and(|c| c.contains_call())
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -229,6 +458,11 @@ pub enum BinOp {
     FloorDiv,
     Mod,
     Pow,
+    Shl,
+    Shr,
+    BitAnd,
+    BitOr,
+    BitXor,
     Eq,
     NotEq,
     Lt,
@@ -237,6 +471,8 @@ pub enum BinOp {
     GtEq,
     And,
     Or,
+    /// Mapping membership: `key in self.mapping`
+    In,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -244,6 +480,8 @@ pub enum UnaryOp {
     Not,
     Neg,
     Pos,
+    /// Bitwise complement: `~flags`
+    BitNot,
 }
 
 /// Struct declaration
@@ -11,12 +11,16 @@
 // Quorlin Parser Library
 pub mod ast;
 pub mod parser;
+pub mod pretty;
+pub mod type_names;
+pub mod visit;
 
-use quorlin_lexer::Token;
+use quorlin_lexer::{Lexer, Token};
 
 // Re-export main types
 pub use ast::*;
 pub use parser::Parser;
+pub use visit::Visitor;
 
 /// Parser errors
 #[derive(Debug, thiserror::Error)]
@@ -34,6 +38,14 @@ pub fn parse_module(tokens: Vec<Token>) -> Result<Module, ParseError> {
     parser.parse_module()
 }
 
+/// Parse a standalone expression, such as an `@validate` constraint
+pub fn parse_expression(source: &str) -> Result<Expr, ParseError> {
+    let tokens = Lexer::new(source)
+        .tokenize()
+        .map_err(|e| ParseError::UnexpectedToken(0, e.to_string()))?;
+    Parser::new(tokens).parse_standalone_expr()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,4 +89,761 @@ event Transfer(from_addr: address, to_addr: address, value: uint256)
             _ => panic!("Expected event item"),
         }
     }
+
+    #[test]
+    fn test_parse_interleaved_assignment_target() {
+        let source = r#"
+contract Bank:
+    fn close(id: uint256):
+        self.accounts[id].balance = 0
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Contract(contract) = &module.items[0] else {
+            panic!("Expected contract item");
+        };
+        let ContractMember::Function(func) = &contract.body[0] else {
+            panic!("Expected function member");
+        };
+        let Stmt::Assign(assign) = &func.body[0] else {
+            panic!("Expected assignment");
+        };
+
+        let expected = Expr::Attribute(
+            Box::new(Expr::Index(
+                Box::new(Expr::Attribute(
+                    Box::new(Expr::Ident("self".to_string())),
+                    "accounts".to_string(),
+                )),
+                Box::new(Expr::Ident("id".to_string())),
+            )),
+            "balance".to_string(),
+        );
+        assert_eq!(assign.target, expected);
+        assert_eq!(assign.value, Expr::IntLiteral("0".to_string()));
+    }
+
+    #[test]
+    fn test_parse_bare_call_statement() {
+        let source = r#"
+contract Notifier:
+    fn ping(x: uint256):
+        self.notify(x)
+        registry.notify()
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Contract(contract) = &module.items[0] else {
+            panic!("Expected contract item");
+        };
+        let ContractMember::Function(func) = &contract.body[0] else {
+            panic!("Expected function member");
+        };
+        assert_eq!(
+            func.body[0],
+            Stmt::Expr(Expr::Call(
+                Box::new(Expr::Attribute(
+                    Box::new(Expr::Ident("self".to_string())),
+                    "notify".to_string(),
+                )),
+                vec![Expr::Ident("x".to_string())],
+            ))
+        );
+        assert!(matches!(&func.body[1], Stmt::Expr(Expr::Call(_, args)) if args.is_empty()));
+    }
+
+    #[test]
+    fn test_parse_rejects_trailing_tokens_after_call_statement() {
+        let source = r#"
+contract Notifier:
+    fn ping(x: uint256):
+        self.notify(x) + 1
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("Expected assignment or end of statement"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_rejects_call_as_assignment_target() {
+        let source = r#"
+contract Broken:
+    fn run():
+        f() = 1
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("Invalid assignment target"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_rejects_literal_as_assignment_target() {
+        let source = r#"
+contract Broken:
+    fn run():
+        1 = x
+"#;
+
+        // A statement cannot start with a literal, so this fails before any
+        // assignment is considered
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("Expected statement"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_augmented_assignment_desugars() {
+        let source = r#"
+contract Counter:
+    fn bump(by: uint256):
+        self.count += by
+        self.totals[by] -= 1
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Contract(contract) = &module.items[0] else {
+            panic!("Expected contract item");
+        };
+        let ContractMember::Function(func) = &contract.body[0] else {
+            panic!("Expected function member");
+        };
+        let count = Expr::Attribute(Box::new(Expr::Ident("self".to_string())), "count".to_string());
+        assert_eq!(
+            func.body[0],
+            Stmt::Assign(AssignStmt {
+                target: count.clone(),
+                type_annotation: None,
+                value: Expr::BinOp(Box::new(count), BinOp::Add, Box::new(Expr::Ident("by".to_string()))),
+            })
+        );
+        assert!(matches!(
+            &func.body[1],
+            Stmt::Assign(AssignStmt { value: Expr::BinOp(_, BinOp::Sub, _), .. })
+        ));
+
+        let tokens = Lexer::new("contract C:\n    fn f():\n        self.a[g()] += 1\n").tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("must not contain calls"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_tuple_unpacking() {
+        let source = r#"
+contract Pair:
+    fn swap(a: uint256, b: uint256):
+        x, y = self.pair()
+        a, b = b, a
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Contract(contract) = &module.items[0] else {
+            panic!("Expected contract item");
+        };
+        let ContractMember::Function(func) = &contract.body[0] else {
+            panic!("Expected function member");
+        };
+        let ident = |name: &str| Expr::Ident(name.to_string());
+        assert!(matches!(
+            &func.body[0],
+            Stmt::Assign(AssignStmt { target: Expr::Tuple(targets), value: Expr::Call(..), .. })
+                if *targets == vec![ident("x"), ident("y")]
+        ));
+        assert_eq!(
+            func.body[1],
+            Stmt::Assign(AssignStmt {
+                target: Expr::Tuple(vec![ident("a"), ident("b")]),
+                type_annotation: None,
+                value: Expr::Tuple(vec![ident("b"), ident("a")]),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_struct_fields() {
+        let source = r#"
+struct Position:
+    owner: address
+    amounts: list[uint256]
+    open: bool
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let simple = |name: &str| Type::Simple(name.to_string());
+        let field = |name: &str, type_annotation: Type| StructField { name: name.to_string(), type_annotation };
+        assert_eq!(
+            module.items[0],
+            Item::Struct(StructDecl {
+                name: "Position".to_string(),
+                fields: vec![
+                    field("owner", simple("address")),
+                    field("amounts", Type::List(Box::new(simple("uint256")))),
+                    field("open", simple("bool")),
+                ],
+            })
+        );
+
+        let tokens = Lexer::new("struct Empty:\n    pass\n").tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("Struct 'Empty' must declare at least one field"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_enum_variants() {
+        let source = "enum Status:\n    Pending\n    Active\n    Closed\n";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        assert_eq!(
+            module.items[0],
+            Item::Enum(EnumDecl {
+                name: "Status".to_string(),
+                variants: vec!["Pending".to_string(), "Active".to_string(), "Closed".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_error_declaration() {
+        let source = "error Insufficient(available: uint256, needed: uint256)\n";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Error(error) = &module.items[0] else {
+            panic!("Expected error item");
+        };
+        assert_eq!(error.name, "Insufficient");
+        let params: Vec<_> = error.params.iter().map(|p| (p.name.as_str(), &p.type_annotation)).collect();
+        let uint256 = Type::Simple("uint256".to_string());
+        assert_eq!(params, vec![("available", &uint256), ("needed", &uint256)]);
+    }
+
+    #[test]
+    fn test_parse_rejects_require_error_that_is_not_a_name() {
+        let source = r#"
+contract Guard:
+    fn check(ok: bool):
+        require(ok, self.errors.Denied())
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("must name a custom error"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_decorator_arguments() {
+        let source = r#"
+contract Gated:
+    @external
+    @target("evm", 'ink')
+    fn f():
+        pass
+"#;
+        let module = parse_module(Lexer::new(source).tokenize().unwrap()).unwrap();
+        let Item::Contract(contract) = &module.items[0] else { panic!("expected a contract") };
+        let ContractMember::Function(func) = &contract.body[0] else { panic!("expected a function") };
+        assert_eq!(func.decorators[0], Decorator { name: "external".to_string(), args: vec![] });
+        assert_eq!(
+            func.decorators[1].args,
+            vec![Expr::StringLiteral("evm".to_string()), Expr::StringLiteral("ink".to_string())]
+        );
+        assert_eq!(func.target_backends(), Some(vec!["evm", "ink"]));
+    }
+
+    #[test]
+    fn test_parse_contract_constant() {
+        let source = "contract Token:\n    const MAX_SUPPLY: uint256 = 1000000\n";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Contract(contract) = &module.items[0] else {
+            panic!("Expected contract item");
+        };
+        assert_eq!(
+            contract.body[0],
+            ContractMember::Constant(Constant {
+                name: "MAX_SUPPLY".to_string(),
+                type_annotation: Type::Simple("uint256".to_string()),
+                value: Expr::IntLiteral("1000000".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_module_constant() {
+        let source = "const MAX: uint256 = 10\n\ncontract Token:\n    const CAP: uint256 = MAX\n";
+        let module = parse_module(Lexer::new(source).tokenize().unwrap()).unwrap();
+
+        assert_eq!(
+            module.items[0],
+            Item::Const(Constant {
+                name: "MAX".to_string(),
+                type_annotation: Type::Simple("uint256".to_string()),
+                value: Expr::IntLiteral("10".to_string()),
+            })
+        );
+        assert!(matches!(&module.items[1], Item::Contract(contract) if contract.body.len() == 1));
+    }
+
+    #[test]
+    fn test_parse_interface_signatures() {
+        let source = r#"
+interface IERC20:
+    fn transfer(to: address, amount: uint256) -> bool
+    fn balance_of(owner: address) -> uint256
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Interface(interface) = &module.items[0] else {
+            panic!("Expected interface item");
+        };
+        assert_eq!(interface.name, "IERC20");
+        let names: Vec<_> = interface.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["transfer", "balance_of"]);
+        assert_eq!(interface.functions[0].params.len(), 2);
+        assert_eq!(interface.functions[0].return_type, Some(Type::Simple("bool".to_string())));
+        assert_eq!(interface.functions[1].return_type, Some(Type::Simple("uint256".to_string())));
+    }
+
+    #[test]
+    fn test_parse_default_parameters() {
+        let source = "contract M:\n    fn mint(to: address, amount: uint256 = 100):\n        pass\n";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Contract(contract) = &module.items[0] else {
+            panic!("Expected contract item");
+        };
+        let ContractMember::Function(func) = &contract.body[0] else {
+            panic!("Expected function member");
+        };
+        assert_eq!(func.params[0].default, None);
+        assert_eq!(func.params[1].default, Some(Expr::IntLiteral("100".to_string())));
+
+        let source = "contract M:\n    fn mint(amount: uint256 = 100, to: address):\n        pass\n";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(
+            err.to_string().contains("Parameter 'to' without a default follows a parameter with a default"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_keyword_arguments() {
+        let ident = |name: &str| Box::new(Expr::Ident(name.to_string()));
+        assert_eq!(
+            parse_expression("transfer(to=recipient, amount=100)").unwrap(),
+            Expr::Call(
+                ident("transfer"),
+                vec![
+                    Expr::KeywordArg("to".to_string(), ident("recipient")),
+                    Expr::KeywordArg("amount".to_string(), Box::new(Expr::IntLiteral("100".to_string()))),
+                ]
+            )
+        );
+        // `==` inside an argument is a comparison, not a keyword
+        assert!(matches!(
+            &parse_expression("f(a == b)").unwrap(),
+            Expr::Call(_, args) if matches!(args[0], Expr::BinOp(_, BinOp::Eq, _))
+        ));
+
+        let err = parse_expression("f(to=a, b)").unwrap_err();
+        assert!(err.to_string().contains("Positional argument follows keyword argument"), "{}", err);
+        let err = parse_expression("f(to=a, to=b)").unwrap_err();
+        assert!(err.to_string().contains("Keyword argument 'to' given more than once"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_bitwise_precedence() {
+        let ident = |name: &str| Box::new(Expr::Ident(name.to_string()));
+        let bin = |left, op, right| Box::new(Expr::BinOp(left, op, right));
+
+        // `|` binds loosest, then `^`, then `&`, then shifts
+        assert_eq!(
+            parse_expression("a | b ^ c & d << 1").unwrap(),
+            *bin(
+                ident("a"),
+                BinOp::BitOr,
+                bin(
+                    ident("b"),
+                    BinOp::BitXor,
+                    bin(
+                        ident("c"),
+                        BinOp::BitAnd,
+                        bin(ident("d"), BinOp::Shl, Box::new(Expr::IntLiteral("1".to_string())))
+                    )
+                )
+            )
+        );
+        // and all of them tighter than comparisons
+        assert_eq!(
+            parse_expression("flags & MASK == 0").unwrap(),
+            *bin(bin(ident("flags"), BinOp::BitAnd, ident("MASK")), BinOp::Eq, Box::new(Expr::IntLiteral("0".to_string())))
+        );
+        assert_eq!(
+            parse_expression("~a & b").unwrap(),
+            *bin(Box::new(Expr::UnaryOp(UnaryOp::BitNot, ident("a"))), BinOp::BitAnd, ident("b"))
+        );
+    }
+
+    #[test]
+    fn test_parse_comparison_chain_desugars() {
+        let ident = |name: &str| Box::new(Expr::Ident(name.to_string()));
+        let zero = || Box::new(Expr::IntLiteral("0".to_string()));
+
+        assert_eq!(
+            parse_expression("0 <= amount < cap").unwrap(),
+            Expr::BinOp(
+                Box::new(Expr::BinOp(zero(), BinOp::LtEq, ident("amount"))),
+                BinOp::And,
+                Box::new(Expr::BinOp(ident("amount"), BinOp::Lt, ident("cap"))),
+            )
+        );
+        // Longer chains and together every adjacent pair
+        assert!(matches!(
+            parse_expression("a < b < c < d").unwrap(),
+            Expr::BinOp(first, BinOp::And, last)
+                if matches!(*first, Expr::BinOp(_, BinOp::And, _))
+                    && *last == Expr::BinOp(ident("c"), BinOp::Lt, ident("d"))
+        ));
+
+        // A shared operand with calls is left for code generators to bind
+        let call = || Box::new(Expr::Call(ident("f"), vec![Expr::Ident("x".to_string())]));
+        assert_eq!(
+            parse_expression("0 < f(x) < 10").unwrap(),
+            Expr::BinOp(
+                Box::new(Expr::BinOp(zero(), BinOp::Lt, call())),
+                BinOp::And,
+                Box::new(Expr::BinOp(call(), BinOp::Lt, Box::new(Expr::IntLiteral("10".to_string())))),
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_list_comprehension() {
+        let ident = |name: &str| Box::new(Expr::Ident(name.to_string()));
+        let int = |value: &str| Box::new(Expr::IntLiteral(value.to_string()));
+        let range = Box::new(Expr::Call(ident("range"), vec![Expr::Ident("n".to_string())]));
+
+        assert_eq!(
+            parse_expression("[x * 2 for x in range(n)]").unwrap(),
+            Expr::ListComp {
+                element: Box::new(Expr::BinOp(ident("x"), BinOp::Mul, int("2"))),
+                variable: "x".to_string(),
+                iterable: range.clone(),
+                condition: None,
+            }
+        );
+        // A trailing `if` filters rather than starting a conditional expression
+        assert_eq!(
+            parse_expression("[x for x in range(n) if x % 2 == 0]").unwrap(),
+            Expr::ListComp {
+                element: ident("x"),
+                variable: "x".to_string(),
+                iterable: range,
+                condition: Some(Box::new(Expr::BinOp(
+                    Box::new(Expr::BinOp(ident("x"), BinOp::Mod, int("2"))),
+                    BinOp::Eq,
+                    int("0"),
+                ))),
+            }
+        );
+
+        let err = parse_expression("[x for x in range(n)").unwrap_err();
+        assert!(err.to_string().contains("Expected ']' after list comprehension"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_fstring() {
+        let text = |value: &str| Expr::StringLiteral(value.to_string());
+        let balance = Expr::Index(
+            Box::new(Expr::Attribute(Box::new(Expr::Ident("self".to_string())), "balances".to_string())),
+            Box::new(Expr::Ident("who".to_string())),
+        );
+        assert_eq!(
+            parse_expression(r#"f"balance {self.balances[who]} too low""#).unwrap(),
+            Expr::FString(vec![text("balance "), balance, text(" too low")])
+        );
+
+        let err = parse_expression(r#"f"{1 +}""#).unwrap_err();
+        assert!(err.to_string().contains("Invalid f-string expression '1 +'"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_fstring_require_message_is_kept() {
+        let source = r#"
+contract Vault:
+    const MIN: uint256 = 10
+
+    fn withdraw(amount: uint256):
+        require(amount >= MIN, f"amount below {MIN}")
+"#;
+        let module = parse_module(Lexer::new(source).tokenize().unwrap()).unwrap();
+        let Item::Contract(contract) = &module.items[0] else { panic!("expected a contract") };
+        let ContractMember::Function(func) = &contract.body[1] else { panic!("expected a function") };
+        let Stmt::Require(require) = &func.body[0] else { panic!("expected a require") };
+        assert_eq!(
+            require.message,
+            Some(Expr::FString(vec![
+                Expr::StringLiteral("amount below ".to_string()),
+                Expr::Ident("MIN".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_deep_nesting_is_an_error() {
+        let nested = |depth: usize| {
+            format!(
+                "contract Deep:\n    fn f() -> uint256:\n        return {}1{}\n",
+                "(".repeat(depth),
+                ")".repeat(depth)
+            )
+        };
+
+        let tokens = Lexer::new(&nested(50)).tokenize().unwrap();
+        assert!(parse_module(tokens).is_ok());
+
+        let tokens = Lexer::new(&nested(50)).tokenize().unwrap();
+        assert!(Parser::new(tokens).with_max_depth(8).parse_module().is_err());
+
+        let tokens = Lexer::new(&nested(10_000)).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("Maximum nesting depth"), "{}", err);
+
+        let source = format!("contract Neg:\n    fn f():\n        x = {}1\n", "-".repeat(10_000));
+        let tokens = Lexer::new(&source).tokenize().unwrap();
+        assert!(parse_module(tokens).is_err());
+
+        let source = format!(
+            "contract Nested:\n    _m: {}uint256{}\n",
+            "mapping[address, ".repeat(10_000),
+            "]".repeat(10_000)
+        );
+        let tokens = Lexer::new(&source).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("Maximum nesting depth"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_empty_contract_with_pass() {
+        let tokens = Lexer::new("contract Empty:\n    pass").tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        match &module.items[0] {
+            Item::Contract(contract) => {
+                assert_eq!(contract.name, "Empty");
+                assert!(contract.body.is_empty());
+            }
+            _ => panic!("Expected contract item"),
+        }
+    }
+
+    #[test]
+    fn test_parse_floor_division() {
+        let source = r#"
+contract Math:
+    fn half(a: uint256, b: uint256) -> uint256:
+        # floor division, not a comment
+        return a // b
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Contract(contract) = &module.items[0] else {
+            panic!("Expected contract item");
+        };
+        let ContractMember::Function(func) = &contract.body[0] else {
+            panic!("Expected function member");
+        };
+        assert_eq!(
+            func.body,
+            vec![Stmt::Return(Some(Expr::BinOp(
+                Box::new(Expr::Ident("a".to_string())),
+                BinOp::FloorDiv,
+                Box::new(Expr::Ident("b".to_string())),
+            )))]
+        );
+    }
+
+    #[test]
+    fn test_parse_conditional_expression() {
+        let ident = |name: &str| Box::new(Expr::Ident(name.to_string()));
+
+        // The branches take whole `or` expressions
+        assert_eq!(
+            parse_expression("a or b if flag else c").unwrap(),
+            Expr::IfExp {
+                test: ident("flag"),
+                body: Box::new(Expr::BinOp(ident("a"), BinOp::Or, ident("b"))),
+                orelse: ident("c"),
+            }
+        );
+        // and chain to the right
+        assert_eq!(
+            parse_expression("a if x else b if y else c").unwrap(),
+            Expr::IfExp {
+                test: ident("x"),
+                body: ident("a"),
+                orelse: Box::new(Expr::IfExp { test: ident("y"), body: ident("b"), orelse: ident("c") }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_loop_else_clause() {
+        let source = r#"
+contract Loops:
+    fn run(n: uint256):
+        while n > 0:
+            break
+        else:
+            pass
+        for i in range(n):
+            pass
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let Item::Contract(contract) = &module.items[0] else {
+            panic!("Expected contract item");
+        };
+        let ContractMember::Function(func) = &contract.body[0] else {
+            panic!("Expected function member");
+        };
+        let Stmt::While(while_stmt) = &func.body[0] else {
+            panic!("Expected while loop");
+        };
+        assert_eq!(while_stmt.else_branch, Some(vec![Stmt::Pass]));
+        let Stmt::For(for_stmt) = &func.body[1] else {
+            panic!("Expected for loop");
+        };
+        assert_eq!(for_stmt.else_branch, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_revert_in_expression() {
+        let source = r#"
+contract Guard:
+    fn pick(a: uint256, ok: bool):
+        y: uint256 = a if ok else revert("x")
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("'revert' is a statement"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_relative_import() {
+        let source = "from .utils import helper\nfrom ..shared.math import mul_div\n";
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+        let Item::Import(sibling) = &module.items[0] else {
+            panic!("Expected import");
+        };
+        assert_eq!((sibling.module.as_str(), sibling.level), ("utils", 1));
+        assert_eq!(sibling.items, vec!["helper".to_string()]);
+        let Item::Import(parent) = &module.items[1] else {
+            panic!("Expected import");
+        };
+        assert_eq!(parent.source_path(), "..shared.math");
+    }
+
+    #[test]
+    fn test_parse_rejects_variadic_parameters() {
+        let source = r#"
+contract Summer:
+    fn f(*args):
+        pass
+"#;
+
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let err = parse_module(tokens).unwrap_err();
+        assert!(err.to_string().contains("Variadic parameters are not supported"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_rejects_duplicate_parameters() {
+        for (source, name) in [
+            ("event Transfer(a: address, a: uint256)\n", "a"),
+            ("contract C:\n    fn f(x: uint256, x: bool):\n        pass\n", "x"),
+        ] {
+            let tokens = Lexer::new(source).tokenize().unwrap();
+            let err = parse_module(tokens).unwrap_err();
+            assert!(err.to_string().contains(&format!("Duplicate parameter '{}'", name)), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_unary_minus_binds_looser_than_power() {
+        let int = |n: &str| Box::new(Expr::IntLiteral(n.to_string()));
+        let neg_pow = |base: Box<Expr>| Expr::UnaryOp(UnaryOp::Neg, Box::new(Expr::BinOp(base, BinOp::Pow, int("2"))));
+
+        assert_eq!(parse_expression("-x ** 2").unwrap(), neg_pow(Box::new(Expr::Ident("x".to_string()))));
+        // -(2 ** 2) == -4, not (-2) ** 2 == 4
+        assert_eq!(parse_expression("-2 ** 2").unwrap(), neg_pow(int("2")));
+        assert_eq!(
+            parse_expression("2 ** -1").unwrap(),
+            Expr::BinOp(int("2"), BinOp::Pow, Box::new(Expr::UnaryOp(UnaryOp::Neg, int("1"))))
+        );
+    }
+
+    #[test]
+    fn test_common_type_mistakes_get_suggestions() {
+        let error = |source: &str| {
+            let tokens = Lexer::new(source).tokenize().unwrap();
+            parse_module(tokens).unwrap_err().to_string()
+        };
+
+        let err = error("contract C:\n    x: uint\n");
+        assert!(err.contains("Unknown type 'uint': integer types need a size in bits, such as uint256"), "{}", err);
+        let err = error("contract C:\n    x: map[address, uint256]\n");
+        assert!(err.contains("Unknown type 'map': use mapping[KeyType, ValueType]"), "{}", err);
+        let err = error("contract C:\n    x: Address\n");
+        assert!(err.contains("did you mean 'address'?"), "{}", err);
+        let err = error("contract C:\n    x: uint7\n");
+        assert!(err.contains("7 is not a valid size for uint (valid sizes: multiples of 8 from 8 to 256)"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_any_byte_multiple_integer_width() {
+        let source = "contract C:\n    owner: uint160\n    tick: int24\n";
+        let module = parse_module(Lexer::new(source).tokenize().unwrap()).unwrap();
+        let Item::Contract(contract) = &module.items[0] else { panic!("expected a contract") };
+        let types: Vec<_> = contract
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ContractMember::StateVar(var) => Some(var.type_annotation.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(types, vec![Type::Simple("uint160".to_string()), Type::Simple("int24".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_list_literal() {
+        let int = |n: &str| Expr::IntLiteral(n.to_string());
+        assert_eq!(parse_expression("[1, 2, 3]").unwrap(), Expr::List(vec![int("1"), int("2"), int("3")]));
+        assert_eq!(parse_expression("[]").unwrap(), Expr::List(vec![]));
+    }
 }
@@ -3,16 +3,43 @@
 
 use crate::ast::*;
 use crate::ParseError;
-use quorlin_lexer::{Token, TokenType};
+use quorlin_lexer::{FStringPart, Span, Token, TokenType};
+
+/// Default limit on nested expressions and statements. Deeply nested input
+/// fails with a parse error instead of overflowing the stack; the limit keeps
+/// unoptimised builds well within a 2 MiB thread stack.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
 
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    depth: usize,
+    max_depth: usize,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, current: 0 }
+        Parser { tokens, current: 0, depth: 0, max_depth: DEFAULT_MAX_DEPTH }
+    }
+
+    /// Set the maximum nesting depth of expressions and statements
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Run `parse` one nesting level deeper, failing once the limit is reached
+    fn nested<T>(&mut self, parse: fn(&mut Self) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        if self.depth >= self.max_depth {
+            return Err(ParseError::UnexpectedToken(
+                self.current,
+                format!("Maximum nesting depth of {} exceeded", self.max_depth),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
     }
 
     pub fn parse_module(&mut self) -> Result<Module, ParseError> {
@@ -29,6 +56,20 @@ impl Parser {
         Ok(Module { items })
     }
 
+    /// Parse a single expression that must make up the whole token stream
+    pub fn parse_standalone_expr(&mut self) -> Result<Expr, ParseError> {
+        self.skip_newlines();
+        let expr = self.parse_expr()?;
+        self.skip_newlines();
+        if !self.is_at_end() && !self.check(&TokenType::Eof) {
+            return Err(ParseError::UnexpectedToken(
+                self.current,
+                format!("Unexpected {:?} after expression", self.peek()),
+            ));
+        }
+        Ok(expr)
+    }
+
     fn parse_item(&mut self) -> Result<Item, ParseError> {
         self.skip_newlines();
 
@@ -46,17 +87,46 @@ impl Parser {
             self.parse_interface()
         } else if self.check(&TokenType::Error) {
             self.parse_error_decl()
+        } else if self.check(&TokenType::Const) {
+            Ok(Item::Const(self.parse_constant()?))
         } else {
             Err(ParseError::UnexpectedToken(
                 self.current,
-                format!("Expected item (from, contract, event, struct, enum, interface, or error), found {:?}", self.peek()),
+                format!(
+                    "Expected item (from, contract, const, event, struct, enum, interface, or error), found {:?}",
+                    self.peek()
+                ),
             ))
         }
     }
 
+    /// Constant: `const NAME: type = value`, in a module or a contract
+    fn parse_constant(&mut self) -> Result<Constant, ParseError> {
+        self.consume(&TokenType::Const, "Expected 'const'")?;
+        let name = self.consume_ident("Expected constant name")?;
+        self.consume(&TokenType::Colon, "Expected ':'")?;
+        let type_annotation = self.parse_type()?;
+        self.consume(&TokenType::Eq, "Expected '=' in constant declaration")?;
+        let value = self.parse_expr()?;
+
+        self.skip_newlines();
+
+        Ok(Constant {
+            name,
+            type_annotation,
+            value,
+        })
+    }
+
     fn parse_import(&mut self) -> Result<Item, ParseError> {
         self.consume(&TokenType::From, "Expected 'from'")?;
 
+        // Leading dots make the import relative to the importing file
+        let mut level = 0;
+        while self.match_token(&TokenType::Dot) {
+            level += 1;
+        }
+
         // Parse module path (e.g., std.math)
         let mut module_path = Vec::new();
         module_path.push(self.consume_ident("Expected module name")?);
@@ -80,7 +150,7 @@ impl Parser {
 
         self.skip_newlines();
 
-        Ok(Item::Import(ImportStmt { module, items }))
+        Ok(Item::Import(ImportStmt { module, items, level }))
     }
 
     fn parse_event(&mut self) -> Result<Item, ParseError> {
@@ -104,7 +174,10 @@ impl Parser {
                     false
                 };
 
-                let param_name = self.consume_ident("Expected parameter name")?;
+                let param_name = self.consume_param_name()?;
+                if params.iter().any(|param: &EventParam| param.name == param_name) {
+                    return Err(self.duplicate_param(&param_name));
+                }
                 self.consume(&TokenType::Colon, "Expected ':'")?;
                 let type_annotation = self.parse_type()?;
 
@@ -129,12 +202,31 @@ impl Parser {
     fn parse_contract(&mut self) -> Result<Item, ParseError> {
         self.consume(&TokenType::Contract, "Expected 'contract'")?;
         let name = self.consume_ident("Expected contract name")?;
+        let span = self.previous_span();
+
+        // contract Derived(Base, Other):
+        let mut bases = Vec::new();
+        if self.match_token(&TokenType::LParen) {
+            loop {
+                bases.push(self.consume_ident("Expected base contract name")?);
+                if !self.match_token(&TokenType::Comma) {
+                    break;
+                }
+            }
+            self.consume(&TokenType::RParen, "Expected ')'")?;
+        }
+
         self.consume(&TokenType::Colon, "Expected ':'")?;
         self.skip_newlines();
         self.consume(&TokenType::Indent, "Expected indented block")?;
 
         let mut body = Vec::new();
         while !self.check(&TokenType::Dedent) && !self.is_at_end() {
+            // `pass` is a placeholder and produces no member
+            if self.match_token(&TokenType::Pass) {
+                self.skip_newlines();
+                continue;
+            }
             body.push(self.parse_contract_member()?);
             self.skip_newlines();
         }
@@ -143,30 +235,60 @@ impl Parser {
 
         Ok(Item::Contract(ContractDecl {
             name,
-            bases: vec![],
+            bases,
             body,
             docstring: None,
+            span,
         }))
     }
 
     fn parse_contract_member(&mut self) -> Result<ContractMember, ParseError> {
         self.skip_newlines();
 
-        // Check for decorator
-        let has_decorator = self.check(&TokenType::At);
+        // Check for decorators: @name or @name("arg", ...)
         let mut decorators = Vec::new();
 
-        if has_decorator {
-            self.advance(); // consume @
-            decorators.push(self.consume_ident("Expected decorator name")?);
+        while self.match_token(&TokenType::At) {
+            let name = self.consume_ident("Expected decorator name")?;
+
+            if self.match_token(&TokenType::LParen) {
+                let mut args = Vec::new();
+                if !self.check(&TokenType::RParen) {
+                    loop {
+                        match self.peek().map(|t| &t.token_type) {
+                            Some(TokenType::StringLiteral(s)) | Some(TokenType::StringLiteralSingle(s)) => {
+                                args.push(Expr::StringLiteral(s.clone()));
+                                self.advance();
+                            }
+                            _ => {
+                                return Err(ParseError::UnexpectedToken(
+                                    self.current,
+                                    format!("Expected string argument to @{}, found {:?}", name, self.peek()),
+                                ))
+                            }
+                        }
+                        if !self.match_token(&TokenType::Comma) {
+                            break;
+                        }
+                    }
+                }
+                self.consume(&TokenType::RParen, "Expected ')'")?;
+                decorators.push(Decorator { name, args });
+            } else {
+                decorators.push(Decorator { name, args: Vec::new() });
+            }
+
             self.skip_newlines();
         }
 
         if self.check(&TokenType::Fn) {
             self.parse_function(decorators)
+        } else if self.check(&TokenType::Const) {
+            Ok(ContractMember::Constant(self.parse_constant()?))
         } else {
             // State variable: name: type = value
             let name = self.consume_ident("Expected state variable or function")?;
+            let span = self.previous_span();
             self.consume(&TokenType::Colon, "Expected ':'")?;
             let type_annotation = self.parse_type()?;
 
@@ -182,26 +304,44 @@ impl Parser {
                 name,
                 type_annotation,
                 initial_value,
+                span,
             }))
         }
     }
 
-    fn parse_function(&mut self, decorators: Vec<String>) -> Result<ContractMember, ParseError> {
+    fn parse_function(&mut self, decorators: Vec<Decorator>) -> Result<ContractMember, ParseError> {
         self.consume(&TokenType::Fn, "Expected 'fn'")?;
         let name = self.consume_ident("Expected function name")?;
+        let span = self.previous_span();
         self.consume(&TokenType::LParen, "Expected '('")?;
 
-        let mut params = Vec::new();
+        let mut params: Vec<Param> = Vec::new();
         if !self.check(&TokenType::RParen) {
             loop {
-                let param_name = self.consume_ident("Expected parameter name")?;
+                let param_name = self.consume_param_name()?;
+                if params.iter().any(|param: &Param| param.name == param_name) {
+                    return Err(self.duplicate_param(&param_name));
+                }
                 self.consume(&TokenType::Colon, "Expected ':'")?;
                 let type_annotation = self.parse_type()?;
 
+                // Defaulted parameters must be trailing so omitted arguments
+                // are always the last ones
+                let default = if self.match_token(&TokenType::Eq) {
+                    Some(self.parse_expr()?)
+                } else if params.iter().any(|p| p.default.is_some()) {
+                    return Err(ParseError::UnexpectedToken(
+                        self.current - 1,
+                        format!("Parameter '{}' without a default follows a parameter with a default", param_name),
+                    ));
+                } else {
+                    None
+                };
+
                 params.push(Param {
                     name: param_name,
                     type_annotation,
-                    default: None,
+                    default,
                 });
 
                 if !self.match_token(&TokenType::Comma) {
@@ -237,10 +377,15 @@ impl Parser {
             return_type,
             body,
             docstring: None,
+            span,
         }))
     }
 
     fn parse_stmt(&mut self) -> Result<Stmt, ParseError> {
+        self.nested(Self::parse_stmt_inner)
+    }
+
+    fn parse_stmt_inner(&mut self) -> Result<Stmt, ParseError> {
         self.skip_newlines();
 
         if self.match_token(&TokenType::Let) {
@@ -291,16 +436,30 @@ impl Parser {
 
             Ok(Stmt::Emit(EmitStmt { event, args }))
         } else if self.match_token(&TokenType::Require) {
-            // require(condition, error_message)
+            // require(condition, error_message) or require(condition, ErrorName(args))
             self.consume(&TokenType::LParen, "Expected '('")?;
             let condition = self.parse_expr()?;
 
+            let mut error = None;
             let message = if self.match_token(&TokenType::Comma) {
                 let msg_expr = self.parse_expr()?;
-                // Extract string from string literal expression
+                // Keep string and f-string messages; codegen folds f-strings
                 match msg_expr {
-                    Expr::StringLiteral(s) => Some(s),
-                    _ => Some("Requirement failed".to_string()), // Default message
+                    Expr::StringLiteral(_) | Expr::FString(_) => Some(msg_expr),
+                    Expr::Call(callee, args) => match *callee {
+                        Expr::Ident(name) => {
+                            error = Some(RaiseStmt { error: name, args });
+                            None
+                        }
+                        _ => {
+                            return Err(ParseError::UnexpectedToken(
+                                self.current,
+                                "A require error must name a custom error: require(condition, ErrorName(args))"
+                                    .to_string(),
+                            ))
+                        }
+                    },
+                    _ => Some(Expr::StringLiteral("Requirement failed".to_string())), // Default message
                 }
             } else {
                 None
@@ -309,14 +468,19 @@ impl Parser {
             self.consume(&TokenType::RParen, "Expected ')'")?;
             self.skip_newlines();
 
-            Ok(Stmt::Require(RequireStmt { condition, message }))
+            Ok(Stmt::Require(RequireStmt { condition, message, error }))
         } else if self.check(&TokenType::SelfKw) || self.check_ident() {
             // Parse potential target or expression statement
-            // Target could be: name, self.attr, self.attr[index], call(), etc.
+            // Target could be: name, self.attr, self.attr[index], self.attr[index].field, call(), etc.
             
-            // We use parse_atom_with_postfix because it handles ident/self followed by ., (), []
+            // We use parse_atom_with_postfix because it handles ident/self followed by
+            // any interleaving of ., (), and [] postfixes
             let target = self.parse_atom_with_postfix()?;
 
+            if self.check(&TokenType::Comma) {
+                return self.parse_unpacking(target);
+            }
+
             // Check for type annotation: name: type = value
             let type_annotation = if self.match_token(&TokenType::Colon) {
                 Some(self.parse_type()?)
@@ -325,6 +489,19 @@ impl Parser {
             };
 
             // Check for assignment operator: =, +=, -=, *=, /=
+            let aug_op = self.peek().and_then(|token| match token.token_type {
+                TokenType::PlusEq => Some(BinOp::Add),
+                TokenType::MinusEq => Some(BinOp::Sub),
+                TokenType::StarEq => Some(BinOp::Mul),
+                TokenType::SlashEq => Some(BinOp::Div),
+                _ => None,
+            });
+            if (self.check(&TokenType::Eq) || aug_op.is_some()) && !Self::is_assignable(&target) {
+                return Err(ParseError::UnexpectedToken(
+                    self.current,
+                    "Invalid assignment target: only variables, attributes and index expressions can be assigned to".to_string(),
+                ));
+            }
             if self.match_token(&TokenType::Eq) {
                 // Simple assignment: target = value
                 let value = self.parse_expr()?;
@@ -335,21 +512,43 @@ impl Parser {
                     type_annotation,
                     value,
                 }))
-            } else if self.match_token(&TokenType::PlusEq) 
-                   || self.match_token(&TokenType::MinusEq)
-                   || self.match_token(&TokenType::StarEq)
-                   || self.match_token(&TokenType::SlashEq) {
-                
+            } else if let Some(op) = aug_op {
                 if type_annotation.is_some() {
                     return Err(ParseError::UnexpectedToken(self.current, "Type annotations not allowed in augmented assignment".to_string()));
                 }
+                // The target is evaluated twice once desugared, so it must be free of calls
+                if target.contains_call() {
+                    return Err(ParseError::UnexpectedToken(
+                        self.current,
+                        "Augmented assignment target must not contain calls; assign the value to a local first".to_string(),
+                    ));
+                }
+                self.advance();
 
-                Err(ParseError::UnexpectedToken(self.current, "Augmented assignment implementation pending refactor".to_string()))
+                // Desugar `target op= value` into `target = target op value`
+                let value = self.parse_expr()?;
+                self.skip_newlines();
+
+                Ok(Stmt::Assign(AssignStmt {
+                    value: Expr::BinOp(Box::new(target.clone()), op, Box::new(value)),
+                    target,
+                    type_annotation: None,
+                }))
             } else {
                 if type_annotation.is_some() {
                      return Err(ParseError::UnexpectedToken(self.current, "Expected assignment after type annotation".to_string()));
                 }
                 // Not an assignment -> Expression statement (e.g., function call)
+                if !(self.is_at_end()
+                    || self.check(&TokenType::Newline)
+                    || self.check(&TokenType::Dedent)
+                    || self.check(&TokenType::Eof))
+                {
+                    return Err(ParseError::UnexpectedToken(
+                        self.current,
+                        format!("Expected assignment or end of statement, found {:?}", self.peek()),
+                    ));
+                }
                 self.skip_newlines();
                 Ok(Stmt::Expr(target))
             }
@@ -392,6 +591,38 @@ impl Parser {
         }
     }
 
+    /// `a, b = value`, after the first target. The value may itself be a bare
+    /// comma-separated list, as in `a, b = b, a`.
+    fn parse_unpacking(&mut self, first: Expr) -> Result<Stmt, ParseError> {
+        let mut targets = vec![first];
+        while self.match_token(&TokenType::Comma) {
+            targets.push(self.parse_atom_with_postfix()?);
+        }
+        if !targets.iter().all(Self::is_assignable) {
+            return Err(ParseError::UnexpectedToken(
+                self.current,
+                "Invalid assignment target: only variables, attributes and index expressions can be assigned to".to_string(),
+            ));
+        }
+        self.consume(&TokenType::Eq, "Expected '=' after unpacking targets")?;
+
+        let mut value = self.parse_expr()?;
+        if self.check(&TokenType::Comma) {
+            let mut items = vec![value];
+            while self.match_token(&TokenType::Comma) {
+                items.push(self.parse_expr()?);
+            }
+            value = Expr::Tuple(items);
+        }
+        self.skip_newlines();
+
+        Ok(Stmt::Assign(AssignStmt {
+            target: Expr::Tuple(targets),
+            value,
+            type_annotation: None,
+        }))
+    }
+
     fn parse_if_stmt(&mut self) -> Result<Stmt, ParseError> {
         // if condition:
         //     body
@@ -469,8 +700,9 @@ impl Parser {
             self.skip_newlines();
         }
         self.consume(&TokenType::Dedent, "Expected dedent after while block")?;
+        let else_branch = self.parse_loop_else()?;
 
-        Ok(Stmt::While(WhileStmt { condition, body }))
+        Ok(Stmt::While(WhileStmt { condition, body, else_branch }))
     }
 
     fn parse_for_stmt(&mut self) -> Result<Stmt, ParseError> {
@@ -490,15 +722,41 @@ impl Parser {
             self.skip_newlines();
         }
         self.consume(&TokenType::Dedent, "Expected dedent after for block")?;
+        let else_branch = self.parse_loop_else()?;
 
         Ok(Stmt::For(ForStmt {
             variable,
             iterable,
             body,
+            else_branch,
         }))
     }
 
+    /// Optional `else:` block after a loop, run when the loop ends without `break`
+    fn parse_loop_else(&mut self) -> Result<Option<Vec<Stmt>>, ParseError> {
+        if !self.match_token(&TokenType::Else) {
+            return Ok(None);
+        }
+        self.consume(&TokenType::Colon, "Expected ':' after else")?;
+        self.skip_newlines();
+        self.consume(&TokenType::Indent, "Expected indented block after else")?;
+
+        let mut else_body = Vec::new();
+        while !self.check(&TokenType::Dedent) && !self.is_at_end() {
+            else_body.push(self.parse_stmt()?);
+            self.skip_newlines();
+        }
+        self.consume(&TokenType::Dedent, "Expected dedent after else block")?;
+
+        Ok(Some(else_body))
+    }
+
     fn parse_expr(&mut self) -> Result<Expr, ParseError> {
+        self.nested(Self::parse_ternary)
+    }
+
+    // Conditional expression: `body if test else orelse`
+    fn parse_ternary(&mut self) -> Result<Expr, ParseError> {
         let expr = self.parse_or()?;
         
         if self.match_token(&TokenType::If) {
@@ -550,9 +808,12 @@ impl Parser {
         Ok(expr)
     }
 
-    // Comparison operators: ==, !=, <, >, <=, >=
+    // Comparison operators: ==, !=, <, >, <=, >=. As in Python, a chain
+    // `a <= b < c` means `a <= b and b < c`; code generators evaluate a
+    // shared operand containing calls only once
     fn parse_comparison(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.parse_additive()?;
+        let mut left = self.parse_bit_or()?;
+        let mut chain: Option<Expr> = None;
 
         while let Some(token) = self.peek() {
             let op = match &token.token_type {
@@ -562,6 +823,66 @@ impl Parser {
                 TokenType::Gt => BinOp::Gt,
                 TokenType::LtEq => BinOp::LtEq,
                 TokenType::GtEq => BinOp::GtEq,
+                TokenType::In => BinOp::In,
+                _ => break,
+            };
+
+            self.advance();
+            let right = self.parse_bit_or()?;
+            let comparison = Expr::BinOp(Box::new(left), op, Box::new(right.clone()));
+            chain = Some(match chain {
+                Some(previous) => Expr::BinOp(Box::new(previous), BinOp::And, Box::new(comparison)),
+                None => comparison,
+            });
+            left = right;
+        }
+
+        Ok(chain.unwrap_or(left))
+    }
+
+    // Bitwise operators bind tighter than comparisons and looser than
+    // shifts, `|` loosest and `&` tightest, as in Python
+    fn parse_bit_or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_bit_xor()?;
+
+        while self.match_token(&TokenType::Pipe) {
+            let right = self.parse_bit_xor()?;
+            expr = Expr::BinOp(Box::new(expr), BinOp::BitOr, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_bit_xor(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_bit_and()?;
+
+        while self.match_token(&TokenType::Caret) {
+            let right = self.parse_bit_and()?;
+            expr = Expr::BinOp(Box::new(expr), BinOp::BitXor, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_bit_and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_shift()?;
+
+        while self.match_token(&TokenType::Ampersand) {
+            let right = self.parse_shift()?;
+            expr = Expr::BinOp(Box::new(expr), BinOp::BitAnd, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    // Bit shifts: <<, >>
+    fn parse_shift(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_additive()?;
+
+        while let Some(token) = self.peek() {
+            let op = match &token.token_type {
+                TokenType::ShiftLeft => BinOp::Shl,
+                TokenType::ShiftRight => BinOp::Shr,
                 _ => break,
             };
 
@@ -594,89 +915,46 @@ impl Parser {
 
     // Multiplication, division, and modulo
     fn parse_multiplicative(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.parse_power()?;
+        let mut expr = self.parse_unary()?;
 
         while let Some(token) = self.peek() {
             let op = match &token.token_type {
                 TokenType::Star => BinOp::Mul,
                 TokenType::Slash => BinOp::Div,
+                TokenType::SlashSlash => BinOp::FloorDiv,
                 TokenType::Percent => BinOp::Mod,
                 _ => break,
             };
 
             self.advance();
-            let right = self.parse_power()?;
+            let right = self.parse_unary()?;
             expr = Expr::BinOp(Box::new(expr), op, Box::new(right));
         }
 
         Ok(expr)
     }
 
-    // Power operator (right-associative)
-    fn parse_power(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.parse_postfix()?;
-
-        if let Some(token) = self.peek() {
-            if let TokenType::DoubleStar = token.token_type {
-                self.advance();
-                let right = self.parse_power()?; // Right-associative
-                expr = Expr::BinOp(Box::new(expr), BinOp::Pow, Box::new(right));
-            }
-        }
-
-        Ok(expr)
-    }
-
-    fn parse_postfix(&mut self) -> Result<Expr, ParseError> {
-        // Parse unary expression first (which handles primary + unary ops)
-        let mut expr = self.parse_unary()?;
-
-        // Handle postfix operations: ., (), and []
-        loop {
-            if self.match_token(&TokenType::Dot) {
-                let attr = self.consume_ident("Expected attribute name")?;
-                expr = Expr::Attribute(Box::new(expr), attr);
-            } else if self.check(&TokenType::LParen) {
-                self.advance();
-                let mut args = Vec::new();
-
-                if !self.check(&TokenType::RParen) {
-                    loop {
-                        args.push(self.parse_expr()?);
-                        if !self.match_token(&TokenType::Comma) {
-                            break;
-                        }
-                    }
-                }
-
-                self.consume(&TokenType::RParen, "Expected ')'")?;
-                expr = Expr::Call(Box::new(expr), args);
-            } else if self.match_token(&TokenType::LBracket) {
-                let index = self.parse_expr()?;
-                self.consume(&TokenType::RBracket, "Expected ']'")?;
-                expr = Expr::Index(Box::new(expr), Box::new(index));
-            } else {
-                break;
-            }
-        }
-
-        Ok(expr)
-    }
-
+    // Unary operators bind looser than `**` on their left, as in Python:
+    // `-x ** 2` is `-(x ** 2)`
     fn parse_unary(&mut self) -> Result<Expr, ParseError> {
-        // Check for unary operators: -, +, not
+        // Check for unary operators: -, +, ~, not
         if let Some(token) = self.peek() {
             match &token.token_type {
                 TokenType::Minus => {
                     self.advance();
-                    let expr = self.parse_unary()?;
+                    let expr = self.nested(Self::parse_unary)?;
                     return Ok(Expr::UnaryOp(UnaryOp::Neg, Box::new(expr)));
                 }
                 TokenType::Plus => {
                     self.advance();
-                    let expr = self.parse_unary()?;
+                    let expr = self.nested(Self::parse_unary)?;
                     return Ok(Expr::UnaryOp(UnaryOp::Pos, Box::new(expr)));
                 }
+                TokenType::Tilde => {
+                    self.advance();
+                    let expr = self.nested(Self::parse_unary)?;
+                    return Ok(Expr::UnaryOp(UnaryOp::BitNot, Box::new(expr)));
+                }
                 TokenType::Not => {
                     self.advance();
                     // For 'not', parse a postfix expression (primary + postfix ops)
@@ -688,8 +966,24 @@ impl Parser {
             }
         }
 
-        // No unary operator, parse atomic expression with postfix
-        self.parse_atom_with_postfix()
+        // No unary operator, parse a power expression
+        self.parse_power()
+    }
+
+    // Power operator (right-associative). The exponent may carry its own
+    // unary sign: `2 ** -1`
+    fn parse_power(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_atom_with_postfix()?;
+
+        if let Some(token) = self.peek() {
+            if let TokenType::DoubleStar = token.token_type {
+                self.advance();
+                let right = self.nested(Self::parse_unary)?; // Right-associative
+                expr = Expr::BinOp(Box::new(expr), BinOp::Pow, Box::new(right));
+            }
+        }
+
+        Ok(expr)
     }
 
     fn parse_atom_with_postfix(&mut self) -> Result<Expr, ParseError> {
@@ -707,7 +1001,7 @@ impl Parser {
 
                 if !self.check(&TokenType::RParen) {
                     loop {
-                        args.push(self.parse_expr()?);
+                        args.push(self.parse_call_arg(&args)?);
                         if !self.match_token(&TokenType::Comma) {
                             break;
                         }
@@ -728,6 +1022,54 @@ impl Parser {
         Ok(expr)
     }
 
+    /// The rest of `[element for variable in iterable if condition]` after
+    /// its element. As in Python, the iterable and condition cannot be
+    /// conditional expressions, so a trailing `if` always filters.
+    fn parse_list_comp(&mut self, element: Expr) -> Result<Expr, ParseError> {
+        self.consume(&TokenType::For, "Expected 'for'")?;
+        let variable = self.consume_ident("Expected comprehension variable")?;
+        self.consume(&TokenType::In, "Expected 'in' after comprehension variable")?;
+        let iterable = self.parse_or()?;
+        let condition = if self.match_token(&TokenType::If) {
+            Some(Box::new(self.parse_or()?))
+        } else {
+            None
+        };
+        self.consume(&TokenType::RBracket, "Expected ']' after list comprehension")?;
+
+        Ok(Expr::ListComp {
+            element: Box::new(element),
+            variable,
+            iterable: Box::new(iterable),
+            condition,
+        })
+    }
+
+    /// One call argument, positional or `name=value`. Positional arguments
+    /// may not follow keyword ones, as in Python.
+    fn parse_call_arg(&mut self, previous: &[Expr]) -> Result<Expr, ParseError> {
+        let is_keyword = matches!(self.peek().map(|t| &t.token_type), Some(TokenType::Ident(_)))
+            && matches!(self.tokens.get(self.current + 1).map(|t| &t.token_type), Some(TokenType::Eq));
+        if is_keyword {
+            let name = self.consume_ident("Expected argument name")?;
+            self.advance();
+            if previous.iter().any(|arg| matches!(arg, Expr::KeywordArg(n, _) if *n == name)) {
+                return Err(ParseError::UnexpectedToken(
+                    self.current - 2,
+                    format!("Keyword argument '{}' given more than once", name),
+                ));
+            }
+            return Ok(Expr::KeywordArg(name, Box::new(self.parse_expr()?)));
+        }
+        if previous.iter().any(|arg| matches!(arg, Expr::KeywordArg(..))) {
+            return Err(ParseError::UnexpectedToken(
+                self.current,
+                "Positional argument follows keyword argument".to_string(),
+            ));
+        }
+        self.parse_expr()
+    }
+
     fn parse_primary(&mut self) -> Result<Expr, ParseError> {
         if let Some(token) = self.peek() {
             match &token.token_type {
@@ -741,6 +1083,29 @@ impl Parser {
                     self.advance();
                     Ok(Expr::StringLiteral(val))
                 }
+                TokenType::FString(parts) => {
+                    let parts = parts.clone();
+                    let position = self.current;
+                    self.advance();
+                    let parts = parts
+                        .into_iter()
+                        .map(|part| match part {
+                            FStringPart::Literal(text) => Ok(Expr::StringLiteral(text)),
+                            FStringPart::Hole(source) => crate::parse_expression(&source).map_err(|e| {
+                                ParseError::UnexpectedToken(
+                                    position,
+                                    format!("Invalid f-string expression '{}': {}", source.trim(), e),
+                                )
+                            }),
+                        })
+                        .collect::<Result<_, _>>()?;
+                    Ok(Expr::FString(parts))
+                }
+                TokenType::HexLiteral(h) => {
+                    let val = h.clone();
+                    self.advance();
+                    Ok(Expr::HexLiteral(val))
+                }
                 TokenType::True => {
                     self.advance();
                     Ok(Expr::BoolLiteral(true))
@@ -813,6 +1178,33 @@ impl Parser {
                         Ok(first)
                     }
                 }
+                TokenType::LBracket => {
+                    // List literal: [a, b, c]
+                    self.advance();
+                    let mut items = Vec::new();
+                    while !self.check(&TokenType::RBracket) {
+                        items.push(self.parse_expr()?);
+                        if items.len() == 1 && self.check(&TokenType::For) {
+                            return self.parse_list_comp(items.remove(0));
+                        }
+                        if !self.match_token(&TokenType::Comma) {
+                            break;
+                        }
+                    }
+                    self.consume(&TokenType::RBracket, "Expected ']'")?;
+                    Ok(Expr::List(items))
+                }
+                // Aborting is a statement; `x if ok else revert(..)` has no value to produce
+                TokenType::Revert | TokenType::Raise => {
+                    let keyword = if token.token_type == TokenType::Revert { "revert" } else { "raise" };
+                    Err(ParseError::UnexpectedToken(
+                        self.current,
+                        format!(
+                            "'{}' is a statement and cannot be used inside an expression; use require(...) or an if statement instead",
+                            keyword
+                        ),
+                    ))
+                }
                 _ => Err(ParseError::UnexpectedToken(
                     self.current,
                     format!("Expected expression, found {:?}", token.token_type),
@@ -824,6 +1216,42 @@ impl Parser {
     }
 
     fn parse_type(&mut self) -> Result<Type, ParseError> {
+        self.nested(Self::parse_type_inner)
+    }
+
+    fn parse_type_inner(&mut self) -> Result<Type, ParseError> {
+        let mut ty = self.parse_base_type()?;
+
+        // Fixed-size array suffixes: T[4], T[N], T[2][3]
+        while self.match_token(&TokenType::LBracket) {
+            ty = match self.peek().map(|t| &t.token_type) {
+                Some(TokenType::IntLiteral(n)) => {
+                    let size = n.parse::<usize>().map_err(|_| {
+                        ParseError::UnexpectedToken(self.current, format!("Invalid array length '{}'", n))
+                    })?;
+                    self.advance();
+                    Type::FixedArray(Box::new(ty), size)
+                }
+                Some(TokenType::Ident(name)) => {
+                    // Resolved to a FixedArray once constants are known
+                    let name = name.clone();
+                    self.advance();
+                    Type::ConstArray(Box::new(ty), name)
+                }
+                _ => {
+                    return Err(ParseError::UnexpectedToken(
+                        self.current,
+                        format!("Expected array length, found {:?}", self.peek()),
+                    ))
+                }
+            };
+            self.consume(&TokenType::RBracket, "Expected ']'")?;
+        }
+
+        Ok(ty)
+    }
+
+    fn parse_base_type(&mut self) -> Result<Type, ParseError> {
         if let Some(token) = self.peek() {
             match &token.token_type {
                 TokenType::Bool => {
@@ -838,6 +1266,10 @@ impl Parser {
                     self.advance();
                     Ok(Type::Simple("str".to_string()))
                 }
+                TokenType::Bytes => {
+                    self.advance();
+                    Ok(Type::Simple("bytes".to_string()))
+                }
                 TokenType::Uint(size) => {
                     let size = size.clone();
                     self.advance();
@@ -870,6 +1302,12 @@ impl Parser {
                     Ok(Type::List(Box::new(elem_type)))
                 }
                 TokenType::Ident(name) => {
+                    if let Some(suggestion) = type_suggestion(name) {
+                        return Err(ParseError::UnexpectedToken(
+                            self.current,
+                            format!("Unknown type '{}': {}", name, suggestion),
+                        ));
+                    }
                     let name = name.clone();
                     self.advance();
                     Ok(Type::Simple(name))
@@ -949,6 +1387,11 @@ impl Parser {
         self.previous()
     }
 
+    /// Whether `expr` may appear on the left of `=`
+    fn is_assignable(expr: &Expr) -> bool {
+        matches!(expr, Expr::Ident(_) | Expr::Attribute(_, _) | Expr::Index(_, _))
+    }
+
     fn is_at_end(&self) -> bool {
         self.current >= self.tokens.len()
     }
@@ -965,6 +1408,11 @@ impl Parser {
         }
     }
 
+    /// Span of the token just consumed
+    fn previous_span(&self) -> Span {
+        self.previous().map(|token| token.span).unwrap_or_default()
+    }
+
     fn consume(&mut self, token_type: &TokenType, message: &str) -> Result<(), ParseError> {
         if self.check(token_type) {
             self.advance();
@@ -977,6 +1425,22 @@ impl Parser {
         }
     }
 
+    /// Consume a parameter name, rejecting Python-style `*args` / `**kwargs`
+    fn consume_param_name(&mut self) -> Result<String, ParseError> {
+        if self.check(&TokenType::Star) || self.check(&TokenType::DoubleStar) {
+            return Err(ParseError::UnexpectedToken(
+                self.current,
+                "Variadic parameters are not supported; declare each parameter explicitly or take a list".to_string(),
+            ));
+        }
+        self.consume_ident("Expected parameter name")
+    }
+
+    /// A parameter list that names `name` twice; points at the second one
+    fn duplicate_param(&self, name: &str) -> ParseError {
+        ParseError::UnexpectedToken(self.current - 1, format!("Duplicate parameter '{}'", name))
+    }
+
     fn consume_ident(&mut self, message: &str) -> Result<String, ParseError> {
         if let Some(token) = self.peek() {
             if let TokenType::Ident(name) = &token.token_type {
@@ -1003,6 +1467,11 @@ impl Parser {
 
         let mut fields = Vec::new();
         while !self.check(&TokenType::Dedent) && !self.is_at_end() {
+            // A struct has no placeholder body; `pass` only yields the error below
+            if self.match_token(&TokenType::Pass) {
+                self.skip_newlines();
+                continue;
+            }
             let field_name = self.consume_ident("Expected field name")?;
             self.consume(&TokenType::Colon, "Expected ':'")?;
             let type_annotation = self.parse_type()?;
@@ -1015,6 +1484,12 @@ impl Parser {
             self.skip_newlines();
         }
 
+        if fields.is_empty() {
+            return Err(ParseError::UnexpectedToken(
+                self.current,
+                format!("Struct '{}' must declare at least one field", name),
+            ));
+        }
         self.consume(&TokenType::Dedent, "Expected dedent")?;
 
         Ok(Item::Struct(StructDecl { name, fields }))
@@ -1055,7 +1530,10 @@ impl Parser {
             let mut params = Vec::new();
             if !self.check(&TokenType::RParen) {
                 loop {
-                    let param_name = self.consume_ident("Expected parameter name")?;
+                    let param_name = self.consume_param_name()?;
+                    if params.iter().any(|param: &Param| param.name == param_name) {
+                        return Err(self.duplicate_param(&param_name));
+                    }
                     self.consume(&TokenType::Colon, "Expected ':'")?;
                     let type_annotation = self.parse_type()?;
 
@@ -1101,7 +1579,10 @@ impl Parser {
         let mut params = Vec::new();
         if !self.check(&TokenType::RParen) {
             loop {
-                let param_name = self.consume_ident("Expected parameter name")?;
+                let param_name = self.consume_param_name()?;
+                if params.iter().any(|param: &Param| param.name == param_name) {
+                    return Err(self.duplicate_param(&param_name));
+                }
                 self.consume(&TokenType::Colon, "Expected ':'")?;
                 let type_annotation = self.parse_type()?;
 
@@ -1123,3 +1604,30 @@ impl Parser {
         Ok(Item::Error(ErrorDecl { name, params }))
     }
 }
+
+/// A fix for type names that are common mistakes rather than user-defined
+/// types: unsized integers, Python-style map names and misspelled builtins
+fn type_suggestion(name: &str) -> Option<String> {
+    const SIZES: &str = "multiples of 8 from 8 to 256";
+    for prefix in ["uint", "int"] {
+        if let Some(bits) = name.strip_prefix(prefix) {
+            if bits.is_empty() {
+                return Some(format!("integer types need a size in bits, such as {}256 (valid sizes: {})", prefix, SIZES));
+            }
+            if bits.chars().all(|c| c.is_ascii_digit()) {
+                // The lexer only has tokens for the common widths
+                if bits.parse::<u32>().is_ok_and(|bits| bits % 8 == 0 && (8..=256).contains(&bits)) {
+                    return None;
+                }
+                return Some(format!("{} is not a valid size for {} (valid sizes: {})", bits, prefix, SIZES));
+            }
+        }
+    }
+    let builtin = match name {
+        "map" | "dict" | "Dict" | "Mapping" | "HashMap" => return Some("use mapping[KeyType, ValueType]".to_string()),
+        "boolean" => "bool",
+        "addr" | "adress" | "addres" => "address",
+        _ => ["bool", "address"].into_iter().find(|builtin| name.eq_ignore_ascii_case(builtin))?,
+    };
+    Some(format!("did you mean '{}'?", builtin))
+}
@@ -0,0 +1,278 @@
+//! Human-readable AST dump
+//!
+//! Renders a module as an indented tree, one node per line, with
+//! expressions written back in source syntax. Nested operators are fully
+//! parenthesised so the tree's grouping is visible at a glance.
+
+use crate::ast::*;
+use crate::type_names::canonical_display;
+
+/// Render `module` as an indented tree
+pub fn pretty_print(module: &Module) -> String {
+    let mut printer = Printer::default();
+    for item in &module.items {
+        printer.item(item);
+    }
+    printer.out
+}
+
+#[derive(Default)]
+struct Printer {
+    out: String,
+    depth: usize,
+}
+
+impl Printer {
+    fn line(&mut self, text: &str) {
+        self.out.push_str(&"  ".repeat(self.depth));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /// Print `header`, then run `children` one level deeper
+    fn node(&mut self, header: &str, children: impl FnOnce(&mut Self)) {
+        self.line(header);
+        self.depth += 1;
+        children(self);
+        self.depth -= 1;
+    }
+
+    fn params(&mut self, params: &[Param]) {
+        for param in params {
+            let mut text = format!("Param {}: {}", param.name, canonical_display(&param.type_annotation));
+            if let Some(default) = &param.default {
+                text.push_str(&format!(" = {}", expr(default)));
+            }
+            self.line(&text);
+        }
+    }
+
+    fn item(&mut self, item: &Item) {
+        match item {
+            Item::Import(import) => {
+                self.line(&format!("Import {}: {}", import.source_path(), import.items.join(", ")));
+            }
+            Item::Contract(contract) => {
+                let mut header = format!("Contract \"{}\"", contract.name);
+                if !contract.bases.is_empty() {
+                    header.push_str(&format!(" ({})", contract.bases.join(", ")));
+                }
+                self.node(&header, |p| {
+                    for member in &contract.body {
+                        p.member(member);
+                    }
+                });
+            }
+            Item::Struct(decl) => self.node(&format!("Struct \"{}\"", decl.name), |p| {
+                for field in &decl.fields {
+                    p.line(&format!("Field {}: {}", field.name, canonical_display(&field.type_annotation)));
+                }
+            }),
+            Item::Enum(decl) => self.node(&format!("Enum \"{}\"", decl.name), |p| {
+                for variant in &decl.variants {
+                    p.line(&format!("Variant {}", variant));
+                }
+            }),
+            Item::Interface(decl) => self.node(&format!("Interface \"{}\"", decl.name), |p| {
+                for sig in &decl.functions {
+                    p.node(&format!("Function \"{}\"", sig.name), |p| {
+                        p.params(&sig.params);
+                        if let Some(ty) = &sig.return_type {
+                            p.line(&format!("Returns {}", canonical_display(ty)));
+                        }
+                    });
+                }
+            }),
+            Item::Event(decl) => self.node(&format!("Event \"{}\"", decl.name), |p| {
+                for param in &decl.params {
+                    let indexed = if param.indexed { " [indexed]" } else { "" };
+                    p.line(&format!("Param {}: {}{}", param.name, canonical_display(&param.type_annotation), indexed));
+                }
+            }),
+            Item::Error(decl) => self.node(&format!("Error \"{}\"", decl.name), |p| p.params(&decl.params)),
+            Item::Const(constant) => self.constant(constant),
+        }
+    }
+
+    fn constant(&mut self, constant: &Constant) {
+        self.line(&format!(
+            "Const {}: {} = {}",
+            constant.name,
+            canonical_display(&constant.type_annotation),
+            expr(&constant.value)
+        ));
+    }
+
+    fn member(&mut self, member: &ContractMember) {
+        match member {
+            ContractMember::StateVar(var) => {
+                let mut text = format!("StateVar {}: {}", var.name, canonical_display(&var.type_annotation));
+                if let Some(value) = &var.initial_value {
+                    text.push_str(&format!(" = {}", expr(value)));
+                }
+                self.line(&text);
+            }
+            ContractMember::Constant(constant) => self.constant(constant),
+            ContractMember::Function(func) => {
+                let mut header = format!("Function \"{}\"", func.name);
+                for decorator in &func.decorators {
+                    header.push_str(&format!(" @{}", decorator.name));
+                    if !decorator.args.is_empty() {
+                        header.push_str(&format!("({})", exprs(&decorator.args)));
+                    }
+                }
+                self.node(&header, |p| {
+                    p.params(&func.params);
+                    if let Some(ty) = &func.return_type {
+                        p.line(&format!("Returns {}", canonical_display(ty)));
+                    }
+                    p.block(&func.body);
+                });
+            }
+        }
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Assign(assign) => {
+                let annotation = assign
+                    .type_annotation
+                    .as_ref()
+                    .map(|ty| format!(": {}", canonical_display(ty)))
+                    .unwrap_or_default();
+                self.line(&format!("Assign {}{} = {}", expr(&assign.target), annotation, expr(&assign.value)));
+            }
+            Stmt::Expr(e) => self.line(&format!("Expr {}", expr(e))),
+            Stmt::Return(Some(e)) => self.line(&format!("Return {}", expr(e))),
+            Stmt::Return(None) => self.line("Return"),
+            Stmt::Pass => self.line("Pass"),
+            Stmt::Break => self.line("Break"),
+            Stmt::Continue => self.line("Continue"),
+            Stmt::If(if_stmt) => self.node(&format!("If {}", expr(&if_stmt.condition)), |p| {
+                p.block(&if_stmt.then_branch);
+                for (condition, body) in &if_stmt.elif_branches {
+                    p.node(&format!("Elif {}", expr(condition)), |p| p.block(body));
+                }
+                if let Some(body) = &if_stmt.else_branch {
+                    p.node("Else", |p| p.block(body));
+                }
+            }),
+            Stmt::For(for_stmt) => {
+                self.node(&format!("For {} in {}", for_stmt.variable, expr(&for_stmt.iterable)), |p| {
+                    p.block(&for_stmt.body);
+                    if let Some(body) = &for_stmt.else_branch {
+                        p.node("Else", |p| p.block(body));
+                    }
+                })
+            }
+            Stmt::While(while_stmt) => self.node(&format!("While {}", expr(&while_stmt.condition)), |p| {
+                p.block(&while_stmt.body);
+                if let Some(body) = &while_stmt.else_branch {
+                    p.node("Else", |p| p.block(body));
+                }
+            }),
+            Stmt::Require(req) => {
+                let mut text = format!("Require {}", expr(&req.condition));
+                if let Some(message) = &req.message {
+                    text.push_str(&format!(", {}", expr(message)));
+                }
+                if let Some(error) = &req.error {
+                    text.push_str(&format!(", {}({})", error.error, exprs(&error.args)));
+                }
+                self.line(&text);
+            }
+            Stmt::Revert(message) => self.line(&format!("Revert {:?}", message)),
+            Stmt::Emit(emit) => self.line(&format!("Emit {}({})", emit.event, exprs(&emit.args))),
+            Stmt::Raise(raise) => self.line(&format!("Raise {}({})", raise.error, exprs(&raise.args))),
+        }
+    }
+}
+
+fn exprs(items: &[Expr]) -> String {
+    items.iter().map(expr).collect::<Vec<_>>().join(", ")
+}
+
+/// `e` in source syntax
+fn expr(e: &Expr) -> String {
+    match e {
+        Expr::IntLiteral(value) | Expr::HexLiteral(value) => value.clone(),
+        Expr::StringLiteral(value) => format!("{:?}", value),
+        Expr::BoolLiteral(value) => if *value { "True" } else { "False" }.to_string(),
+        Expr::NoneLiteral => "None".to_string(),
+        Expr::Ident(name) => name.clone(),
+        Expr::BinOp(left, op, right) => format!("{} {} {}", operand(left), bin_op(op), operand(right)),
+        Expr::UnaryOp(op, inner) => match op {
+            UnaryOp::Not => format!("not {}", operand(inner)),
+            UnaryOp::Neg => format!("-{}", operand(inner)),
+            UnaryOp::Pos => format!("+{}", operand(inner)),
+            UnaryOp::BitNot => format!("~{}", operand(inner)),
+        },
+        Expr::Call(callee, args) => format!("{}({})", expr(callee), exprs(args)),
+        Expr::Attribute(base, attr) => format!("{}.{}", expr(base), attr),
+        Expr::Index(base, index) => format!("{}[{}]", expr(base), expr(index)),
+        Expr::List(items) => format!("[{}]", exprs(items)),
+        Expr::Tuple(items) if items.len() == 1 => format!("({},)", expr(&items[0])),
+        Expr::Tuple(items) => format!("({})", exprs(items)),
+        Expr::IfExp { test, body, orelse } => {
+            format!("{} if {} else {}", operand(body), operand(test), operand(orelse))
+        }
+        Expr::KeywordArg(name, value) => format!("{}={}", name, expr(value)),
+        Expr::ListComp { element, variable, iterable, condition } => {
+            let filter = condition.as_ref().map(|c| format!(" if {}", operand(c))).unwrap_or_default();
+            format!("[{} for {} in {}{}]", expr(element), variable, operand(iterable), filter)
+        }
+        Expr::FString(parts) => {
+            let body: String = parts
+                .iter()
+                .map(|part| match part {
+                    Expr::StringLiteral(text) => {
+                        let quoted = format!("{:?}", text);
+                        quoted[1..quoted.len() - 1].replace('{', "{{").replace('}', "}}")
+                    }
+                    hole => format!("{{{}}}", expr(hole)),
+                })
+                .collect();
+            format!("f\"{}\"", body)
+        }
+    }
+}
+
+/// An operand of an operator, parenthesised when it is itself an operation
+fn operand(e: &Expr) -> String {
+    match e {
+        Expr::BinOp(..) | Expr::UnaryOp(..) | Expr::IfExp { .. } => format!("({})", expr(e)),
+        _ => expr(e),
+    }
+}
+
+fn bin_op(op: &BinOp) -> &'static str {
+    match op {
+        BinOp::Add => "+",
+        BinOp::Sub => "-",
+        BinOp::Mul => "*",
+        BinOp::Div => "/",
+        BinOp::FloorDiv => "//",
+        BinOp::Mod => "%",
+        BinOp::Pow => "**",
+        BinOp::Shl => "<<",
+        BinOp::Shr => ">>",
+        BinOp::BitAnd => "&",
+        BinOp::BitOr => "|",
+        BinOp::BitXor => "^",
+        BinOp::Eq => "==",
+        BinOp::NotEq => "!=",
+        BinOp::Lt => "<",
+        BinOp::LtEq => "<=",
+        BinOp::Gt => ">",
+        BinOp::GtEq => ">=",
+        BinOp::And => "and",
+        BinOp::Or => "or",
+        BinOp::In => "in",
+    }
+}
@@ -0,0 +1,84 @@
+//! Canonical type spellings
+//!
+//! Source code may spell the same type more than one way (`str` or
+//! `string`). Checkers and backends go through these helpers so that type
+//! comparisons, ABI signatures and diagnostics all agree on one spelling.
+
+use crate::ast::Type;
+
+/// A type with no representation in the Solidity ABI
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("type '{0}' cannot appear in an ABI signature")]
+pub struct UnsupportedAbiType(pub String);
+
+/// The canonical spelling of a simple type name
+pub fn canonical_name(name: &str) -> &str {
+    match name {
+        "str" | "string" => "string",
+        other => other,
+    }
+}
+
+/// The Solidity ABI spelling of `ty`, as used in function selectors and ABI
+/// JSON. Mappings only exist in storage and arrays sized by a constant must
+/// be resolved first, so both are rejected.
+pub fn canonical_abi_type(ty: &Type) -> Result<String, UnsupportedAbiType> {
+    match ty {
+        Type::Simple(name) => Ok(canonical_name(name).to_string()),
+        Type::List(inner) => Ok(format!("{}[]", canonical_abi_type(inner)?)),
+        Type::FixedArray(inner, size) => Ok(format!("{}[{}]", canonical_abi_type(inner)?, size)),
+        Type::Optional(inner) => canonical_abi_type(inner),
+        Type::Tuple(types) => {
+            let types = types.iter().map(canonical_abi_type).collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("({})", types.join(",")))
+        }
+        Type::Mapping(_, _) | Type::ConstArray(_, _) => Err(UnsupportedAbiType(canonical_display(ty))),
+    }
+}
+
+/// `ty` as written in Quorlin source, for diagnostics
+pub fn canonical_display(ty: &Type) -> String {
+    match ty {
+        Type::Simple(name) => canonical_name(name).to_string(),
+        Type::List(inner) => format!("list[{}]", canonical_display(inner)),
+        Type::FixedArray(inner, size) => format!("{}[{}]", canonical_display(inner), size),
+        Type::ConstArray(inner, size) => format!("{}[{}]", canonical_display(inner), size),
+        Type::Mapping(key, value) => format!("mapping[{}, {}]", canonical_display(key), canonical_display(value)),
+        Type::Optional(inner) => format!("Optional[{}]", canonical_display(inner)),
+        Type::Tuple(types) => {
+            let types: Vec<String> = types.iter().map(canonical_display).collect();
+            format!("({})", types.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simple(name: &str) -> Type {
+        Type::Simple(name.to_string())
+    }
+
+    #[test]
+    fn test_abi_spellings_match_solidity() {
+        for name in ["uint256", "address", "bool", "bytes32"] {
+            assert_eq!(canonical_abi_type(&simple(name)).unwrap(), name);
+        }
+        assert_eq!(canonical_abi_type(&simple("str")).unwrap(), "string");
+        assert_eq!(canonical_abi_type(&Type::List(Box::new(simple("address")))).unwrap(), "address[]");
+        assert_eq!(
+            canonical_abi_type(&Type::Tuple(vec![simple("uint256"), simple("bool")])).unwrap(),
+            "(uint256,bool)"
+        );
+    }
+
+    #[test]
+    fn test_mapping_is_not_an_abi_type() {
+        let mapping = Type::Mapping(Box::new(simple("address")), Box::new(simple("uint256")));
+        assert_eq!(
+            canonical_abi_type(&mapping),
+            Err(UnsupportedAbiType("mapping[address, uint256]".to_string()))
+        );
+    }
+}
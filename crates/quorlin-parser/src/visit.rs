@@ -0,0 +1,330 @@
+//! AST traversal
+//!
+//! `Visitor` has one method per node kind. Every method defaults to walking
+//! the node's children via the matching `walk_*` function, so an implementation
+//! only overrides the nodes it cares about and calls `walk_*` to keep descending.
+
+use crate::ast::*;
+
+/// Read-only AST visitor with default depth-first walking
+pub trait Visitor<'ast> {
+    fn visit_module(&mut self, module: &'ast Module) {
+        walk_module(self, module);
+    }
+
+    fn visit_item(&mut self, item: &'ast Item) {
+        walk_item(self, item);
+    }
+
+    fn visit_contract_member(&mut self, member: &'ast ContractMember) {
+        walk_contract_member(self, member);
+    }
+
+    fn visit_function(&mut self, func: &'ast Function) {
+        walk_function(self, func);
+    }
+
+    fn visit_param(&mut self, param: &'ast Param) {
+        walk_param(self, param);
+    }
+
+    fn visit_event_param(&mut self, param: &'ast EventParam) {
+        walk_event_param(self, param);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        walk_expr(self, expr);
+    }
+
+    fn visit_type(&mut self, ty: &'ast Type) {
+        walk_type(self, ty);
+    }
+}
+
+pub fn walk_module<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, module: &'ast Module) {
+    for item in &module.items {
+        visitor.visit_item(item);
+    }
+}
+
+pub fn walk_item<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, item: &'ast Item) {
+    match item {
+        Item::Import(_) | Item::Enum(_) => {}
+        Item::Contract(contract) => {
+            for member in &contract.body {
+                visitor.visit_contract_member(member);
+            }
+        }
+        Item::Struct(s) => {
+            for field in &s.fields {
+                visitor.visit_type(&field.type_annotation);
+            }
+        }
+        Item::Interface(interface) => {
+            for func in &interface.functions {
+                for param in &func.params {
+                    visitor.visit_param(param);
+                }
+                if let Some(ret) = &func.return_type {
+                    visitor.visit_type(ret);
+                }
+            }
+        }
+        Item::Event(event) => {
+            for param in &event.params {
+                visitor.visit_event_param(param);
+            }
+        }
+        Item::Error(error) => {
+            for param in &error.params {
+                visitor.visit_param(param);
+            }
+        }
+        Item::Const(constant) => {
+            visitor.visit_type(&constant.type_annotation);
+            visitor.visit_expr(&constant.value);
+        }
+    }
+}
+
+pub fn walk_contract_member<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, member: &'ast ContractMember) {
+    match member {
+        ContractMember::StateVar(var) => {
+            visitor.visit_type(&var.type_annotation);
+            if let Some(value) = &var.initial_value {
+                visitor.visit_expr(value);
+            }
+        }
+        ContractMember::Function(func) => visitor.visit_function(func),
+        ContractMember::Constant(constant) => {
+            visitor.visit_type(&constant.type_annotation);
+            visitor.visit_expr(&constant.value);
+        }
+    }
+}
+
+pub fn walk_function<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, func: &'ast Function) {
+    for param in &func.params {
+        visitor.visit_param(param);
+    }
+    if let Some(ret) = &func.return_type {
+        visitor.visit_type(ret);
+    }
+    for stmt in &func.body {
+        visitor.visit_stmt(stmt);
+    }
+}
+
+pub fn walk_param<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, param: &'ast Param) {
+    visitor.visit_type(&param.type_annotation);
+    if let Some(default) = &param.default {
+        visitor.visit_expr(default);
+    }
+}
+
+pub fn walk_event_param<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, param: &'ast EventParam) {
+    visitor.visit_type(&param.type_annotation);
+}
+
+pub fn walk_stmt<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, stmt: &'ast Stmt) {
+    match stmt {
+        Stmt::Assign(assign) => {
+            visitor.visit_expr(&assign.target);
+            if let Some(ty) = &assign.type_annotation {
+                visitor.visit_type(ty);
+            }
+            visitor.visit_expr(&assign.value);
+        }
+        Stmt::Expr(expr) | Stmt::Return(Some(expr)) => visitor.visit_expr(expr),
+        Stmt::Return(None) | Stmt::Pass | Stmt::Break | Stmt::Continue | Stmt::Revert(_) => {}
+        Stmt::If(if_stmt) => {
+            visitor.visit_expr(&if_stmt.condition);
+            for stmt in &if_stmt.then_branch {
+                visitor.visit_stmt(stmt);
+            }
+            for (condition, body) in &if_stmt.elif_branches {
+                visitor.visit_expr(condition);
+                for stmt in body {
+                    visitor.visit_stmt(stmt);
+                }
+            }
+            if let Some(else_branch) = &if_stmt.else_branch {
+                for stmt in else_branch {
+                    visitor.visit_stmt(stmt);
+                }
+            }
+        }
+        Stmt::For(for_stmt) => {
+            visitor.visit_expr(&for_stmt.iterable);
+            for stmt in for_stmt.body.iter().chain(for_stmt.else_branch.iter().flatten()) {
+                visitor.visit_stmt(stmt);
+            }
+        }
+        Stmt::While(while_stmt) => {
+            visitor.visit_expr(&while_stmt.condition);
+            for stmt in while_stmt.body.iter().chain(while_stmt.else_branch.iter().flatten()) {
+                visitor.visit_stmt(stmt);
+            }
+        }
+        Stmt::Require(require) => {
+            visitor.visit_expr(&require.condition);
+            if let Some(message) = &require.message {
+                visitor.visit_expr(message);
+            }
+            for arg in require.error.iter().flat_map(|error| &error.args) {
+                visitor.visit_expr(arg);
+            }
+        }
+        Stmt::Emit(emit) => {
+            for arg in &emit.args {
+                visitor.visit_expr(arg);
+            }
+        }
+        Stmt::Raise(raise) => {
+            for arg in &raise.args {
+                visitor.visit_expr(arg);
+            }
+        }
+    }
+}
+
+pub fn walk_expr<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, expr: &'ast Expr) {
+    match expr {
+        Expr::IntLiteral(_)
+        | Expr::HexLiteral(_)
+        | Expr::StringLiteral(_)
+        | Expr::BoolLiteral(_)
+        | Expr::NoneLiteral
+        | Expr::Ident(_) => {}
+        Expr::BinOp(left, _, right) | Expr::Index(left, right) => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        Expr::UnaryOp(_, operand) | Expr::Attribute(operand, _) | Expr::KeywordArg(_, operand) => {
+            visitor.visit_expr(operand)
+        }
+        Expr::Call(func, args) => {
+            visitor.visit_expr(func);
+            for arg in args {
+                visitor.visit_expr(arg);
+            }
+        }
+        Expr::List(items) | Expr::Tuple(items) | Expr::FString(items) => {
+            for item in items {
+                visitor.visit_expr(item);
+            }
+        }
+        Expr::IfExp { test, body, orelse } => {
+            visitor.visit_expr(test);
+            visitor.visit_expr(body);
+            visitor.visit_expr(orelse);
+        }
+        Expr::ListComp { element, iterable, condition, .. } => {
+            visitor.visit_expr(iterable);
+            visitor.visit_expr(element);
+            if let Some(condition) = condition {
+                visitor.visit_expr(condition);
+            }
+        }
+    }
+}
+
+pub fn walk_type<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, ty: &'ast Type) {
+    match ty {
+        Type::Simple(_) => {}
+        Type::List(inner) | Type::Optional(inner) | Type::FixedArray(inner, _) | Type::ConstArray(inner, _) => {
+            visitor.visit_type(inner);
+        }
+        Type::Mapping(key, value) => {
+            visitor.visit_type(key);
+            visitor.visit_type(value);
+        }
+        Type::Tuple(types) => {
+            for ty in types {
+                visitor.visit_type(ty);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_module;
+    use quorlin_lexer::Lexer;
+
+    struct CallCounter {
+        calls: usize,
+    }
+
+    impl<'ast> Visitor<'ast> for CallCounter {
+        fn visit_expr(&mut self, expr: &'ast Expr) {
+            if matches!(expr, Expr::Call(_, _)) {
+                self.calls += 1;
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    #[test]
+    fn test_visitor_counts_nested_calls() {
+        let source = r#"
+contract Nested:
+    _total: uint256 = compute(1)
+
+    fn run(n: uint256) -> uint256:
+        if n > limit():
+            return 0
+        elif n == 1:
+            self._total = helper(n) if ready() else 0
+        else:
+            for i in range(n):
+                while check(i):
+                    emit Ping(f(g(i)))
+        return n
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let mut counter = CallCounter { calls: 0 };
+        counter.visit_module(&module);
+
+        // compute, limit, helper, ready, range, check, f, g
+        assert_eq!(counter.calls, 8);
+    }
+
+    struct ParamCollector {
+        names: Vec<String>,
+    }
+
+    impl<'ast> Visitor<'ast> for ParamCollector {
+        fn visit_param(&mut self, param: &'ast Param) {
+            self.names.push(param.name.clone());
+            walk_param(self, param);
+        }
+
+        fn visit_event_param(&mut self, param: &'ast EventParam) {
+            self.names.push(param.name.clone());
+            walk_event_param(self, param);
+        }
+    }
+
+    #[test]
+    fn test_visitor_visits_event_and_error_params() {
+        let source = r#"
+event Transfer(sender: address, amount: uint256)
+error InsufficientBalance(needed: uint256)
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let module = parse_module(tokens).unwrap();
+
+        let mut collector = ParamCollector { names: Vec::new() };
+        collector.visit_module(&module);
+
+        assert_eq!(collector.names, ["sender", "amount", "needed"]);
+    }
+}
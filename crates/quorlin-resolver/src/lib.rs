@@ -1,8 +1,12 @@
 //! Quorlin Import Resolver
 //!
-//! Resolves `from std.X import Y` statements by loading stdlib modules.
-//! The stdlib is OPTIONAL - compiler works without it.
+//! Resolves `from std.X import Y` statements by loading stdlib modules,
+//! relative imports (`from .utils import Y`, `from ..shared import Y`) by
+//! loading sibling project files, and other absolute imports
+//! (`from myproj.utils import Y`) from the configured include paths. The
+//! stdlib is OPTIONAL - compiler works without it.
 
+use quorlin_lexer::Lexer;
 use quorlin_parser::ast::{ImportStmt, Module};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -44,6 +48,9 @@ pub struct StdlibResolver {
     
     /// Currently resolving modules (for circular dependency detection)
     resolving_stack: Vec<String>,
+    
+    /// Project source roots searched, in order, for non-std absolute imports
+    include_paths: Vec<PathBuf>,
 }
 
 impl StdlibResolver {
@@ -68,9 +75,15 @@ impl StdlibResolver {
             module_cache: HashMap::new(),
             stdlib_enabled,
             resolving_stack: Vec::new(),
+            include_paths: Vec::new(),
         }
     }
     
+    /// Adds a project source root searched for non-std absolute imports
+    pub fn add_include_path(&mut self, dir: impl Into<PathBuf>) {
+        self.include_paths.push(dir.into());
+    }
+    
     /// Checks if stdlib is available
     pub fn is_available(&self) -> bool {
         self.stdlib_enabled
@@ -83,16 +96,22 @@ impl StdlibResolver {
     ///
     /// # Returns
     /// * `Ok(Some(source))` - Module source code
-    /// * `Ok(None)` - Stdlib not available (not an error)
+    /// * `Ok(None)` - Stdlib not available, or a non-std module is not on
+    ///   any include path (not an error)
     /// * `Err(_)` - Module should exist but couldn't be loaded
     pub fn resolve_import(&mut self, import: &ImportStmt) -> Result<Option<String>, ResolverError> {
+        let module_path = &import.module;
+        let is_std = module_path.split('.').next() == Some("std");
+        
+        // Non-std modules can only come from the include paths, if any
+        if !is_std {
+            return self.load_from_include_paths(module_path);
+        }
         // If stdlib not enabled, return None (not an error)
         if !self.stdlib_enabled {
             return Ok(None);
         }
         
-        let module_path = &import.module;
-        
         // Check cache first
         if let Some(cached) = self.module_cache.get(module_path) {
             return Ok(Some(cached.clone()));
@@ -115,6 +134,67 @@ impl StdlibResolver {
         result
     }
     
+    /// Resolves an import statement appearing in `importing_file`
+    ///
+    /// Relative imports are loaded from the importing file's directory (one
+    /// leading dot) or its ancestors (each further dot goes up a level) and
+    /// must exist. Absolute imports go through `resolve_import`.
+    pub fn resolve_import_from(
+        &mut self,
+        import: &ImportStmt,
+        importing_file: &Path,
+    ) -> Result<Option<String>, ResolverError> {
+        if !import.is_relative() {
+            return self.resolve_import(import);
+        }
+        
+        let file_path = Self::relative_module_file(import, importing_file)?;
+        let key = file_path.to_string_lossy().into_owned();
+        
+        if let Some(cached) = self.module_cache.get(&key) {
+            return Ok(Some(cached.clone()));
+        }
+        if !file_path.is_file() {
+            return Err(ResolverError::ModuleNotFound(format!(
+                "{} (looked for {})",
+                import.source_path(),
+                file_path.display()
+            )));
+        }
+        
+        let contents = std::fs::read_to_string(&file_path)?;
+        self.module_cache.insert(key, contents.clone());
+        Ok(Some(contents))
+    }
+    
+    /// Converts a relative import to a file path
+    ///
+    /// Examples, importing from `src/main.ql`:
+    /// - ".utils" -> "src/utils.ql"
+    /// - "..shared.math" -> "shared/math.ql"
+    fn relative_module_file(import: &ImportStmt, importing_file: &Path) -> Result<PathBuf, ResolverError> {
+        let mut path = importing_file.parent().unwrap_or(Path::new("")).to_path_buf();
+        for _ in 1..import.level {
+            if !path.pop() {
+                return Err(ResolverError::InvalidModulePath(format!(
+                    "{} goes above the root of {}",
+                    import.source_path(),
+                    importing_file.display()
+                )));
+            }
+        }
+        
+        for part in import.module.split('.') {
+            if part.is_empty() {
+                return Err(ResolverError::InvalidModulePath(import.source_path()));
+            }
+            path.push(part);
+        }
+        path.set_extension("ql");
+        
+        Ok(path)
+    }
+    
     /// Loads a module from the filesystem
     fn load_module(&mut self, module_path: &str) -> Result<Option<String>, ResolverError> {
         // Convert module path to file path
@@ -127,7 +207,7 @@ impl StdlibResolver {
         
         // Read file
         let contents = std::fs::read_to_string(&file_path)
-            .map_err(|e| ResolverError::IoError(e))?;
+            .map_err(ResolverError::IoError)?;
         
         // Cache the module
         self.module_cache.insert(module_path.to_string(), contents.clone());
@@ -135,6 +215,30 @@ impl StdlibResolver {
         Ok(Some(contents))
     }
     
+    /// Loads a non-std module from the first include path that has it
+    fn load_from_include_paths(&mut self, module_path: &str) -> Result<Option<String>, ResolverError> {
+        if let Some(cached) = self.module_cache.get(module_path) {
+            return Ok(Some(cached.clone()));
+        }
+        if module_path.split('.').any(str::is_empty) {
+            return Err(ResolverError::InvalidModulePath(module_path.to_string()));
+        }
+        
+        let relative: PathBuf = module_path.split('.').collect();
+        let Some(file_path) = self
+            .include_paths
+            .iter()
+            .map(|dir| dir.join(&relative).with_extension("ql"))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+        
+        let contents = std::fs::read_to_string(&file_path)?;
+        self.module_cache.insert(module_path.to_string(), contents.clone());
+        Ok(Some(contents))
+    }
+    
     /// Converts a module path to a file path
     ///
     /// Examples:
@@ -190,6 +294,74 @@ impl StdlibResolver {
         Ok(resolved)
     }
     
+    /// Resolves all imports in a module read from `source_file`, including
+    /// relative ones
+    ///
+    /// Relatively imported files are parsed and their own imports resolved in
+    /// turn. Absolute imports are keyed by module path and relative imports
+    /// by the file they were loaded from. An import that leads back to a file
+    /// still being resolved is a `CircularDependency`.
+    pub fn resolve_all_imports_from(
+        &mut self,
+        module: &Module,
+        source_file: &Path,
+    ) -> Result<HashMap<String, String>, ResolverError> {
+        let mut resolved = HashMap::new();
+        
+        self.resolving_stack.push(source_file.to_string_lossy().into_owned());
+        let result = self.resolve_imports_into(module, source_file, &mut resolved);
+        self.resolving_stack.pop();
+        
+        result.map(|()| resolved)
+    }
+    
+    fn resolve_imports_into(
+        &mut self,
+        module: &Module,
+        source_file: &Path,
+        resolved: &mut HashMap<String, String>,
+    ) -> Result<(), ResolverError> {
+        for item in &module.items {
+            let quorlin_parser::ast::Item::Import(import) = item else {
+                continue;
+            };
+            if !import.is_relative() {
+                if let Some(source) = self.resolve_import(import)? {
+                    resolved.insert(import.module.clone(), source);
+                }
+                continue;
+            }
+            
+            let file_path = Self::relative_module_file(import, source_file)?;
+            let key = file_path.to_string_lossy().into_owned();
+            if self.resolving_stack.contains(&key) {
+                return Err(ResolverError::CircularDependency(format!(
+                    "{} (imported by {})",
+                    file_path.display(),
+                    source_file.display()
+                )));
+            }
+            let Some(source) = self.resolve_import_from(import, source_file)? else {
+                continue;
+            };
+            
+            let tokens = Lexer::new(&source)
+                .tokenize()
+                .map_err(|e| ResolverError::ParseError(format!("{}: {}", file_path.display(), e)))?;
+            let imported = quorlin_parser::parse_module(tokens)
+                .map_err(|e| ResolverError::ParseError(format!("{}: {}", file_path.display(), e)))?;
+            
+            self.resolving_stack.push(key.clone());
+            let result = self.resolve_imports_into(&imported, &file_path, resolved);
+            self.resolving_stack.pop();
+            result?;
+            
+            resolved.insert(key, source);
+        }
+        
+        Ok(())
+    }
+    
     /// Clears the module cache
     pub fn clear_cache(&mut self) {
         self.module_cache.clear();
@@ -199,8 +371,6 @@ impl StdlibResolver {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
-    use std::path::PathBuf;
     
     #[test]
     fn test_module_path_conversion() {
@@ -221,6 +391,97 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[test]
+    fn test_relative_import_resolves_next_to_source_file() {
+        let project = std::env::temp_dir().join(format!("quorlin-resolver-{}", std::process::id()));
+        std::fs::create_dir_all(project.join("contracts")).unwrap();
+        std::fs::write(project.join("contracts").join("utils.ql"), "fn helper():\n    pass\n").unwrap();
+        std::fs::write(project.join("shared.ql"), "fn common():\n    pass\n").unwrap();
+        let main = project.join("contracts").join("main.ql");
+        
+        // Relative imports work without a stdlib
+        let mut resolver = StdlibResolver::new(&project.join("missing"));
+        let sibling = ImportStmt { module: "utils".to_string(), items: vec!["helper".to_string()], level: 1 };
+        let source = resolver.resolve_import_from(&sibling, &main).unwrap();
+        assert_eq!(source.as_deref(), Some("fn helper():\n    pass\n"));
+        
+        let parent = ImportStmt { module: "shared".to_string(), items: vec!["common".to_string()], level: 2 };
+        assert!(resolver.resolve_import_from(&parent, &main).unwrap().is_some());
+        
+        let missing = ImportStmt { module: "nope".to_string(), items: vec!["x".to_string()], level: 1 };
+        assert!(matches!(
+            resolver.resolve_import_from(&missing, &main),
+            Err(ResolverError::ModuleNotFound(_))
+        ));
+        
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+    
+    #[test]
+    fn test_relative_imports_resolve_transitively_and_detect_cycles() {
+        let project = std::env::temp_dir().join(format!("quorlin-resolver-cycle-{}", std::process::id()));
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("math.ql"), "event Ping(value: uint256)\n").unwrap();
+        std::fs::write(project.join("utils.ql"), "from .math import Ping\n").unwrap();
+        std::fs::write(project.join("a.ql"), "from .b import Pong\n").unwrap();
+        std::fs::write(project.join("b.ql"), "from .a import Pang\n").unwrap();
+        
+        let module_importing = |module: &str| Module {
+            items: vec![quorlin_parser::ast::Item::Import(ImportStmt {
+                module: module.to_string(),
+                items: vec!["x".to_string()],
+                level: 1,
+            })],
+        };
+        
+        let mut resolver = StdlibResolver::new(&project.join("missing"));
+        let resolved = resolver
+            .resolve_all_imports_from(&module_importing("utils"), &project.join("main.ql"))
+            .unwrap();
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.keys().any(|key| key.ends_with("math.ql")), "{:?}", resolved.keys());
+        
+        let result = resolver.resolve_all_imports_from(&module_importing("b"), &project.join("a.ql"));
+        assert!(matches!(result, Err(ResolverError::CircularDependency(_))), "{:?}", result);
+        
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+    
+    #[test]
+    fn test_absolute_import_resolves_from_include_path() {
+        let root = std::env::temp_dir().join(format!("quorlin-resolver-include-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("myproj")).unwrap();
+        std::fs::write(root.join("myproj").join("utils.ql"), "event Ping(value: uint256)\n").unwrap();
+        
+        let mut resolver = StdlibResolver::new(&root.join("missing"));
+        resolver.add_include_path(root.join("elsewhere"));
+        resolver.add_include_path(&root);
+        let import = ImportStmt { module: "myproj.utils".to_string(), items: vec!["Ping".to_string()], level: 0 };
+        let source = resolver.resolve_import(&import).unwrap();
+        assert_eq!(source.as_deref(), Some("event Ping(value: uint256)\n"));
+        
+        let missing = ImportStmt { module: "myproj.nope".to_string(), items: vec!["x".to_string()], level: 0 };
+        assert!(resolver.resolve_import(&missing).unwrap().is_none());
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+    
+    #[test]
+    fn test_non_std_import_without_include_paths_is_unresolved() {
+        let root = std::env::temp_dir().join(format!("quorlin-resolver-no-include-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("stdlib")).unwrap();
+        let import = ImportStmt { module: "myproj.utils".to_string(), items: vec!["Ping".to_string()], level: 0 };
+        
+        let mut with_stdlib = StdlibResolver::new(&root);
+        assert!(with_stdlib.is_available());
+        assert!(with_stdlib.resolve_import(&import).unwrap().is_none());
+        
+        let mut without_stdlib = StdlibResolver::new(&root.join("missing"));
+        assert!(without_stdlib.resolve_import(&import).unwrap().is_none());
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+    
     #[test]
     fn test_stdlib_not_available() {
         let temp_dir = std::env::temp_dir().join("nonexistent");
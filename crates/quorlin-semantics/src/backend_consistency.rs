@@ -5,7 +5,7 @@
 //! This ensures that Quorlin code behaves consistently regardless of which blockchain it's deployed to.
 
 use quorlin_parser::Type;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Backend type mapping errors
 #[derive(Debug, Clone, PartialEq)]
@@ -62,6 +62,14 @@ struct BackendTypeInfo {
 /// Backend consistency checker
 pub struct BackendConsistencyChecker {
     type_mappings: HashMap<String, (BackendTypeInfo, BackendTypeInfo, BackendTypeInfo)>,
+    /// Types each backend deliberately represents at its own native width
+    size_allowances: HashSet<String>,
+}
+
+impl Default for BackendConsistencyChecker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BackendConsistencyChecker {
@@ -69,12 +77,19 @@ impl BackendConsistencyChecker {
     pub fn new() -> Self {
         let mut checker = Self {
             type_mappings: HashMap::new(),
+            size_allowances: HashSet::new(),
         };
 
         checker.initialize_type_mappings();
         checker
     }
 
+    /// Accept size differences for `name` across backends instead of
+    /// reporting them. Support and overflow behaviour are still checked.
+    pub fn allow_size_difference(&mut self, name: &str) {
+        self.size_allowances.insert(name.to_string());
+    }
+
     /// Initialize known type mappings
     fn initialize_type_mappings(&mut self) {
         // Unsigned integers
@@ -96,8 +111,10 @@ impl BackendConsistencyChecker {
         // Boolean
         self.add_type("bool", 1, true, 1, true, 1, true);
 
-        // Address
+        // Address: 20-byte EVM accounts vs 32-byte public keys. Each backend
+        // encodes addresses its own way, so the width difference is expected.
         self.add_type("address", 160, true, 256, true, 256, true);
+        self.allow_size_difference("address");
 
         // Strings and bytes
         self.add_type_varied("str", true, None, true, None, true, None);
@@ -106,6 +123,7 @@ impl BackendConsistencyChecker {
     }
 
     /// Add a type with uniform support across backends
+    #[allow(clippy::too_many_arguments)]
     fn add_type(
         &mut self,
         name: &str,
@@ -139,6 +157,7 @@ impl BackendConsistencyChecker {
     }
 
     /// Add a type with varied support
+    #[allow(clippy::too_many_arguments)]
     fn add_type_varied(
         &mut self,
         name: &str,
@@ -198,7 +217,9 @@ impl BackendConsistencyChecker {
                     }
 
                     // Check size consistency
-                    if evm.bit_size != solana.bit_size || solana.bit_size != ink.bit_size {
+                    if !self.size_allowances.contains(name)
+                        && (evm.bit_size != solana.bit_size || solana.bit_size != ink.bit_size)
+                    {
                         errors.push(ConsistencyError::InconsistentSize {
                             quorlin_type: name.clone(),
                             evm_size: evm.bit_size,
@@ -251,7 +272,7 @@ impl BackendConsistencyChecker {
                 let solana_status = if solana.supported { "✅" } else { "❌" };
                 let ink_status = if ink.supported { "✅" } else { "❌" };
 
-                let notes = if evm.bit_size == solana.bit_size && solana.bit_size == ink.bit_size {
+                let mut notes = if evm.bit_size == solana.bit_size && solana.bit_size == ink.bit_size {
                     format!("{} bits", evm.bit_size.map_or("variable".to_string(), |s| s.to_string()))
                 } else {
                     format!(
@@ -261,6 +282,9 @@ impl BackendConsistencyChecker {
                         ink.bit_size.map_or("var".to_string(), |s| s.to_string())
                     )
                 };
+                if self.size_allowances.contains(name.as_str()) {
+                    notes.push_str(" (native width per backend)");
+                }
 
                 report.push_str(&format!("| {} | {} | {} | {} | {} |\n", name, evm_status, solana_status, ink_status, notes));
             }
@@ -285,6 +309,21 @@ mod tests {
         assert_eq!(errors.len(), 0, "uint256 should be consistent across backends");
     }
 
+    #[test]
+    fn test_address_width_difference_is_allowed() {
+        let checker = BackendConsistencyChecker::new();
+        let balances = Type::Mapping(
+            Box::new(Type::Simple("address".to_string())),
+            Box::new(Type::Simple("uint256".to_string())),
+        );
+        let errors = checker.check_type(&balances);
+        assert!(
+            !errors.iter().any(|e| matches!(e, ConsistencyError::InconsistentSize { .. })),
+            "{:?}",
+            errors
+        );
+    }
+
     #[test]
     fn test_generate_report() {
         let checker = BackendConsistencyChecker::new();
@@ -9,6 +9,8 @@ pub mod symbol_table;
 pub mod type_checker;
 pub mod validator;
 
+use quorlin_lexer::Span;
+use quorlin_parser::type_names::canonical_display;
 use quorlin_parser::{Module, Type};
 use std::collections::HashMap;
 
@@ -46,9 +48,74 @@ pub enum SemanticError {
 /// Result type for semantic analysis
 pub type SemanticResult<T> = Result<T, SemanticError>;
 
+/// Non-fatal semantic diagnostics
+#[derive(Debug, Clone, PartialEq)]
+pub enum SemanticWarning {
+    /// State variables without a declared default that `__init__` never assigns
+    UninitializedState {
+        contract: String,
+        variables: Vec<String>,
+    },
+
+    /// Mapping membership test, which on EVM treats keys set to zero as absent
+    ZeroValueMembership {
+        mapping: String,
+    },
+
+    /// `if`/`elif` chain over an enum value that misses variants and has no `else`
+    NonExhaustiveEnum {
+        subject: String,
+        enum_name: String,
+        missing: Vec<String>,
+    },
+
+    /// Function that reads state but never writes it, emits or calls out,
+    /// and is not marked `@view`
+    MissingView {
+        function: String,
+    },
+}
+
+impl std::fmt::Display for SemanticWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemanticWarning::UninitializedState { contract, variables } => {
+                write!(
+                    f,
+                    "⚠️  UNINITIALIZED STATE in '{}': __init__ never assigns {}",
+                    contract,
+                    variables.join(", ")
+                )
+            }
+            SemanticWarning::ZeroValueMembership { mapping } => {
+                write!(
+                    f,
+                    "⚠️  MAPPING MEMBERSHIP on '{}': on EVM, keys mapped to a zero value are reported as absent",
+                    mapping
+                )
+            }
+            SemanticWarning::NonExhaustiveEnum { subject, enum_name, missing } => {
+                write!(
+                    f,
+                    "⚠️  NON-EXHAUSTIVE ENUM CHECK on '{}': if/elif over {} has no else and does not handle {}",
+                    subject,
+                    enum_name,
+                    missing.join(", ")
+                )
+            }
+            SemanticWarning::MissingView { function } => {
+                write!(
+                    f,
+                    "⚠️  MISSING @view on '{}': it only reads state; marking it @view makes that explicit in the ABI",
+                    function
+                )
+            }
+        }
+    }
+}
+
 /// Context for tracking current function being analyzed
 struct FunctionContext {
-    name: String,
     return_type: Option<Type>,
     has_return: bool,
 }
@@ -58,9 +125,6 @@ pub struct SemanticAnalyzer {
     /// Symbol table for tracking definitions
     symbols: symbol_table::SymbolTable,
 
-    /// Type environment
-    type_env: HashMap<String, Type>,
-
     /// Current function context (for return type checking)
     current_function: Option<FunctionContext>,
 
@@ -69,6 +133,42 @@ pub struct SemanticAnalyzer {
 
     /// Function return types (function_name -> return_type)
     function_return_types: HashMap<String, Option<Type>>,
+
+    /// Function parameters (function_name -> params), for keyword arguments
+    function_params: HashMap<String, Vec<quorlin_parser::Param>>,
+
+    /// Declared constants (name -> value expression)
+    constants: HashMap<String, quorlin_parser::Expr>,
+
+    /// Declared enums (name -> variants)
+    enums: HashMap<String, Vec<String>>,
+
+    /// Declared custom errors (name -> parameter count)
+    error_arities: HashMap<String, usize>,
+
+    /// Non-fatal diagnostics collected during analysis
+    warnings: Vec<SemanticWarning>,
+
+    /// Errors collected so far; analysis continues past each one
+    errors: Vec<SemanticError>,
+
+    /// Declaration each error was found in, by index into `errors`
+    error_spans: Vec<Option<Span>>,
+
+    /// Declaration being checked, recorded against any error it raises
+    current_span: Option<Span>,
+
+    /// Whether the security pass runs after type checking
+    security_enabled: bool,
+
+    /// Findings of the last security pass
+    security_warnings: Vec<security_analyzer::SecurityWarning>,
+
+    /// Security findings at or above this severity fail the analysis
+    deny_security: Option<security_analyzer::Severity>,
+
+    /// Whether conditions whose type could not be inferred are accepted
+    lenient_conditions: bool,
 }
 
 impl SemanticAnalyzer {
@@ -76,41 +176,167 @@ impl SemanticAnalyzer {
     pub fn new() -> Self {
         Self {
             symbols: symbol_table::SymbolTable::new(),
-            type_env: HashMap::new(),
             current_function: None,
             initialized_vars: std::collections::HashSet::new(),
             function_return_types: HashMap::new(),
+            function_params: HashMap::new(),
+            constants: HashMap::new(),
+            enums: HashMap::new(),
+            error_arities: HashMap::new(),
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            error_spans: Vec::new(),
+            current_span: None,
+            security_enabled: true,
+            security_warnings: Vec::new(),
+            deny_security: None,
+            lenient_conditions: false,
         }
     }
 
-    /// Analyze a module
-    pub fn analyze(&mut self, module: &Module) -> SemanticResult<()> {
+    /// Enable or disable the security analysis pass (enabled by default)
+    pub fn set_security_analysis(&mut self, enabled: bool) {
+        self.security_enabled = enabled;
+    }
+
+    /// Accept `if`/`while`/`require` conditions whose type could not be
+    /// inferred instead of rejecting them (disabled by default)
+    pub fn set_lenient_conditions(&mut self, lenient: bool) {
+        self.lenient_conditions = lenient;
+    }
+
+    /// Make security warnings at or above `threshold` an error instead of
+    /// only printing them (`None`, the default, never fails)
+    pub fn set_deny_security(&mut self, threshold: Option<security_analyzer::Severity>) {
+        self.deny_security = threshold;
+    }
+
+    /// Security findings from the last call to `analyze`
+    pub fn security_warnings(&self) -> &[security_analyzer::SecurityWarning] {
+        &self.security_warnings
+    }
+
+    /// Warnings collected by the last call to `analyze`
+    pub fn warnings(&self) -> &[SemanticWarning] {
+        &self.warnings
+    }
+
+    /// Declarations the errors of the last `analyze_all` were found in, in the
+    /// same order (`None` where no enclosing declaration is known)
+    pub fn error_spans(&self) -> &[Option<Span>] {
+        &self.error_spans
+    }
+
+    /// Analyze a module, stopping at the first error
+    pub fn analyze(&mut self, module: &mut Module) -> SemanticResult<()> {
+        self.analyze_all(module).map_err(|mut errors| errors.remove(0))
+    }
+
+    /// Analyze a module, reporting every error found rather than only the first.
+    /// Constant array lengths in `module` are resolved in place once constants
+    /// have been collected.
+    pub fn analyze_all(&mut self, module: &mut Module) -> Result<(), Vec<SemanticError>> {
+        self.warnings.clear();
+        self.errors.clear();
+        self.error_spans.clear();
+
         // First pass: collect all top-level definitions
         for item in &module.items {
-            self.collect_definitions(item)?;
+            self.current_span = None;
+            self.recover(|this| this.collect_definitions(item));
         }
 
         // Second pass: type check and validate
         for item in &module.items {
-            self.check_item(item)?;
+            self.current_span = None;
+            self.recover(|this| this.check_item(item));
+        }
+
+        // Overrides are checked against the whole inheritance chain
+        let contracts: Vec<&quorlin_parser::ContractDecl> = module
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                quorlin_parser::Item::Contract(contract) => Some(contract),
+                _ => None,
+            })
+            .collect();
+        for contract in &contracts {
+            self.current_span = Some(contract.span);
+            self.recover(|_| validator::validate_overrides(contract, &contracts));
+        }
+
+        // Constructor completeness and read-only functions (non-fatal)
+        for item in &module.items {
+            if let quorlin_parser::Item::Contract(contract) = item {
+                self.check_constructor_initializes_state(contract);
+                self.check_missing_view(contract);
+            }
+        }
+
+        self.current_span = None;
+        self.recover(|this| this.resolve_types(module));
+
+        if !self.warnings.is_empty() {
+            eprintln!("\n⚠️  Semantic Warnings:");
+            for warning in &self.warnings {
+                eprintln!("   {}", warning);
+            }
+            eprintln!();
+        }
+
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors));
         }
 
         // Third pass: security analysis
-        let mut security_analyzer = security_analyzer::SecurityAnalyzer::new();
-        let warnings = security_analyzer.analyze(module);
+        self.security_warnings.clear();
+        if self.security_enabled {
+            let mut security_analyzer = security_analyzer::SecurityAnalyzer::new();
+            self.security_warnings = security_analyzer.analyze(module);
+        }
 
         // Print security warnings (non-fatal)
-        if !warnings.is_empty() {
+        if !self.security_warnings.is_empty() {
             eprintln!("\n🔒 Security Analysis Warnings:");
-            for warning in &warnings {
+            for warning in &self.security_warnings {
                 eprintln!("   {}", warning);
             }
             eprintln!();
         }
 
+        if let Some(threshold) = self.deny_security {
+            let denied: Vec<String> = self
+                .security_warnings
+                .iter()
+                .filter(|warning| warning.severity() >= threshold)
+                .map(|warning| warning.to_string())
+                .collect();
+            if !denied.is_empty() {
+                self.error_spans = vec![None];
+                return Err(vec![SemanticError::ValidationError(format!(
+                    "{} security warning(s) at or above {} severity: {}",
+                    denied.len(),
+                    threshold,
+                    denied.join("; ")
+                ))]);
+            }
+        }
+
         Ok(())
     }
 
+    /// Run one analysis step, recording its error and closing any scopes it
+    /// left open so the steps after it can still be checked
+    fn recover(&mut self, step: impl FnOnce(&mut Self) -> SemanticResult<()>) {
+        let depth = self.symbols.scope_depth();
+        if let Err(error) = step(self) {
+            self.symbols.unwind_to(depth);
+            self.errors.push(error);
+            self.error_spans.push(self.current_span);
+        }
+    }
+
     fn collect_definitions(&mut self, item: &quorlin_parser::Item) -> SemanticResult<()> {
         use quorlin_parser::Item;
 
@@ -124,16 +350,38 @@ impl SemanticAnalyzer {
                 self.symbols.define_event(&event.name)?;
                 Ok(())
             }
+            Item::Error(error) => {
+                self.symbols.define_error(&error.name)?;
+                self.error_arities.insert(error.name.clone(), error.params.len());
+                Ok(())
+            }
             Item::Contract(contract) => {
+                self.current_span = Some(contract.span);
                 self.symbols.define_contract(&contract.name)?;
                 // Collect contract members
                 self.symbols.enter_scope();
                 for member in &contract.body {
+                    self.current_span = member_span(member).or(Some(contract.span));
                     self.collect_contract_member(member)?;
                 }
                 self.symbols.exit_scope();
                 Ok(())
             }
+            Item::Interface(interface) => {
+                self.symbols.define_interface(&interface.name)?;
+                Ok(())
+            }
+            Item::Enum(enum_decl) => {
+                self.enums.insert(enum_decl.name.clone(), enum_decl.variants.clone());
+                Ok(())
+            }
+            // Module constants live in the global scope, visible to every contract
+            Item::Const(constant) => {
+                self.symbols.define_variable(&constant.name, &constant.type_annotation)?;
+                self.initialized_vars.insert(constant.name.clone());
+                self.constants.insert(constant.name.clone(), constant.value.clone());
+                Ok(())
+            }
             _ => Ok(()),
         }
     }
@@ -157,9 +405,15 @@ impl SemanticAnalyzer {
                 self.symbols.define_function(&func.name)?;
                 // Store function return type for later type inference
                 self.function_return_types.insert(func.name.clone(), func.return_type.clone());
+                self.function_params.insert(func.name.clone(), func.params.clone());
+                Ok(())
+            }
+            ContractMember::Constant(constant) => {
+                self.symbols.define_variable(&constant.name, &constant.type_annotation)?;
+                self.initialized_vars.insert(constant.name.clone());
+                self.constants.insert(constant.name.clone(), constant.value.clone());
                 Ok(())
             }
-            _ => Ok(()),
         }
     }
 
@@ -170,28 +424,80 @@ impl SemanticAnalyzer {
             Item::Contract(contract) => {
                 self.symbols.enter_scope();
 
-                // Re-define state variables in this scope so they're available for type checking
+                // Re-define state variables and constants in this scope so they're available for type checking
                 for member in &contract.body {
-                    if let quorlin_parser::ContractMember::StateVar(var) = member {
-                        self.symbols.define_variable(&var.name, &var.type_annotation)?;
-                        if var.initial_value.is_some() {
-                            self.initialized_vars.insert(var.name.clone());
+                    self.current_span = member_span(member).or(Some(contract.span));
+                    match member {
+                        quorlin_parser::ContractMember::StateVar(var) => {
+                            let ty = self.resolve_type(&var.type_annotation)?;
+                            self.symbols.define_variable(&var.name, &ty)?;
+                            if var.initial_value.is_some() {
+                                self.initialized_vars.insert(var.name.clone());
+                            }
                         }
+                        quorlin_parser::ContractMember::Constant(constant) => {
+                            self.symbols.define_variable(&constant.name, &constant.type_annotation)?;
+                        }
+                        _ => {}
                     }
                 }
 
                 // Check each member
                 for member in &contract.body {
-                    self.check_contract_member(member)?;
+                    self.current_span = member_span(member).or(Some(contract.span));
+                    self.recover(|this| this.check_contract_member(member));
                 }
+                self.current_function = None;
+                self.current_span = Some(contract.span);
+
+                self.recover(|_| validator::validate_view_call_graph(contract));
+                self.recover(|_| validator::validate_inline_not_recursive(contract));
 
                 self.symbols.exit_scope();
                 Ok(())
             }
+            Item::Const(constant) => validator::validate_int_literal_range(&constant.type_annotation, &constant.value),
             _ => Ok(()),
         }
     }
 
+    /// Warn when an `if`/`elif` chain without `else` compares one value
+    /// against some, but not all, variants of an enum
+    fn check_enum_exhaustiveness(&mut self, if_stmt: &quorlin_parser::IfStmt) {
+        let conditions = std::iter::once(&if_stmt.condition)
+            .chain(if_stmt.elif_branches.iter().map(|(condition, _)| condition));
+
+        let mut comparisons = Vec::new();
+        for condition in conditions {
+            match enum_comparisons(condition, &self.enums) {
+                Some(found) => comparisons.extend(found),
+                None => return,
+            }
+        }
+
+        let Some(&(subject, enum_name, _)) = comparisons.first() else {
+            return;
+        };
+        if comparisons.iter().any(|&(other, name, _)| other != subject || name != enum_name) {
+            return;
+        }
+
+        let covered: std::collections::HashSet<&str> =
+            comparisons.iter().map(|&(_, _, variant)| variant).collect();
+        let missing: Vec<String> = self.enums[enum_name]
+            .iter()
+            .filter(|variant| !covered.contains(variant.as_str()))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            self.warnings.push(SemanticWarning::NonExhaustiveEnum {
+                subject: describe_target(subject),
+                enum_name: enum_name.to_string(),
+                missing,
+            });
+        }
+    }
+
     fn check_contract_member(
         &mut self,
         member: &quorlin_parser::ContractMember,
@@ -204,10 +510,10 @@ impl SemanticAnalyzer {
                 for decorator in &func.decorators {
                     validator::validate_decorator(decorator, "function")?;
                 }
+                validator::validate_init_decorators(func)?;
 
                 // Set function context for return type checking
                 self.current_function = Some(FunctionContext {
-                    name: func.name.clone(),
                     return_type: func.return_type.clone(),
                     has_return: false,
                 });
@@ -217,13 +523,19 @@ impl SemanticAnalyzer {
 
                 // Add parameters to scope (parameters are always initialized)
                 for param in &func.params {
-                    self.symbols.define_variable(&param.name, &param.type_annotation)?;
+                    let ty = self.resolve_type(&param.type_annotation)?;
+                    self.symbols.define_variable(&param.name, &ty)?;
                     self.initialized_vars.insert(param.name.clone());
                 }
 
+                // @validate constraints run at entry, so they see only the parameters
+                for constraint in validator::parse_validations(func)? {
+                    self.check_condition(&constraint)?;
+                }
+
                 // Check function body
                 for stmt in &func.body {
-                    self.check_statement(stmt)?;
+                    self.recover(|this| this.check_statement(stmt));
                 }
 
                 // Check that non-void functions have return statements
@@ -238,7 +550,16 @@ impl SemanticAnalyzer {
                 self.current_function = None;
                 Ok(())
             }
-            _ => Ok(()),
+            ContractMember::StateVar(var) => {
+                if let Some(value) = &var.initial_value {
+                    validator::validate_fixed_bytes_literal(&var.type_annotation, value)?;
+                    validator::validate_int_literal_range(&var.type_annotation, value)?;
+                }
+                Ok(())
+            }
+            ContractMember::Constant(constant) => {
+                validator::validate_int_literal_range(&constant.type_annotation, &constant.value)
+            }
         }
     }
 
@@ -247,17 +568,50 @@ impl SemanticAnalyzer {
 
         match stmt {
             Stmt::Assign(assign) => {
+                // Constants are inlined by the backends and can never change
+                let assigned = match &assign.target {
+                    quorlin_parser::Expr::Ident(name) => Some(name),
+                    quorlin_parser::Expr::Attribute(base, name)
+                        if matches!(&**base, quorlin_parser::Expr::Ident(b) if b == "self") =>
+                    {
+                        Some(name)
+                    }
+                    _ => None,
+                };
+                if let Some(name) = assigned.filter(|name| self.constants.contains_key(*name)) {
+                    return Err(SemanticError::InvalidOperation(format!("Cannot assign to constant '{}'", name)));
+                }
+
                 // Check that the value expression type-checks
                 let value_type = self.check_expression(&assign.value)?;
 
                 // If target has type annotation, validate compatibility
-                if let Some(target_type) = &assign.type_annotation {
-                    type_checker::check_type_compatibility(target_type, &value_type)?;
+                let annotation = match &assign.type_annotation {
+                    Some(ty) => Some(self.resolve_type(ty)?),
+                    None => None,
+                };
+                if let (Some(annotated), quorlin_parser::Expr::Attribute(base, name)) = (&annotation, &assign.target) {
+                    if matches!(&**base, quorlin_parser::Expr::Ident(b) if b == "self") {
+                        self.check_state_annotation(name, annotated)?;
+                    }
+                }
+                if let Some(target_type) = &annotation {
+                    validator::validate_int_literal_range(target_type, &assign.value)?;
+                    if !validator::validate_fixed_bytes_literal(target_type, &assign.value)? {
+                        type_checker::check_type_compatibility(target_type, &value_type)?;
+                    }
+                }
+
+                if let quorlin_parser::Expr::Tuple(targets) = &assign.target {
+                    return self.check_unpacking(targets, &value_type);
                 }
 
                 // Infer and check target type
                 let target_type = self.infer_target_type(&assign.target)?;
-                if target_type != Type::Simple("unknown".to_string()) {
+                validator::validate_int_literal_range(&target_type, &assign.value)?;
+                if target_type != Type::Simple("unknown".to_string())
+                    && !validator::validate_fixed_bytes_literal(&target_type, &assign.value)?
+                {
                     type_checker::check_type_compatibility(&target_type, &value_type)?;
                 }
 
@@ -265,7 +619,7 @@ impl SemanticAnalyzer {
                 if let quorlin_parser::Expr::Ident(name) = &assign.target {
                     self.initialized_vars.insert(name.clone());
                     // If this has a type annotation, define it in the symbol table
-                    if let Some(target_type) = &assign.type_annotation {
+                    if let Some(target_type) = &annotation {
                         // This is a local variable declaration (let x: type = value)
                         let _ = self.symbols.define_variable(name, target_type);
                     } else if target_type != Type::Simple("unknown".to_string()) {
@@ -302,7 +656,7 @@ impl SemanticAnalyzer {
                         // Returning void - check function expects void
                         if ctx.return_type.is_some() {
                             return Err(SemanticError::TypeMismatch {
-                                expected: format!("{:?}", ctx.return_type),
+                                expected: ctx.return_type.as_ref().map_or_else(|| "void".to_string(), canonical_display),
                                 found: "void".to_string(),
                             });
                         }
@@ -322,28 +676,17 @@ impl SemanticAnalyzer {
                 Ok(())
             }
             Stmt::Require(req) => {
-                // Check condition is boolean-like
-                let cond_type = self.check_expression(&req.condition)?;
-                // Require should have boolean condition
-                let bool_type = Type::Simple("bool".to_string());
-                if cond_type != bool_type && cond_type != Type::Simple("unknown".to_string()) {
-                    return Err(SemanticError::TypeMismatch {
-                        expected: "bool".to_string(),
-                        found: format!("{:?}", cond_type),
-                    });
+                self.check_condition(&req.condition)?;
+                if let Some(message) = &req.message {
+                    self.check_expression(message)?;
+                }
+                if let Some(error) = &req.error {
+                    self.check_raise(error)?;
                 }
                 Ok(())
             }
             Stmt::If(if_stmt) => {
-                // Check condition type
-                let cond_type = self.check_expression(&if_stmt.condition)?;
-                let bool_type = Type::Simple("bool".to_string());
-                if cond_type != bool_type && cond_type != Type::Simple("unknown".to_string()) {
-                    return Err(SemanticError::TypeMismatch {
-                        expected: "bool".to_string(),
-                        found: format!("{:?}", cond_type),
-                    });
-                }
+                self.check_condition(&if_stmt.condition)?;
 
                 // Check branches
                 for stmt in &if_stmt.then_branch {
@@ -351,18 +694,16 @@ impl SemanticAnalyzer {
                 }
 
                 for (elif_cond, elif_body) in &if_stmt.elif_branches {
-                    let elif_type = self.check_expression(elif_cond)?;
-                    if elif_type != bool_type && elif_type != Type::Simple("unknown".to_string()) {
-                        return Err(SemanticError::TypeMismatch {
-                            expected: "bool".to_string(),
-                            found: format!("{:?}", elif_type),
-                        });
-                    }
+                    self.check_condition(elif_cond)?;
                     for stmt in elif_body {
                         self.check_statement(stmt)?;
                     }
                 }
 
+                if if_stmt.else_branch.is_none() && !if_stmt.elif_branches.is_empty() {
+                    self.check_enum_exhaustiveness(if_stmt);
+                }
+
                 if let Some(else_body) = &if_stmt.else_branch {
                     for stmt in else_body {
                         self.check_statement(stmt)?;
@@ -372,16 +713,9 @@ impl SemanticAnalyzer {
                 Ok(())
             }
             Stmt::While(while_stmt) => {
-                let cond_type = self.check_expression(&while_stmt.condition)?;
-                let bool_type = Type::Simple("bool".to_string());
-                if cond_type != bool_type && cond_type != Type::Simple("unknown".to_string()) {
-                    return Err(SemanticError::TypeMismatch {
-                        expected: "bool".to_string(),
-                        found: format!("{:?}", cond_type),
-                    });
-                }
+                self.check_condition(&while_stmt.condition)?;
 
-                for stmt in &while_stmt.body {
+                for stmt in while_stmt.body.iter().chain(while_stmt.else_branch.iter().flatten()) {
                     self.check_statement(stmt)?;
                 }
                 Ok(())
@@ -403,6 +737,11 @@ impl SemanticAnalyzer {
                 }
 
                 self.symbols.exit_scope();
+
+                // The else block runs after the loop, outside the loop variable's scope
+                for stmt in for_stmt.else_branch.iter().flatten() {
+                    self.check_statement(stmt)?;
+                }
                 Ok(())
             }
             Stmt::Pass => Ok(()),
@@ -411,20 +750,58 @@ impl SemanticAnalyzer {
                 self.check_expression(expr)?;
                 Ok(())
             }
-            Stmt::AugAssign(_aug) => {
-                // NOTE: AugAssign is currently unused - the parser desugars augmented assignments
-                // (like x += 1) into regular assignments (x = x + 1) as Stmt::Assign.
-                // This handler is kept for potential future use.
-                Ok(())
-            }
             Stmt::Revert(_msg) => Ok(()),
-            Stmt::Raise(raise) => {
-                // Check error arguments
-                for arg in &raise.args {
-                    self.check_expression(arg)?;
-                }
-                Ok(())
-            }
+            Stmt::Raise(raise) => self.check_raise(raise),
+        }
+    }
+
+    /// Check that a raised error is declared and given one argument per
+    /// parameter
+    fn check_raise(&mut self, raise: &quorlin_parser::RaiseStmt) -> SemanticResult<()> {
+        let Some(&arity) = self.error_arities.get(&raise.error) else {
+            return Err(SemanticError::ValidationError(format!("Undefined error: {}", raise.error)));
+        };
+        if raise.args.len() != arity {
+            return Err(SemanticError::ValidationError(format!(
+                "Error '{}' takes {} argument(s) but {} were given",
+                raise.error,
+                arity,
+                raise.args.len()
+            )));
+        }
+        for arg in &raise.args {
+            self.check_expression(arg)?;
+        }
+        Ok(())
+    }
+
+    /// Check that re-annotating state variable `name` in an assignment
+    /// (`self.x: T = ...`) repeats its declared type
+    fn check_state_annotation(&self, name: &str, annotated: &Type) -> SemanticResult<()> {
+        let Some(declared) = self.symbols.lookup_variable(name) else {
+            return Ok(());
+        };
+        if canonical_display(declared) != canonical_display(annotated) {
+            return Err(SemanticError::TypeMismatch {
+                expected: format!("{} (declared type of state variable '{}')", canonical_display(declared), name),
+                found: canonical_display(annotated),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that a branch or loop condition is a `bool`. Integers and other
+    /// values are never coerced; a condition of unknown type is only accepted
+    /// in lenient mode
+    fn check_condition(&mut self, condition: &quorlin_parser::Expr) -> SemanticResult<()> {
+        let cond_type = self.check_expression(condition)?;
+        match &cond_type {
+            Type::Simple(name) if name == "bool" => Ok(()),
+            Type::Simple(name) if name == "unknown" && self.lenient_conditions => Ok(()),
+            _ => Err(SemanticError::TypeMismatch {
+                expected: "bool".to_string(),
+                found: canonical_display(&cond_type),
+            }),
         }
     }
 
@@ -432,7 +809,10 @@ impl SemanticAnalyzer {
         use quorlin_parser::Expr;
 
         match expr {
-            Expr::IntLiteral(_) => Ok(Type::Simple("uint256".to_string())),
+            Expr::IntLiteral(digits) => {
+                validator::validate_int_literal(digits)?;
+                Ok(Type::Simple("uint256".to_string()))
+            }
             Expr::StringLiteral(_) => Ok(Type::Simple("str".to_string())),
             Expr::BoolLiteral(_) => Ok(Type::Simple("bool".to_string())),
             Expr::NoneLiteral => Ok(Type::Simple("None".to_string())),
@@ -449,8 +829,25 @@ impl SemanticAnalyzer {
                 }
             }
             Expr::BinOp(left, op, right) => {
-                let left_type = self.check_expression(left)?;
-                let right_type = self.check_expression(right)?;
+                let mut left_type = self.check_expression(left)?;
+                let mut right_type = self.check_expression(right)?;
+
+                // Integer literals take the type of the other numeric operand
+                let is_numeric = |ty: &Type| matches!(ty, Type::Simple(name) if type_checker::is_numeric_type(name));
+                if is_int_literal(left) && is_numeric(&right_type) {
+                    left_type = right_type.clone();
+                } else if is_int_literal(right) && is_numeric(&left_type) {
+                    right_type = left_type.clone();
+                }
+
+                if matches!(op, quorlin_parser::BinOp::In) {
+                    let warning = SemanticWarning::ZeroValueMembership {
+                        mapping: describe_target(right),
+                    };
+                    if !self.warnings.contains(&warning) {
+                        self.warnings.push(warning);
+                    }
+                }
 
                 // Use type inference for binary operations
                 type_checker::infer_binop_type(&left_type, &right_type, op)
@@ -465,7 +862,7 @@ impl SemanticAnalyzer {
                         if expr_type != Type::Simple("bool".to_string()) && expr_type != Type::Simple("unknown".to_string()) {
                             return Err(SemanticError::TypeMismatch {
                                 expected: "bool".to_string(),
-                                found: format!("{:?}", expr_type),
+                                found: canonical_display(&expr_type),
                             });
                         }
                         Ok(Type::Simple("bool".to_string()))
@@ -474,6 +871,15 @@ impl SemanticAnalyzer {
                         // Neg/Pos require numeric types
                         Ok(expr_type)
                     }
+                    UnaryOp::BitNot => {
+                        if !matches!(&expr_type, Type::Simple(name) if name == "unknown" || type_checker::is_numeric_type(name)) {
+                            return Err(SemanticError::TypeMismatch {
+                                expected: "an integer".to_string(),
+                                found: canonical_display(&expr_type),
+                            });
+                        }
+                        Ok(expr_type)
+                    }
                 }
             }
             Expr::Call(func, args) => {
@@ -482,15 +888,16 @@ impl SemanticAnalyzer {
                     self.check_expression(arg)?;
                 }
 
+                if args.iter().any(|arg| matches!(arg, Expr::KeywordArg(..))) {
+                    self.check_keyword_args(func, args)?;
+                }
+
                 // Type inference for common built-in and stdlib functions
                 if let Expr::Ident(func_name) = &**func {
                     match func_name.as_str() {
                         // Type constructors
                         "address" => return Ok(Type::Simple("address".to_string())),
-                        "uint8" | "uint16" | "uint32" | "uint64" | "uint128" | "uint256" => {
-                            return Ok(Type::Simple(func_name.clone()))
-                        }
-                        "int8" | "int16" | "int32" | "int64" | "int128" | "int256" => {
+                        name if type_checker::is_numeric_type(name) => {
                             return Ok(Type::Simple(func_name.clone()))
                         }
                         "bytes32" | "bytes" | "str" | "bool" => {
@@ -506,6 +913,17 @@ impl SemanticAnalyzer {
                         "require" | "assert" => return Ok(Type::Simple("void".to_string())),
                         "range" => return Ok(Type::List(Box::new(Type::Simple("uint256".to_string())))),
 
+                        // `IERC20(addr)` views an address through an interface
+                        name if self.symbols.is_interface_defined(name) => {
+                            if args.len() != 1 {
+                                return Err(SemanticError::ValidationError(format!(
+                                    "Interface cast {}(...) takes exactly one address",
+                                    name
+                                )));
+                            }
+                            return Ok(Type::Simple(name.to_string()));
+                        }
+
                         _ => {
                             // Look up function in symbol table if available
                             // For now, return unknown for undefined functions
@@ -577,10 +995,10 @@ impl SemanticAnalyzer {
                 if let Type::List(elem_type) = &base_type {
                     // Index should be numeric
                     if let Type::Simple(idx_ty) = &index_type {
-                        if !matches!(idx_ty.as_str(), "uint8" | "uint16" | "uint32" | "uint64" | "uint128" | "uint256") {
+                        if !type_checker::is_numeric_type(idx_ty) || idx_ty.starts_with("int") {
                             return Err(SemanticError::TypeMismatch {
                                 expected: "numeric type".to_string(),
-                                found: format!("{:?}", index_type),
+                                found: canonical_display(&index_type),
                             });
                         }
                     }
@@ -618,7 +1036,7 @@ impl SemanticAnalyzer {
                 if test_type != bool_type && test_type != Type::Simple("unknown".to_string()) {
                     return Err(SemanticError::TypeMismatch {
                         expected: "bool".to_string(),
-                        found: format!("{:?}", test_type),
+                        found: canonical_display(&test_type),
                     });
                 }
                 
@@ -630,7 +1048,344 @@ impl SemanticAnalyzer {
                 
                 Ok(body_type)
             }
+            Expr::KeywordArg(_, value) => self.check_expression(value),
+            Expr::ListComp { element, variable, iterable, condition } => {
+                let item_type = match self.check_expression(iterable)? {
+                    Type::List(inner) | Type::FixedArray(inner, _) => *inner,
+                    _ => Type::Simple("unknown".to_string()),
+                };
+
+                // The variable is only visible inside the comprehension
+                self.symbols.enter_scope();
+                self.symbols.define_variable(variable, &item_type)?;
+                if let Some(condition) = condition {
+                    self.check_condition(condition)?;
+                }
+                let element_type = self.check_expression(element)?;
+                self.symbols.exit_scope();
+
+                Ok(Type::List(Box::new(element_type)))
+            }
+            Expr::FString(parts) => {
+                for part in parts {
+                    self.check_expression(part)?;
+                }
+                Ok(Type::Simple("str".to_string()))
+            }
+        }
+    }
+
+    /// Check that every keyword argument of a call names a parameter of the
+    /// called contract function, and that no parameter is left unbound
+    fn check_keyword_args(&self, callee: &quorlin_parser::Expr, args: &[quorlin_parser::Expr]) -> SemanticResult<()> {
+        use quorlin_parser::Expr;
+
+        let name = match callee {
+            Expr::Ident(name) => Some(name),
+            Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => Some(name),
+            _ => None,
+        };
+        let Some((name, params)) = name.and_then(|name| Some((name, self.function_params.get(name)?))) else {
+            return Err(SemanticError::ValidationError(
+                "Keyword arguments are only supported in calls to contract functions".to_string(),
+            ));
+        };
+        quorlin_parser::bind_args(name, params, args)
+            .map(|_| ())
+            .map_err(SemanticError::ValidationError)
+    }
+
+    /// Warn about state variables that have no declared default and are never
+    /// assigned by `__init__`. Mappings are skipped since they are always
+    /// zero-initialized by design.
+    fn check_constructor_initializes_state(&mut self, contract: &quorlin_parser::ContractDecl) {
+        use quorlin_parser::ContractMember;
+
+        let constructor = contract.body.iter().find_map(|member| match member {
+            ContractMember::Function(func) if func.name == "__init__" => Some(func),
+            _ => None,
+        });
+        let Some(constructor) = constructor else {
+            return;
+        };
+
+        let mut assigned = std::collections::HashSet::new();
+        collect_assigned_state(&constructor.body, &mut assigned);
+
+        let missing: Vec<String> = contract
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ContractMember::StateVar(var)
+                    if var.initial_value.is_none()
+                        && !matches!(var.type_annotation, Type::Mapping(_, _))
+                        && !assigned.contains(&var.name) =>
+                {
+                    Some(var.name.clone())
+                }
+                _ => None,
+            })
+            .collect();
+
+        if !missing.is_empty() {
+            self.warnings.push(SemanticWarning::UninitializedState {
+                contract: contract.name.clone(),
+                variables: missing,
+            });
+        }
+    }
+
+    /// Suggest `@view` for functions that read state but never write it, emit
+    /// events, or call anything that might (other contracts or non-view
+    /// functions of this one)
+    fn check_missing_view(&mut self, contract: &quorlin_parser::ContractDecl) {
+        use quorlin_parser::visit::Visitor;
+        use quorlin_parser::ContractMember;
+
+        let state_variables: std::collections::HashSet<String> = contract
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ContractMember::StateVar(var) => Some(var.name.clone()),
+                _ => None,
+            })
+            .collect();
+        let read_only = |func: &quorlin_parser::Function| {
+            func.has_decorator("view") || func.has_decorator("pure")
+        };
+        let read_only_functions: std::collections::HashSet<&str> = contract
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ContractMember::Function(func) if read_only(func) => Some(func.name.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        for member in &contract.body {
+            let ContractMember::Function(func) = member else {
+                continue;
+            };
+            if func.name == "__init__"
+                || read_only(func)
+                || func.has_decorator("payable")
+                || func.has_decorator("constructor")
+                || security_analyzer::body_modifies_state(&func.body, &state_variables)
+            {
+                continue;
+            }
+
+            let mut finder = StateAccessFinder {
+                state_variables: &state_variables,
+                read_only_functions: &read_only_functions,
+                reads_state: false,
+                has_side_effects: false,
+            };
+            for stmt in &func.body {
+                finder.visit_stmt(stmt);
+            }
+            if finder.reads_state && !finder.has_side_effects {
+                self.warnings.push(SemanticWarning::MissingView {
+                    function: func.name.clone(),
+                });
+            }
+        }
+    }
+
+    /// Resolve constant-sized array lengths (`T[SIZE]`) to concrete `FixedArray` sizes
+    pub fn resolve_type(&self, ty: &Type) -> SemanticResult<Type> {
+        match ty {
+            Type::ConstArray(inner, name) => {
+                let size = self
+                    .constants
+                    .get(name)
+                    .and_then(|value| self.eval_constant(value, 0))
+                    .ok_or_else(|| {
+                        SemanticError::ValidationError(format!(
+                            "Array length '{}' is not a compile-time constant integer",
+                            name
+                        ))
+                    })?;
+                Ok(Type::FixedArray(Box::new(self.resolve_type(inner)?), size))
+            }
+            Type::FixedArray(inner, size) => Ok(Type::FixedArray(Box::new(self.resolve_type(inner)?), *size)),
+            Type::List(inner) => Ok(Type::List(Box::new(self.resolve_type(inner)?))),
+            Type::Optional(inner) => Ok(Type::Optional(Box::new(self.resolve_type(inner)?))),
+            Type::Mapping(key, value) => Ok(Type::Mapping(
+                Box::new(self.resolve_type(key)?),
+                Box::new(self.resolve_type(value)?),
+            )),
+            Type::Tuple(types) => Ok(Type::Tuple(
+                types.iter().map(|t| self.resolve_type(t)).collect::<SemanticResult<_>>()?,
+            )),
+            Type::Simple(_) => Ok(ty.clone()),
+        }
+    }
+
+    /// Rewrite every type annotation in `module` with constant array lengths
+    /// resolved. Runs after the definition pass so constants have been collected.
+    fn resolve_types(&self, module: &mut Module) -> SemanticResult<()> {
+        use quorlin_parser::{ContractMember, Item};
+
+        for item in &mut module.items {
+            match item {
+                Item::Contract(contract) => {
+                    for member in &mut contract.body {
+                        match member {
+                            ContractMember::StateVar(var) => {
+                                var.type_annotation = self.resolve_type(&var.type_annotation)?;
+                            }
+                            ContractMember::Constant(constant) => {
+                                constant.type_annotation = self.resolve_type(&constant.type_annotation)?;
+                            }
+                            ContractMember::Function(func) => {
+                                for param in &mut func.params {
+                                    param.type_annotation = self.resolve_type(&param.type_annotation)?;
+                                }
+                                if let Some(ret) = &mut func.return_type {
+                                    *ret = self.resolve_type(ret)?;
+                                }
+                                self.resolve_body_types(&mut func.body)?;
+                            }
+                        }
+                    }
+                }
+                Item::Struct(s) => {
+                    for field in &mut s.fields {
+                        field.type_annotation = self.resolve_type(&field.type_annotation)?;
+                    }
+                }
+                Item::Event(event) => {
+                    for param in &mut event.params {
+                        param.type_annotation = self.resolve_type(&param.type_annotation)?;
+                    }
+                }
+                Item::Error(error) => {
+                    for param in &mut error.params {
+                        param.type_annotation = self.resolve_type(&param.type_annotation)?;
+                    }
+                }
+                Item::Interface(interface) => {
+                    for func in &mut interface.functions {
+                        for param in &mut func.params {
+                            param.type_annotation = self.resolve_type(&param.type_annotation)?;
+                        }
+                        if let Some(ret) = &mut func.return_type {
+                            *ret = self.resolve_type(ret)?;
+                        }
+                    }
+                }
+                Item::Const(constant) => {
+                    constant.type_annotation = self.resolve_type(&constant.type_annotation)?;
+                }
+                Item::Import(_) | Item::Enum(_) => {}
+            }
         }
+        Ok(())
+    }
+
+    fn resolve_body_types(&self, body: &mut [quorlin_parser::Stmt]) -> SemanticResult<()> {
+        use quorlin_parser::Stmt;
+
+        for stmt in body {
+            match stmt {
+                Stmt::Assign(assign) => {
+                    if let Some(ty) = &mut assign.type_annotation {
+                        *ty = self.resolve_type(ty)?;
+                    }
+                }
+                Stmt::If(if_stmt) => {
+                    self.resolve_body_types(&mut if_stmt.then_branch)?;
+                    for (_, elif_body) in &mut if_stmt.elif_branches {
+                        self.resolve_body_types(elif_body)?;
+                    }
+                    if let Some(else_body) = &mut if_stmt.else_branch {
+                        self.resolve_body_types(else_body)?;
+                    }
+                }
+                Stmt::For(for_stmt) => {
+                    self.resolve_body_types(&mut for_stmt.body)?;
+                    if let Some(else_body) = &mut for_stmt.else_branch {
+                        self.resolve_body_types(else_body)?;
+                    }
+                }
+                Stmt::While(while_stmt) => {
+                    self.resolve_body_types(&mut while_stmt.body)?;
+                    if let Some(else_body) = &mut while_stmt.else_branch {
+                        self.resolve_body_types(else_body)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Fold a constant integer expression, following references to other constants
+    fn eval_constant(&self, expr: &quorlin_parser::Expr, depth: usize) -> Option<usize> {
+        use quorlin_parser::{BinOp, Expr};
+
+        // Guards against constants that reference each other cyclically
+        if depth > self.constants.len() {
+            return None;
+        }
+
+        match expr {
+            Expr::IntLiteral(n) => n.parse().ok(),
+            Expr::Ident(name) => self.eval_constant(self.constants.get(name)?, depth + 1),
+            Expr::BinOp(left, op, right) => {
+                let left = self.eval_constant(left, depth)?;
+                let right = self.eval_constant(right, depth)?;
+                match op {
+                    BinOp::Add => left.checked_add(right),
+                    BinOp::Sub => left.checked_sub(right),
+                    BinOp::Mul => left.checked_mul(right),
+                    BinOp::Div | BinOp::FloorDiv => left.checked_div(right),
+                    BinOp::Mod => left.checked_rem(right),
+                    BinOp::Pow => left.checked_pow(u32::try_from(right).ok()?),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// `a, b = value`: the value must be a tuple with one element per target,
+    /// and each element must fit its target
+    fn check_unpacking(&mut self, targets: &[quorlin_parser::Expr], value_type: &Type) -> SemanticResult<()> {
+        use quorlin_parser::Expr;
+
+        let unknown = Type::Simple("unknown".to_string());
+        let types = match value_type {
+            Type::Tuple(types) if types.len() == targets.len() => types.clone(),
+            ty if *ty == unknown => vec![unknown.clone(); targets.len()],
+            _ => {
+                return Err(SemanticError::TypeMismatch {
+                    expected: format!("a tuple of {} values", targets.len()),
+                    found: canonical_display(value_type),
+                })
+            }
+        };
+
+        for (target, ty) in targets.iter().zip(&types) {
+            let target_type = self.infer_target_type(target)?;
+            if target_type != unknown && *ty != unknown {
+                type_checker::check_type_compatibility(&target_type, ty)?;
+            }
+            match target {
+                Expr::Ident(name) => {
+                    self.initialized_vars.insert(name.clone());
+                    if target_type == unknown {
+                        let _ = self.symbols.define_variable(name, ty);
+                    }
+                }
+                Expr::Attribute(_, name) => {
+                    self.initialized_vars.insert(name.clone());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
     }
 
     /// Infer the type of an assignment target
@@ -677,6 +1432,163 @@ impl SemanticAnalyzer {
     }
 }
 
+/// Where a contract member is declared, for members that record it
+fn member_span(member: &quorlin_parser::ContractMember) -> Option<Span> {
+    use quorlin_parser::ContractMember;
+
+    match member {
+        ContractMember::Function(func) => Some(func.span),
+        ContractMember::StateVar(var) => Some(var.span),
+        ContractMember::Constant(_) => None,
+    }
+}
+
+/// Whether `expr` is an integer literal, possibly negated
+fn is_int_literal(expr: &quorlin_parser::Expr) -> bool {
+    use quorlin_parser::{Expr, UnaryOp};
+
+    match expr {
+        Expr::IntLiteral(_) => true,
+        Expr::UnaryOp(UnaryOp::Neg | UnaryOp::Pos, operand) => is_int_literal(operand),
+        _ => false,
+    }
+}
+
+/// Render a storage location such as `self.allowances[..]` for diagnostics
+fn describe_target(expr: &quorlin_parser::Expr) -> String {
+    use quorlin_parser::Expr;
+
+    match expr {
+        Expr::Ident(name) => name.clone(),
+        Expr::Attribute(base, attr) => format!("{}.{}", describe_target(base), attr),
+        Expr::Index(base, _) => format!("{}[..]", describe_target(base)),
+        _ => "expression".to_string(),
+    }
+}
+
+/// The `(subject, enum, variant)` comparisons of a condition made only of
+/// `subject == Enum.Variant` tests joined by `or`, or `None` for anything else
+fn enum_comparisons<'a>(
+    condition: &'a quorlin_parser::Expr,
+    enums: &HashMap<String, Vec<String>>,
+) -> Option<Vec<(&'a quorlin_parser::Expr, &'a str, &'a str)>> {
+    use quorlin_parser::{BinOp, Expr};
+
+    let as_variant = |expr: &'a Expr| match expr {
+        Expr::Attribute(base, variant) => match &**base {
+            Expr::Ident(name) if enums.contains_key(name) => Some((name.as_str(), variant.as_str())),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match condition {
+        Expr::BinOp(left, BinOp::Or, right) => {
+            let mut comparisons = enum_comparisons(left, enums)?;
+            comparisons.extend(enum_comparisons(right, enums)?);
+            Some(comparisons)
+        }
+        Expr::BinOp(left, BinOp::Eq, right) => match (as_variant(left), as_variant(right)) {
+            (None, Some((enum_name, variant))) => Some(vec![(&**left, enum_name, variant)]),
+            (Some((enum_name, variant)), None) => Some(vec![(&**right, enum_name, variant)]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Records whether a function body reads state and whether it does anything
+/// a `@view` function may not: emitting events or calling other contracts or
+/// non-view functions of this one
+struct StateAccessFinder<'a> {
+    state_variables: &'a std::collections::HashSet<String>,
+    read_only_functions: &'a std::collections::HashSet<&'a str>,
+    reads_state: bool,
+    has_side_effects: bool,
+}
+
+impl<'ast> quorlin_parser::visit::Visitor<'ast> for StateAccessFinder<'_> {
+    fn visit_stmt(&mut self, stmt: &'ast quorlin_parser::Stmt) {
+        if matches!(stmt, quorlin_parser::Stmt::Emit(_)) {
+            self.has_side_effects = true;
+        }
+        quorlin_parser::visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast quorlin_parser::Expr) {
+        use quorlin_parser::Expr;
+
+        match expr {
+            Expr::Attribute(base, attr) if matches!(&**base, Expr::Ident(name) if name == "self") => {
+                self.reads_state |= self.state_variables.contains(attr);
+            }
+            Expr::Call(callee, _) => {
+                if let Expr::Attribute(base, method) = &**callee {
+                    let internal = matches!(&**base, Expr::Ident(name) if name == "self");
+                    if !internal || !self.read_only_functions.contains(method.as_str()) {
+                        self.has_side_effects = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+        quorlin_parser::visit::walk_expr(self, expr);
+    }
+}
+
+/// Collect the names of state variables assigned anywhere in `body`
+fn collect_assigned_state(body: &[quorlin_parser::Stmt], assigned: &mut std::collections::HashSet<String>) {
+    use quorlin_parser::Stmt;
+
+    for stmt in body {
+        match stmt {
+            Stmt::Assign(assign) => collect_assigned_target(&assign.target, assigned),
+            Stmt::If(if_stmt) => {
+                collect_assigned_state(&if_stmt.then_branch, assigned);
+                for (_, elif_body) in &if_stmt.elif_branches {
+                    collect_assigned_state(elif_body, assigned);
+                }
+                if let Some(else_body) = &if_stmt.else_branch {
+                    collect_assigned_state(else_body, assigned);
+                }
+            }
+            Stmt::While(while_stmt) => {
+                collect_assigned_state(&while_stmt.body, assigned);
+                if let Some(else_body) = &while_stmt.else_branch {
+                    collect_assigned_state(else_body, assigned);
+                }
+            }
+            Stmt::For(for_stmt) => {
+                collect_assigned_state(&for_stmt.body, assigned);
+                if let Some(else_body) = &for_stmt.else_branch {
+                    collect_assigned_state(else_body, assigned);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_assigned_target(target: &quorlin_parser::Expr, assigned: &mut std::collections::HashSet<String>) {
+    use quorlin_parser::Expr;
+
+    match target {
+        Expr::Attribute(base, attr) if matches!(&**base, Expr::Ident(name) if name == "self") => {
+            assigned.insert(attr.clone());
+        }
+        Expr::Ident(name) => {
+            assigned.insert(name.clone());
+        }
+        Expr::Index(base, _) | Expr::Attribute(base, _) => collect_assigned_target(base, assigned),
+        Expr::Tuple(targets) => {
+            for target in targets {
+                collect_assigned_target(target, assigned);
+            }
+        }
+        _ => {}
+    }
+}
+
 impl Default for SemanticAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -687,11 +1599,718 @@ impl Default for SemanticAnalyzer {
 mod tests {
     use super::*;
 
+    use quorlin_lexer::Lexer;
+
+    fn analyze_source(source: &str) -> (SemanticResult<()>, SemanticAnalyzer) {
+        let tokens = Lexer::new(source).tokenize().expect("lexing failed");
+        let mut module = quorlin_parser::parse_module(tokens).expect("parsing failed");
+        let mut analyzer = SemanticAnalyzer::new();
+        let result = analyzer.analyze(&mut module);
+        (result, analyzer)
+    }
+
     #[test]
     fn test_semantic_analyzer_creation() {
         let _analyzer = SemanticAnalyzer::new();
     }
 
+    #[test]
+    fn test_constructor_missing_state_init_warns() {
+        let source = r#"
+contract Vault:
+    _owner: address
+    _total: uint256
+    _version: uint256 = 1
+    _balances: mapping[address, uint256]
+
+    @constructor
+    fn __init__(total: uint256):
+        self._total = total
+"#;
+        let (result, analyzer) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result.err());
+        assert_eq!(
+            analyzer.warnings(),
+            &[SemanticWarning::UninitializedState {
+                contract: "Vault".to_string(),
+                variables: vec!["_owner".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_read_only_function_without_view_warns() {
+        let source = r#"
+contract Counter:
+    _count: uint256 = 0
+
+    @external
+    fn get_count() -> uint256:
+        return self._count
+
+    @external
+    @view
+    fn count() -> uint256:
+        return self._count
+"#;
+        let (result, analyzer) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result.err());
+        assert_eq!(
+            analyzer.warnings(),
+            &[SemanticWarning::MissingView {
+                function: "get_count".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_event_rejected() {
+        let source = r#"
+event Transfer(from_addr: address, to_addr: address, value: uint256)
+event Transfer(sender: address, amount: uint256)
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(matches!(result, Err(SemanticError::DuplicateDefinition(name)) if name == "Transfer"));
+    }
+
+    #[test]
+    fn test_duplicate_error_rejected() {
+        let source = r#"
+error Bad(code: uint256)
+error Bad()
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(matches!(result, Err(SemanticError::DuplicateDefinition(name)) if name == "Bad"));
+    }
+
+    #[test]
+    fn test_view_calling_mutating_helper_rejected() {
+        let source = r#"
+contract Counter:
+    _count: uint256
+
+    fn _bump() -> uint256:
+        self._count = self._count + 1
+        return self._count
+
+    fn _peek() -> uint256:
+        return self._bump()
+
+    @view
+    fn current() -> uint256:
+        return self._peek()
+"#;
+        let (result, _) = analyze_source(source);
+        match result {
+            Err(SemanticError::ValidationError(msg)) => {
+                assert!(msg.contains("'current'") && msg.contains("'_bump'"), "{}", msg)
+            }
+            other => panic!("expected view violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_mapping_membership_warns_about_zero_values() {
+        let source = r#"
+contract Registry:
+    _allowed: mapping[address, bool]
+
+    @view
+    fn is_known(who: address) -> bool:
+        return who in self._allowed
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let mut module = quorlin_parser::parse_module(tokens).unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.set_security_analysis(false);
+        analyzer.analyze(&mut module).unwrap();
+        assert_eq!(
+            analyzer.warnings(),
+            &[SemanticWarning::ZeroValueMembership { mapping: "self._allowed".to_string() }]
+        );
+
+        let tokens = Lexer::new(&source.replace("who in", "1 in")).tokenize().unwrap();
+        let mut module = quorlin_parser::parse_module(tokens).unwrap();
+        assert!(SemanticAnalyzer::new().analyze(&mut module).is_err());
+    }
+
+    #[test]
+    fn test_signed_unsigned_comparison_is_rejected() {
+        let source = r#"
+contract Ledger:
+    @view
+    fn below(a: int256, b: uint256) -> bool:
+        return a < b
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let mut module = quorlin_parser::parse_module(tokens).unwrap();
+        match SemanticAnalyzer::new().analyze(&mut module) {
+            Err(SemanticError::TypeMismatch { found, .. }) => assert_eq!(found, "int256 and uint256"),
+            other => panic!("expected signedness mismatch, got {:?}", other),
+        }
+
+        // Literals adapt to the signed operand
+        let tokens = Lexer::new(&source.replace("a < b", "a < -1")).tokenize().unwrap();
+        let mut module = quorlin_parser::parse_module(tokens).unwrap();
+        SemanticAnalyzer::new().analyze(&mut module).unwrap();
+    }
+
+    #[test]
+    fn test_security_pass_can_be_disabled() {
+        let source = r#"
+contract Bank:
+    _balances: mapping[address, uint256]
+
+    @external
+    fn withdraw():
+        self._balances[msg.sender] = 0
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let mut module = quorlin_parser::parse_module(tokens).unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.analyze(&mut module).unwrap();
+        assert!(!analyzer.security_warnings().is_empty());
+
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.set_security_analysis(false);
+        analyzer.analyze(&mut module).unwrap();
+        assert!(analyzer.security_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_deny_security_fails_on_reentrancy() {
+        let source = r#"
+contract Bank:
+    _balances: mapping[address, uint256]
+
+    @external
+    fn withdraw(vault: address):
+        vault.send(self._balances[msg.sender])
+        self._balances[msg.sender] = 0
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let mut module = quorlin_parser::parse_module(tokens).unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.analyze(&mut module).unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.set_deny_security(Some(security_analyzer::Severity::High));
+        match analyzer.analyze(&mut module) {
+            Err(SemanticError::ValidationError(msg)) => assert!(msg.contains("REENTRANCY"), "{}", msg),
+            other => panic!("expected denied security warning, got {:?}", other),
+        }
+
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.set_deny_security(Some(security_analyzer::Severity::Critical));
+        analyzer.analyze(&mut module).unwrap();
+    }
+
+    #[test]
+    fn test_constructor_initializing_all_state_is_clean() {
+        let source = r#"
+contract Vault:
+    _owner: address
+
+    @constructor
+    fn __init__():
+        if True:
+            self._owner = msg.sender
+"#;
+        let (result, analyzer) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result.err());
+        assert!(analyzer.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_external_init_is_rejected() {
+        let source = r#"
+contract Vault:
+    _owner: address
+
+    @external
+    fn __init__():
+        self._owner = msg.sender
+"#;
+        let (result, _) = analyze_source(source);
+        match result {
+            Err(SemanticError::ValidationError(msg)) => assert!(msg.contains("@external"), "{}", msg),
+            other => panic!("expected __init__ decorator error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_non_exhaustive_enum_chain_warns() {
+        let source = r#"
+enum Color:
+    Red
+    Green
+    Blue
+
+contract Palette:
+    _last: uint256
+
+    @constructor
+    fn __init__():
+        self._last = 0
+
+    @external
+    fn paint(color: Color):
+        if color == Color.Red:
+            self._last = 1
+        elif color == Color.Green:
+            self._last = 2
+"#;
+        let (result, analyzer) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result.err());
+        assert_eq!(
+            analyzer.warnings(),
+            &[SemanticWarning::NonExhaustiveEnum {
+                subject: "color".to_string(),
+                enum_name: "Color".to_string(),
+                missing: vec!["Blue".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_hex_literal_must_fit_fixed_bytes() {
+        let contract = |hex: &str| {
+            format!(
+                "contract Hasher:\n    _root: bytes32\n\n    @constructor\n    fn __init__():\n        self._root = 0x{}\n",
+                hex
+            )
+        };
+
+        let (result, _) = analyze_source(&contract(&"ab".repeat(32)));
+        assert!(result.is_ok(), "{:?}", result.err());
+
+        let (result, _) = analyze_source(&contract(&"ab".repeat(33)));
+        match result {
+            Err(SemanticError::ValidationError(msg)) => {
+                assert_eq!(msg, "Literal of 33 bytes does not fit in bytes32")
+            }
+            other => panic!("expected literal length error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_independent_errors_are_all_reported() {
+        let source = r#"
+contract Counter:
+    _count: uint256
+    _enabled: bool
+
+    @constructor
+    fn __init__():
+        self._count = True
+        self._enabled = 5
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let mut module = quorlin_parser::parse_module(tokens).unwrap();
+        let errors = SemanticAnalyzer::new().analyze_all(&mut module).unwrap_err();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors.iter().all(|e| matches!(e, SemanticError::TypeMismatch { .. })), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_state_variable_reannotation_must_match_declaration() {
+        let source = r#"
+contract Bank:
+    balance: uint256
+
+    @external
+    fn reset():
+        self.balance: bool = False
+"#;
+        let (result, _) = analyze_source(source);
+        match result {
+            Err(SemanticError::TypeMismatch { expected, found }) => {
+                assert_eq!(expected, "uint256 (declared type of state variable 'balance')");
+                assert_eq!(found, "bool");
+            }
+            other => panic!("expected a type mismatch, got {:?}", other),
+        }
+
+        let matching = r#"
+contract Bank:
+    balance: uint256
+
+    @external
+    fn reset():
+        self.balance: uint256 = 0
+"#;
+        let (result, _) = analyze_source(matching);
+        assert!(result.is_ok(), "{:?}", result.err());
+    }
+
+    #[test]
+    fn test_validate_constraint_must_use_parameters() {
+        let source = r#"
+contract Vault:
+    total: uint256
+
+    @external
+    @validate("amount > total")
+    fn deposit(amount: uint256):
+        self.total = self.total + amount
+"#;
+        let (result, _) = analyze_source(source);
+        match result {
+            Err(SemanticError::ValidationError(message)) => {
+                assert!(message.contains("refers to 'total', which is not a parameter"), "{}", message);
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_literal_wider_than_256_bits_is_rejected() {
+        let source = r#"
+contract Big:
+    total: uint256
+
+    @external
+    fn set():
+        self.total = 115792089237316195423570985008687907853269984665640564039457584007913129639936
+"#;
+        let (result, _) = analyze_source(source);
+        match result {
+            Err(SemanticError::ValidationError(message)) => {
+                assert!(message.contains("does not fit in 256 bits"), "{}", message);
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_integer_condition_is_rejected() {
+        let source = r#"
+contract Counter:
+    count: uint256
+
+    @external
+    fn reset():
+        if self.count:
+            self.count = 0
+"#;
+        let (result, _) = analyze_source(source);
+        match result {
+            Err(SemanticError::TypeMismatch { expected, found }) => {
+                assert_eq!(expected, "bool");
+                assert!(found.contains("uint256"), "{}", found);
+            }
+            other => panic!("expected a type mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_logical_operand_must_be_bool() {
+        let source = r#"
+contract Gate:
+    @external
+    fn check(x: bool) -> bool:
+        return x and 5
+"#;
+        let (result, _) = analyze_source(source);
+        match result {
+            Err(SemanticError::TypeMismatch { expected, found }) => {
+                assert_eq!(expected, "bool");
+                assert_eq!(found, "uint256");
+            }
+            other => panic!("expected a type mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_override_rules() {
+        let base = r#"
+contract Base:
+    @external
+    fn fee() -> uint256:
+        return 1
+"#;
+        let missing = format!(
+            "{}\ncontract Derived(Base):\n    @external\n    fn fee() -> uint256:\n        return 2\n",
+            base
+        );
+        let err = analyze_source(&missing).0.unwrap_err().to_string();
+        assert!(err.contains("must be marked @override"), "{}", err);
+
+        let spurious = format!(
+            "{}\ncontract Derived(Base):\n    @external\n    @override\n    fn rate() -> uint256:\n        return 2\n",
+            base
+        );
+        let err = analyze_source(&spurious).0.unwrap_err().to_string();
+        assert!(err.contains("no base contract declares it"), "{}", err);
+
+        let marked = format!(
+            "{}\ncontract Derived(Base):\n    @external\n    @override\n    fn fee() -> uint256:\n        return 2\n",
+            base
+        );
+        assert!(analyze_source(&marked).0.is_ok());
+    }
+
+    #[test]
+    fn test_recursive_inline_function_is_rejected() {
+        let source = r#"
+contract Math:
+    @inline
+    fn _fact(n: uint256) -> uint256:
+        return 1 if n == 0 else n * self._fact(n - 1)
+"#;
+        let (result, _) = analyze_source(source);
+        match result {
+            Err(SemanticError::ValidationError(msg)) => {
+                assert_eq!(msg, "@inline function '_fact' is recursive and cannot be inlined")
+            }
+            other => panic!("expected recursive @inline error, got {:?}", other),
+        }
+
+        // Mutual recursion is reported against the first function by name
+        let mutual = r#"
+contract Loop:
+    @inline
+    fn _pong(n: uint256) -> uint256:
+        return self._ping(n) + 1
+
+    @inline
+    fn _ping(n: uint256) -> uint256:
+        return self._pong(n) + 1
+"#;
+        let err = analyze_source(mutual).0.unwrap_err().to_string();
+        assert!(err.contains("'_ping' is recursive"), "{}", err);
+    }
+
     // Add comprehensive tests for type checking
     // This is where property-based testing would be valuable
+
+    #[test]
+    fn test_constant_array_length_is_resolved() {
+        let source = r#"
+contract Batcher:
+    const BATCH_SIZE: uint256 = 8
+    _pending: uint256[BATCH_SIZE]
+"#;
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let mut module = quorlin_parser::parse_module(tokens).unwrap();
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.analyze(&mut module).unwrap();
+
+        let quorlin_parser::Item::Contract(contract) = &module.items[0] else {
+            panic!("expected contract");
+        };
+        let quorlin_parser::ContractMember::StateVar(var) = &contract.body[1] else {
+            panic!("expected state variable");
+        };
+        assert_eq!(
+            var.type_annotation,
+            Type::FixedArray(Box::new(Type::Simple("uint256".to_string())), 8)
+        );
+    }
+
+    #[test]
+    fn test_non_constant_array_length_rejected() {
+        let source = r#"
+contract Batcher:
+    _size: uint256
+    _pending: uint256[_size]
+"#;
+        let (result, _) = analyze_source(source);
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("not a compile-time constant"), "{}", err);
+    }
+
+    #[test]
+    fn test_tuple_unpacking_checks_arity() {
+        let source = r#"
+contract Splitter:
+    fn split(v: uint256) -> (uint256, bool):
+        return (v, True)
+
+    fn run(v: uint256) -> uint256:
+        amount, ok = self.split(v)
+        return amount
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+
+        let (result, _) = analyze_source(&source.replace("amount, ok =", "amount, ok, extra ="));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("a tuple of 3 values"), "{}", err);
+    }
+
+    #[test]
+    fn test_enum_is_a_state_variable_type() {
+        let source = r#"
+enum Status:
+    Pending
+    Active
+    Closed
+
+contract Orders:
+    _status: Status
+    _by_id: mapping[uint256, Status]
+
+    @external
+    fn close(id: uint256):
+        self._status = Status.Closed
+        self._by_id[id] = Status.Active
+"#;
+        let (result, analyzer) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(analyzer.enums["Status"], vec!["Pending", "Active", "Closed"]);
+    }
+
+    #[test]
+    fn test_raise_must_match_a_declared_error() {
+        let source = r#"
+error Insufficient(available: uint256, needed: uint256)
+
+contract Vault:
+    _balance: uint256
+
+    @external
+    fn withdraw(amount: uint256):
+        if amount > self._balance:
+            raise Insufficient(self._balance, amount)
+        self._balance = self._balance - amount
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+
+        let (result, _) = analyze_source(&source.replace("raise Insufficient(", "raise Missing("));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Undefined error: Missing"), "{}", err);
+
+        let (result, _) = analyze_source(&source.replace("(self._balance, amount)", "(amount)"));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Error 'Insufficient' takes 2 argument(s) but 1 were given"), "{}", err);
+    }
+
+    #[test]
+    fn test_constants_cannot_be_reassigned() {
+        let source = r#"
+contract Token:
+    const MAX_SUPPLY: uint256 = 1000000
+    _supply: uint256
+
+    @external
+    fn mint(amount: uint256):
+        require(self._supply + amount <= MAX_SUPPLY)
+        self._supply = self._supply + amount
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+
+        for assignment in ["MAX_SUPPLY = amount", "self.MAX_SUPPLY = amount", "MAX_SUPPLY += amount"] {
+            let (result, _) = analyze_source(&source.replace("self._supply = self._supply + amount", assignment));
+            let err = result.unwrap_err();
+            assert!(matches!(err, SemanticError::InvalidOperation(_)), "{}", err);
+            assert!(err.to_string().contains("Cannot assign to constant 'MAX_SUPPLY'"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_module_constants_are_visible_to_contracts() {
+        let source = r#"
+const MAX_SUPPLY: uint256 = 1000000
+
+contract Token:
+    _supply: uint256
+
+    @external
+    fn mint(amount: uint256):
+        require(self._supply + amount <= MAX_SUPPLY)
+        self._supply = self._supply + amount
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+
+        let (result, _) = analyze_source(&source.replace("self._supply = self._supply + amount", "MAX_SUPPLY = amount"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("Cannot assign to constant 'MAX_SUPPLY'"), "{}", err);
+    }
+
+    #[test]
+    fn test_interface_names_a_type() {
+        let source = r#"
+interface IERC20:
+    fn transfer(to: address, amount: uint256) -> bool
+
+contract Payer:
+    @external
+    fn pay(token: address, to: address, amount: uint256):
+        erc20: IERC20 = IERC20(token)
+        ok: bool = erc20.transfer(to, amount)
+"#;
+        let (result, analyzer) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+        assert!(analyzer.symbols.is_interface_defined("IERC20"));
+
+        let (result, _) = analyze_source(&source.replace("IERC20(token)", "IERC20(token, to)"));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Interface cast IERC20(...) takes exactly one address"), "{}", err);
+    }
+
+    #[test]
+    fn test_keyword_arguments_match_parameter_names() {
+        let source = r#"
+contract Vault:
+    fn scaled(amount: uint256, factor: uint256 = 2) -> uint256:
+        return amount * factor
+
+    fn total(amount: uint256) -> uint256:
+        return self.scaled(factor=3, amount=amount)
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+
+        let (result, _) = analyze_source(&source.replace("factor=3", "fator=3"));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Function 'scaled' has no parameter named 'fator'"), "{}", err);
+
+        let (result, _) = analyze_source(&source.replace("factor=3, amount=amount", "factor=3"));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Missing argument 'amount' in call to 'scaled'"), "{}", err);
+    }
+
+    #[test]
+    fn test_bitwise_operands_must_be_integers() {
+        let source = r#"
+contract Flags:
+    @view
+    fn masked(flags: uint256, on: bool) -> uint256:
+        return flags & 4
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+
+        let (result, _) = analyze_source(&source.replace("flags & 4", "flags | on"));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("expected numeric types, found uint256 and bool"), "{}", err);
+
+        let (result, _) = analyze_source(&source.replace("flags & 4", "~on"));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("expected an integer, found bool"), "{}", err);
+    }
+
+    #[test]
+    fn test_list_comprehension_is_a_list() {
+        let source = r#"
+contract Evens:
+    @view
+    fn evens(n: uint256) -> list[uint256]:
+        doubled: list[uint256] = [x * 2 for x in range(n) if x > 0]
+        return doubled
+"#;
+        let (result, _) = analyze_source(source);
+        assert!(result.is_ok(), "{:?}", result);
+
+        let (result, _) = analyze_source(&source.replace("if x > 0", "if x"));
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("expected bool, found uint256"), "{}", err);
+    }
 }
@@ -11,6 +11,8 @@
 use quorlin_parser::{ContractMember, Expr, Function, Item, Module, Stmt};
 use std::collections::HashSet;
 
+pub use quorlin_common::severity::Severity;
+
 /// Security warnings
 #[derive(Debug, Clone, PartialEq)]
 pub enum SecurityWarning {
@@ -39,6 +41,19 @@ pub enum SecurityWarning {
     },
 }
 
+impl SecurityWarning {
+    /// Severity of the finding. Reentrancy patterns are high; the access
+    /// control heuristics are noisier and rated medium.
+    pub fn severity(&self) -> Severity {
+        match self {
+            SecurityWarning::ReentrancyRisk { .. }
+            | SecurityWarning::StateChangeAfterExternalCall { .. } => Severity::High,
+            SecurityWarning::MissingAccessControl { .. }
+            | SecurityWarning::UnprotectedStateModification { .. } => Severity::Medium,
+        }
+    }
+}
+
 impl std::fmt::Display for SecurityWarning {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -64,6 +79,12 @@ pub struct SecurityAnalyzer {
     state_variables: HashSet<String>,
 }
 
+impl Default for SecurityAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SecurityAnalyzer {
     /// Create a new security analyzer
     pub fn new() -> Self {
@@ -118,13 +139,13 @@ impl SecurityAnalyzer {
     /// Check if function has appropriate access control
     fn check_access_control(&mut self, func: &Function) {
         // Skip view functions (they don't modify state)
-        let is_view = func.decorators.iter().any(|d| d == "view");
+        let is_view = func.has_decorator("view");
         if is_view {
             return;
         }
 
         // Skip constructor (access control doesn't apply)
-        let is_constructor = func.decorators.iter().any(|d| d == "constructor");
+        let is_constructor = func.has_decorator("constructor");
         if is_constructor {
             return;
         }
@@ -151,58 +172,12 @@ impl SecurityAnalyzer {
 
     /// Check if function modifies state variables
     fn function_modifies_state(&self, body: &[Stmt]) -> bool {
-        for stmt in body {
-            if self.statement_modifies_state(stmt) {
-                return true;
-            }
-        }
-        false
+        body_modifies_state(body, &self.state_variables)
     }
 
     /// Check if statement modifies state
     fn statement_modifies_state(&self, stmt: &Stmt) -> bool {
-        match stmt {
-            Stmt::Assign(assign) => {
-                // Check if target is a state variable (self.variable)
-                if let Expr::Attribute(base, attr) = &assign.target {
-                    if let Expr::Ident(base_name) = &**base {
-                        if base_name == "self" && self.state_variables.contains(attr) {
-                            return true;
-                        }
-                    }
-                }
-                // Check if target is an indexed state variable (self.mapping[key])
-                if let Expr::Index(base, _) = &assign.target {
-                    if self.is_state_variable_access(base) {
-                        return true;
-                    }
-                }
-                false
-            }
-            Stmt::If(if_stmt) => {
-                self.function_modifies_state(&if_stmt.then_branch)
-                    || if_stmt.elif_branches.iter().any(|(_, body)| self.function_modifies_state(body))
-                    || if_stmt.else_branch.as_ref().map_or(false, |body| self.function_modifies_state(body))
-            }
-            Stmt::For(for_stmt) => self.function_modifies_state(&for_stmt.body),
-            Stmt::While(while_stmt) => self.function_modifies_state(&while_stmt.body),
-            _ => false,
-        }
-    }
-
-    /// Check if expression is a state variable access
-    fn is_state_variable_access(&self, expr: &Expr) -> bool {
-        match expr {
-            Expr::Attribute(base, attr) => {
-                if let Expr::Ident(base_name) = &**base {
-                    base_name == "self" && self.state_variables.contains(attr)
-                } else {
-                    false
-                }
-            }
-            Expr::Index(base, _) => self.is_state_variable_access(base),
-            _ => false,
-        }
+        statement_modifies_state(stmt, &self.state_variables)
     }
 
     /// Check if function has access control checks
@@ -229,7 +204,7 @@ impl SecurityAnalyzer {
                     || if_stmt.elif_branches.iter().any(|(cond, body)| {
                         self.expression_checks_sender(cond) || self.has_access_control_check(body)
                     })
-                    || if_stmt.else_branch.as_ref().map_or(false, |body| self.has_access_control_check(body))
+                    || if_stmt.else_branch.as_ref().is_some_and(|body| self.has_access_control_check(body))
             }
             _ => false,
         }
@@ -283,10 +258,16 @@ impl SecurityAnalyzer {
             Stmt::If(if_stmt) => {
                 self.has_external_call(&if_stmt.then_branch)
                     || if_stmt.elif_branches.iter().any(|(_, body)| self.has_external_call(body))
-                    || if_stmt.else_branch.as_ref().map_or(false, |body| self.has_external_call(body))
+                    || if_stmt.else_branch.as_ref().is_some_and(|body| self.has_external_call(body))
+            }
+            Stmt::For(for_stmt) => {
+                self.has_external_call(&for_stmt.body)
+                    || for_stmt.else_branch.as_ref().is_some_and(|body| self.has_external_call(body))
+            }
+            Stmt::While(while_stmt) => {
+                self.has_external_call(&while_stmt.body)
+                    || while_stmt.else_branch.as_ref().is_some_and(|body| self.has_external_call(body))
             }
-            Stmt::For(for_stmt) => self.has_external_call(&for_stmt.body),
-            Stmt::While(while_stmt) => self.has_external_call(&while_stmt.body),
             _ => false,
         }
     }
@@ -343,15 +324,17 @@ impl SecurityAnalyzer {
                         }
                     }
                 }
-                Stmt::For(for_stmt) => {
-                    if self.check_statements_for_bad_pattern(&for_stmt.body) {
-                        return true;
-                    }
+                Stmt::For(for_stmt)
+                    if self.check_statements_for_bad_pattern(&for_stmt.body)
+                        || for_stmt.else_branch.as_ref().is_some_and(|body| self.check_statements_for_bad_pattern(body)) =>
+                {
+                    return true;
                 }
-                Stmt::While(while_stmt) => {
-                    if self.check_statements_for_bad_pattern(&while_stmt.body) {
-                        return true;
-                    }
+                Stmt::While(while_stmt)
+                    if self.check_statements_for_bad_pattern(&while_stmt.body)
+                        || while_stmt.else_branch.as_ref().is_some_and(|body| self.check_statements_for_bad_pattern(body)) =>
+                {
+                    return true;
                 }
                 _ => {}
             }
@@ -361,6 +344,35 @@ impl SecurityAnalyzer {
     }
 }
 
+/// Check if any statement in `body` writes to one of `state_variables`
+pub(crate) fn body_modifies_state(body: &[Stmt], state_variables: &HashSet<String>) -> bool {
+    body.iter().any(|stmt| statement_modifies_state(stmt, state_variables))
+}
+
+/// Check if statement modifies state
+fn statement_modifies_state(stmt: &Stmt, state_variables: &HashSet<String>) -> bool {
+    match stmt {
+        Stmt::Assign(assign) => assign
+            .target
+            .assigned_state_var()
+            .is_some_and(|name| state_variables.contains(name)),
+        Stmt::If(if_stmt) => {
+            body_modifies_state(&if_stmt.then_branch, state_variables)
+                || if_stmt.elif_branches.iter().any(|(_, body)| body_modifies_state(body, state_variables))
+                || if_stmt.else_branch.as_ref().is_some_and(|body| body_modifies_state(body, state_variables))
+        }
+        Stmt::For(for_stmt) => {
+            body_modifies_state(&for_stmt.body, state_variables)
+                || for_stmt.else_branch.as_ref().is_some_and(|body| body_modifies_state(body, state_variables))
+        }
+        Stmt::While(while_stmt) => {
+            body_modifies_state(&while_stmt.body, state_variables)
+                || while_stmt.else_branch.as_ref().is_some_and(|body| body_modifies_state(body, state_variables))
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
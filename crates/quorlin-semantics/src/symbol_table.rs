@@ -10,7 +10,9 @@ pub enum Symbol {
     Variable { ty: Type },
     Function { name: String },
     Event { name: String },
+    Error { name: String },
     Contract { name: String },
+    Interface { name: String },
 }
 
 /// Scope for symbol resolution
@@ -43,7 +45,9 @@ impl Scope {
 pub struct SymbolTable {
     scopes: Vec<Scope>,
     events: HashMap<String, Symbol>,
+    errors: HashMap<String, Symbol>,
     contracts: HashMap<String, Symbol>,
+    interfaces: HashMap<String, Symbol>,
 }
 
 impl SymbolTable {
@@ -52,7 +56,9 @@ impl SymbolTable {
         Self {
             scopes: vec![Scope::new()], // Start with global scope
             events: HashMap::new(),
+            errors: HashMap::new(),
             contracts: HashMap::new(),
+            interfaces: HashMap::new(),
         }
     }
 
@@ -68,6 +74,16 @@ impl SymbolTable {
         }
     }
 
+    /// Number of open scopes, including the global one
+    pub fn scope_depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Close scopes until only `depth` remain
+    pub fn unwind_to(&mut self, depth: usize) {
+        self.scopes.truncate(depth.max(1));
+    }
+
     /// Define a variable in the current scope
     pub fn define_variable(&mut self, name: &str, ty: &Type) -> SemanticResult<()> {
         if let Some(scope) = self.scopes.last_mut() {
@@ -112,6 +128,20 @@ impl SymbolTable {
         Ok(())
     }
 
+    /// Define a custom error (global)
+    pub fn define_error(&mut self, name: &str) -> SemanticResult<()> {
+        if self.errors.contains_key(name) {
+            return Err(SemanticError::DuplicateDefinition(name.to_string()));
+        }
+        self.errors.insert(
+            name.to_string(),
+            Symbol::Error {
+                name: name.to_string(),
+            },
+        );
+        Ok(())
+    }
+
     /// Define a contract (global)
     pub fn define_contract(&mut self, name: &str) -> SemanticResult<()> {
         if self.contracts.contains_key(name) {
@@ -126,6 +156,20 @@ impl SymbolTable {
         Ok(())
     }
 
+    /// Define an interface (global), which also names a type
+    pub fn define_interface(&mut self, name: &str) -> SemanticResult<()> {
+        if self.interfaces.contains_key(name) {
+            return Err(SemanticError::DuplicateDefinition(name.to_string()));
+        }
+        self.interfaces.insert(
+            name.to_string(),
+            Symbol::Interface {
+                name: name.to_string(),
+            },
+        );
+        Ok(())
+    }
+
     /// Look up a variable in the scope chain
     pub fn lookup_variable(&self, name: &str) -> Option<&Type> {
         // Search from innermost to outermost scope
@@ -142,10 +186,20 @@ impl SymbolTable {
         self.events.contains_key(name)
     }
 
+    /// Check if a custom error is defined
+    pub fn is_error_defined(&self, name: &str) -> bool {
+        self.errors.contains_key(name)
+    }
+
     /// Check if a contract is defined
     pub fn is_contract_defined(&self, name: &str) -> bool {
         self.contracts.contains_key(name)
     }
+
+    /// Check if an interface is defined
+    pub fn is_interface_defined(&self, name: &str) -> bool {
+        self.interfaces.contains_key(name)
+    }
 }
 
 impl Default for SymbolTable {
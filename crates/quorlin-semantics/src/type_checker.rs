@@ -1,14 +1,15 @@
 //! Type checking and inference for Quorlin
 
 use crate::{SemanticError, SemanticResult};
+use quorlin_parser::type_names::{canonical_display, canonical_name};
 use quorlin_parser::Type;
 
 /// Check if two types are compatible
 pub fn types_compatible(expected: &Type, found: &Type) -> bool {
     match (expected, found) {
         (Type::Simple(e), Type::Simple(f)) => {
-            // Exact match
-            if e == f {
+            // Exact match, treating `str` and `string` alike
+            if canonical_name(e) == canonical_name(f) {
                 return true;
             }
 
@@ -53,19 +54,41 @@ pub fn check_type_compatibility(
         Ok(())
     } else {
         Err(SemanticError::TypeMismatch {
-            expected: format!("{:?}", expected),
-            found: format!("{:?}", found),
+            expected: canonical_display(expected),
+            found: canonical_display(found),
         })
     }
 }
 
 /// Check if a type is numeric
-fn is_numeric_type(ty: &str) -> bool {
-    matches!(
-        ty,
-        "uint8" | "uint16" | "uint32" | "uint64" | "uint128" | "uint256"
-            | "int8" | "int16" | "int32" | "int64" | "int128" | "int256"
-    )
+pub(crate) fn is_numeric_type(ty: &str) -> bool {
+    integer_width(ty).is_some()
+}
+
+/// The width in bits of `uintN`/`intN`, for any multiple of 8 up to 256
+fn integer_width(ty: &str) -> Option<u32> {
+    let bits = ty.strip_prefix("uint").or_else(|| ty.strip_prefix("int"))?;
+    if bits.starts_with('0') || !bits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    bits.parse::<u32>().ok().filter(|bits| bits % 8 == 0 && (8..=256).contains(bits))
+}
+
+/// Check if a numeric type is signed
+fn is_signed_type(ty: &str) -> bool {
+    ty.starts_with("int")
+}
+
+/// Reject mixing signed and unsigned integers, whose bit patterns would be
+/// interpreted differently by the two sides
+fn check_same_signedness(left: &str, right: &str) -> SemanticResult<()> {
+    if is_signed_type(left) != is_signed_type(right) {
+        return Err(SemanticError::TypeMismatch {
+            expected: "integers of the same signedness".to_string(),
+            found: format!("{} and {}", left, right),
+        });
+    }
+    Ok(())
 }
 
 /// Check if type `from` can be promoted to type `to`
@@ -79,15 +102,7 @@ fn can_promote(from: &str, to: &str) -> bool {
 
 /// Get the size of a numeric type (in bits)
 fn get_type_size(ty: &str) -> u32 {
-    match ty {
-        "uint8" | "int8" => 8,
-        "uint16" | "int16" => 16,
-        "uint32" | "int32" => 32,
-        "uint64" | "int64" => 64,
-        "uint128" | "int128" => 128,
-        "uint256" | "int256" => 256,
-        _ => 0,
-    }
+    integer_width(ty).unwrap_or(0)
 }
 
 /// Infer the result type of a binary operation
@@ -99,10 +114,32 @@ pub fn infer_binop_type(
     use quorlin_parser::BinOp;
 
     match op {
-        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod | BinOp::Pow => {
-            // Arithmetic operations: both sides must be numeric
+        BinOp::Shl | BinOp::Shr => {
+            // Shifts keep the type of the value being shifted
             if let (Type::Simple(l), Type::Simple(r)) = (left, right) {
                 if is_numeric_type(l) && is_numeric_type(r) {
+                    return Ok(left.clone());
+                }
+            }
+            Err(SemanticError::TypeMismatch {
+                expected: "numeric types".to_string(),
+                found: format!("{} and {}", canonical_display(left), canonical_display(right)),
+            })
+        }
+        BinOp::Add
+        | BinOp::Sub
+        | BinOp::Mul
+        | BinOp::Div
+        | BinOp::FloorDiv
+        | BinOp::Mod
+        | BinOp::Pow
+        | BinOp::BitAnd
+        | BinOp::BitOr
+        | BinOp::BitXor => {
+            // Arithmetic and bitwise operations: both sides must be numeric
+            if let (Type::Simple(l), Type::Simple(r)) = (left, right) {
+                if is_numeric_type(l) && is_numeric_type(r) {
+                    check_same_signedness(l, r)?;
                     // Result type is the larger of the two
                     let result_type = if get_type_size(l) >= get_type_size(r) {
                         l.clone()
@@ -114,26 +151,42 @@ pub fn infer_binop_type(
             }
             Err(SemanticError::TypeMismatch {
                 expected: "numeric types".to_string(),
-                found: format!("{:?} and {:?}", left, right),
+                found: format!("{} and {}", canonical_display(left), canonical_display(right)),
             })
         }
         BinOp::Eq | BinOp::NotEq | BinOp::Lt | BinOp::LtEq | BinOp::Gt | BinOp::GtEq => {
             // Comparison operations: result is always bool
+            if let (Type::Simple(l), Type::Simple(r)) = (left, right) {
+                if is_numeric_type(l) && is_numeric_type(r) {
+                    check_same_signedness(l, r)?;
+                }
+            }
             Ok(Type::Simple("bool".to_string()))
         }
         BinOp::And | BinOp::Or => {
-            // Logical operations: both sides must be bool
-            if let (Type::Simple(l), Type::Simple(r)) = (left, right) {
-                if l == "bool" && r == "bool" {
+            // Logical operations: each side must be bool, or not yet known
+            for operand in [left, right] {
+                if !matches!(operand, Type::Simple(name) if name == "bool" || name == "unknown") {
+                    return Err(SemanticError::TypeMismatch {
+                        expected: "bool".to_string(),
+                        found: canonical_display(operand),
+                    });
+                }
+            }
+            Ok(Type::Simple("bool".to_string()))
+        }
+        BinOp::In => {
+            // Membership: the right side must be a mapping keyed by the left
+            if let Type::Mapping(key, _) = right {
+                if types_compatible(key, left) {
                     return Ok(Type::Simple("bool".to_string()));
                 }
             }
             Err(SemanticError::TypeMismatch {
-                expected: "bool".to_string(),
-                found: format!("{:?} and {:?}", left, right),
+                expected: "key in mapping".to_string(),
+                found: format!("{} in {}", canonical_display(left), canonical_display(right)),
             })
         }
-        _ => Ok(Type::Simple("unknown".to_string())),
     }
 }
 
@@ -159,6 +212,18 @@ mod tests {
         assert!(!types_compatible(&uint256, &bool_type));
     }
 
+    #[test]
+    fn test_mixed_signedness_is_rejected() {
+        use quorlin_parser::BinOp;
+
+        let int256 = Type::Simple("int256".to_string());
+        let uint256 = Type::Simple("uint256".to_string());
+
+        assert!(infer_binop_type(&int256, &uint256, &BinOp::Lt).is_err());
+        assert!(infer_binop_type(&uint256, &int256, &BinOp::Add).is_err());
+        assert!(infer_binop_type(&int256, &int256, &BinOp::Lt).is_ok());
+    }
+
     #[test]
     fn test_binop_type_inference() {
         use quorlin_parser::BinOp;
@@ -174,4 +239,15 @@ mod tests {
         let result = infer_binop_type(&uint256, &uint256, &BinOp::Lt).unwrap();
         assert_eq!(result, Type::Simple("bool".to_string()));
     }
+
+    #[test]
+    fn test_every_byte_multiple_width_is_numeric() {
+        let uint160 = Type::Simple("uint160".to_string());
+        let uint256 = Type::Simple("uint256".to_string());
+        assert!(is_numeric_type("int24"));
+        assert!(types_compatible(&uint256, &uint160));
+        assert!(!types_compatible(&uint160, &uint256));
+        assert!(!is_numeric_type("uint7"));
+        assert!(!is_numeric_type("uint264"));
+    }
 }
@@ -1,24 +1,51 @@
 //! Semantic validation rules for Quorlin
 
+use crate::security_analyzer::body_modifies_state;
 use crate::{SemanticError, SemanticResult};
+use quorlin_parser::visit::{self, Visitor};
+use quorlin_parser::{
+    canonical_backend, parse_expression, ContractDecl, ContractMember, Decorator, Expr, Function, Stmt, Type, UnaryOp,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Valid decorators for functions
 const VALID_FUNCTION_DECORATORS: &[&str] = &[
     "public",
     "view",
+    "pure",
     "internal",
     "payable",
     "external",
     "constructor",
+    "inline",
+    "override",
 ];
 
 /// Validate a decorator on a given construct
-pub fn validate_decorator(decorator: &str, construct: &str) -> SemanticResult<()> {
+pub fn validate_decorator(decorator: &Decorator, construct: &str) -> SemanticResult<()> {
     match construct {
         "function" => {
-            if !VALID_FUNCTION_DECORATORS.contains(&decorator) {
+            // @target("evm", ...) gates emission per backend
+            if decorator.name == "target" {
+                for backend in decorator.string_args() {
+                    if canonical_backend(backend).is_none() {
+                        return Err(SemanticError::ValidationError(format!(
+                            "Unknown backend '{}' in @target decorator",
+                            backend
+                        )));
+                    }
+                }
+                return Ok(());
+            }
+
+            // @validate("amount > 0", ...) constraints are checked with the function
+            if decorator.name == "validate" {
+                return Ok(());
+            }
+
+            if !VALID_FUNCTION_DECORATORS.contains(&decorator.name.as_str()) {
                 return Err(SemanticError::InvalidDecorator(
-                    decorator.to_string(),
+                    decorator.name.clone(),
                     construct.to_string(),
                 ));
             }
@@ -31,9 +58,180 @@ pub fn validate_decorator(decorator: &str, construct: &str) -> SemanticResult<()
     }
 }
 
+/// Validate that `__init__` is only ever the constructor. It runs once at
+/// deployment, so any decorator other than `@constructor` (such as
+/// `@external`) would wrongly make it look like a callable entry point.
+pub fn validate_init_decorators(func: &Function) -> SemanticResult<()> {
+    if func.name != "__init__" {
+        return Ok(());
+    }
+    match func.decorators.iter().find(|d| d.name != "constructor") {
+        Some(decorator) => Err(SemanticError::ValidationError(format!(
+            "__init__ is the constructor and cannot be decorated with @{}; only @constructor is allowed",
+            decorator.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Parse the `@validate` constraints of `func`. Each constraint is an
+/// expression over the function's parameters only; it is injected as a
+/// `require` at function entry, before any state is available.
+pub fn parse_validations(func: &Function) -> SemanticResult<Vec<Expr>> {
+    func.validations()
+        .iter()
+        .map(|constraint| {
+            let expr = parse_expression(constraint).map_err(|e| {
+                SemanticError::ValidationError(format!(
+                    "Invalid @validate constraint '{}' on '{}': {}",
+                    constraint, func.name, e
+                ))
+            })?;
+            let mut finder = NonParamFinder { func, found: None };
+            finder.visit_expr(&expr);
+            if let Some(name) = finder.found {
+                return Err(SemanticError::ValidationError(format!(
+                    "@validate constraint '{}' on '{}' refers to '{}', which is not a parameter",
+                    constraint, func.name, name
+                )));
+            }
+            Ok(expr)
+        })
+        .collect()
+}
+
+/// Finds the first name in an expression that is not a parameter of `func`.
+/// Called builtins such as `len(...)` are allowed.
+struct NonParamFinder<'a> {
+    func: &'a Function,
+    found: Option<String>,
+}
+
+impl<'ast> Visitor<'ast> for NonParamFinder<'_> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if self.found.is_some() {
+            return;
+        }
+        match expr {
+            Expr::Ident(name) if !self.func.params.iter().any(|p| &p.name == name) => {
+                self.found = Some(name.clone());
+            }
+            Expr::Call(callee, args) if matches!(&**callee, Expr::Ident(_)) => {
+                for arg in args {
+                    self.visit_expr(arg);
+                }
+            }
+            _ => visit::walk_expr(self, expr),
+        }
+    }
+}
+
+/// Validate a string or hex literal assigned to a fixed-size `bytesN`.
+/// Returns whether `value` is such a literal (and fits), in which case it
+/// takes the `bytesN` type regardless of its own inferred type.
+pub fn validate_fixed_bytes_literal(target: &Type, value: &Expr) -> SemanticResult<bool> {
+    let Type::Simple(name) = target else {
+        return Ok(false);
+    };
+    let Some(width) = name.strip_prefix("bytes").and_then(|n| n.parse::<usize>().ok()) else {
+        return Ok(false);
+    };
+
+    let length = match value {
+        Expr::HexLiteral(hex) => {
+            let digits = hex.trim_start_matches("0x").chars().filter(|c| *c != '_').count();
+            digits.div_ceil(2)
+        }
+        Expr::StringLiteral(s) => s.len(),
+        _ => return Ok(false),
+    };
+
+    if length > width {
+        return Err(SemanticError::ValidationError(format!(
+            "Literal of {} bytes does not fit in {}",
+            length, name
+        )));
+    }
+    Ok(true)
+}
+
+/// 2**256 - 1, the largest EVM word
+const UINT256_MAX: &str = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+/// 2**255 - 1 and 2**255, the bounds of int256
+const INT256_MAX: &str = "57896044618658097711785492504343953926634992332820282019728792003956564819967";
+const INT256_MIN_MAGNITUDE: &str = "57896044618658097711785492504343953926634992332820282019728792003956564819968";
+
+/// Whether the decimal `a` is at most the decimal `b`
+fn decimal_le(a: &str, b: &str) -> bool {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    (a.len(), a) <= (b.len(), b)
+}
+
+/// Validate that an integer literal fits in one 256-bit word
+pub fn validate_int_literal(digits: &str) -> SemanticResult<()> {
+    if !decimal_le(digits, UINT256_MAX) {
+        return Err(SemanticError::ValidationError(format!(
+            "Integer literal {} does not fit in 256 bits (the maximum is 2**256 - 1)",
+            digits
+        )));
+    }
+    Ok(())
+}
+
+/// Largest value and largest negative magnitude of a `uintN`/`intN` type
+fn int_bounds(name: &str) -> Option<(String, String)> {
+    let (signed, bits) = match name.strip_prefix("uint") {
+        Some(bits) => (false, bits),
+        None => (true, name.strip_prefix("int")?),
+    };
+    let bits: u32 = bits.parse().ok()?;
+    match (signed, bits) {
+        (false, 256) => Some((UINT256_MAX.to_string(), "0".to_string())),
+        (true, 256) => Some((INT256_MAX.to_string(), INT256_MIN_MAGNITUDE.to_string())),
+        (false, 1..=128) => Some(((u128::MAX >> (128 - bits)).to_string(), "0".to_string())),
+        (true, 1..=128) => Some((((1u128 << (bits - 1)) - 1).to_string(), (1u128 << (bits - 1)).to_string())),
+        _ => None,
+    }
+}
+
+/// Validate that an integer literal, possibly negated, assigned to a
+/// `uintN`/`intN` fits its range
+pub fn validate_int_literal_range(target: &Type, value: &Expr) -> SemanticResult<()> {
+    let (digits, negative) = match value {
+        Expr::IntLiteral(digits) => (digits, false),
+        Expr::UnaryOp(UnaryOp::Neg, inner) => match &**inner {
+            Expr::IntLiteral(digits) => (digits, true),
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    validate_int_literal(digits)?;
+
+    let Type::Simple(name) = target else {
+        return Ok(());
+    };
+    let Some((max, min_magnitude)) = int_bounds(name) else {
+        return Ok(());
+    };
+    let fits = if negative { decimal_le(digits, &min_magnitude) } else { decimal_le(digits, &max) };
+    if !fits {
+        let min = if min_magnitude == "0" { "0".to_string() } else { format!("-{}", min_magnitude) };
+        return Err(SemanticError::ValidationError(format!(
+            "Literal {}{} does not fit in {} (range {} to {})",
+            if negative { "-" } else { "" },
+            digits,
+            name,
+            min,
+            max
+        )));
+    }
+    Ok(())
+}
+
 /// Validate that @view functions don't modify state
-pub fn validate_view_function_purity(decorators: &[String], modifies_state: bool) -> SemanticResult<()> {
-    if decorators.contains(&"view".to_string()) && modifies_state {
+pub fn validate_view_function_purity(decorators: &[Decorator], modifies_state: bool) -> SemanticResult<()> {
+    if decorators.iter().any(|d| d.name == "view") && modifies_state {
         return Err(SemanticError::ValidationError(
             "@view functions cannot modify state".to_string(),
         ));
@@ -41,6 +239,234 @@ pub fn validate_view_function_purity(decorators: &[String], modifies_state: bool
     Ok(())
 }
 
+/// Validate that @view/@pure functions never reach a state write, either
+/// directly or through the internal functions they call
+pub fn validate_view_call_graph(contract: &ContractDecl) -> SemanticResult<()> {
+    let state_variables: HashSet<String> = contract
+        .body
+        .iter()
+        .filter_map(|member| match member {
+            ContractMember::StateVar(var) => Some(var.name.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let functions: HashMap<&str, &Function> = contract
+        .body
+        .iter()
+        .filter_map(|member| match member {
+            ContractMember::Function(func) => Some((func.name.as_str(), func)),
+            _ => None,
+        })
+        .collect();
+
+    for member in &contract.body {
+        let ContractMember::Function(func) = member else {
+            continue;
+        };
+        let Some(kind) = func.decorators.iter().map(|d| d.name.as_str()).find(|d| *d == "view" || *d == "pure") else {
+            continue;
+        };
+
+        // Depth-first walk of the internal call graph
+        let mut visited = HashSet::new();
+        let mut stack = vec![func.name.as_str()];
+        while let Some(name) = stack.pop() {
+            if !visited.insert(name) {
+                continue;
+            }
+            let Some(callee) = functions.get(name) else {
+                continue;
+            };
+
+            if body_modifies_state(&callee.body, &state_variables) {
+                return Err(SemanticError::ValidationError(if callee.name == func.name {
+                    format!("@{} function '{}' cannot modify state", kind, func.name)
+                } else {
+                    format!(
+                        "@{} function '{}' calls state-mutating function '{}'",
+                        kind, func.name, callee.name
+                    )
+                }));
+            }
+
+            let mut calls = Vec::new();
+            collect_internal_calls(&callee.body, &mut calls);
+            stack.extend(calls.into_iter().filter_map(|call| functions.get_key_value(call).map(|(k, _)| *k)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate that a function redefining one inherited from a base contract is
+/// marked `@override`, and that every `@override` function has one to replace
+pub fn validate_overrides(contract: &ContractDecl, contracts: &[&ContractDecl]) -> SemanticResult<()> {
+    // Inherited function name -> the base contract that declares it
+    let mut inherited: HashMap<&str, &str> = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut stack: Vec<&str> = contract.bases.iter().map(String::as_str).collect();
+    while let Some(name) = stack.pop() {
+        if name == contract.name || !visited.insert(name) {
+            continue;
+        }
+        // Unknown bases are reported when the storage layout is resolved
+        let Some(base) = contracts.iter().find(|c| c.name == name) else {
+            continue;
+        };
+        for member in &base.body {
+            if let ContractMember::Function(func) = member {
+                inherited.entry(func.name.as_str()).or_insert(base.name.as_str());
+            }
+        }
+        stack.extend(base.bases.iter().map(String::as_str));
+    }
+
+    for member in &contract.body {
+        let ContractMember::Function(func) = member else {
+            continue;
+        };
+        if func.name == "__init__" {
+            continue;
+        }
+        let marked = func.has_decorator("override");
+        match inherited.get(func.name.as_str()) {
+            Some(base) if !marked => {
+                return Err(SemanticError::ValidationError(format!(
+                    "Function '{}' in '{}' redefines '{}.{}' and must be marked @override",
+                    func.name, contract.name, base, func.name
+                )));
+            }
+            None if marked => {
+                return Err(SemanticError::ValidationError(format!(
+                    "Function '{}' in '{}' is marked @override but no base contract declares it",
+                    func.name, contract.name
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate that no `@inline` function can reach itself through the internal
+/// call graph, since expanding it at its call sites would never terminate
+pub fn validate_inline_not_recursive(contract: &ContractDecl) -> SemanticResult<()> {
+    // Ordered by name so the function reported for a cycle is stable
+    let functions: BTreeMap<&str, &Function> = contract
+        .body
+        .iter()
+        .filter_map(|member| match member {
+            ContractMember::Function(func) => Some((func.name.as_str(), func)),
+            _ => None,
+        })
+        .collect();
+
+    for func in functions.values().filter(|f| f.has_decorator("inline")) {
+        let mut visited = HashSet::new();
+        let mut stack = Vec::new();
+        collect_internal_calls(&func.body, &mut stack);
+        while let Some(name) = stack.pop() {
+            if name == func.name {
+                return Err(SemanticError::ValidationError(format!(
+                    "@inline function '{}' is recursive and cannot be inlined",
+                    func.name
+                )));
+            }
+            if !visited.insert(name) {
+                continue;
+            }
+            if let Some(callee) = functions.get(name) {
+                collect_internal_calls(&callee.body, &mut stack);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Collect names of functions called as `self.name(...)` or `name(...)`
+fn collect_internal_calls<'a>(body: &'a [Stmt], calls: &mut Vec<&'a str>) {
+    for stmt in body {
+        match stmt {
+            Stmt::Assign(assign) => {
+                collect_expr_calls(&assign.target, calls);
+                collect_expr_calls(&assign.value, calls);
+            }
+            Stmt::Expr(expr) | Stmt::Return(Some(expr)) => collect_expr_calls(expr, calls),
+            Stmt::Require(req) => {
+                collect_expr_calls(&req.condition, calls);
+                for arg in req.error.iter().flat_map(|error| &error.args) {
+                    collect_expr_calls(arg, calls);
+                }
+            }
+            Stmt::Emit(emit) => emit.args.iter().for_each(|arg| collect_expr_calls(arg, calls)),
+            Stmt::Raise(raise) => raise.args.iter().for_each(|arg| collect_expr_calls(arg, calls)),
+            Stmt::If(if_stmt) => {
+                collect_expr_calls(&if_stmt.condition, calls);
+                collect_internal_calls(&if_stmt.then_branch, calls);
+                for (cond, body) in &if_stmt.elif_branches {
+                    collect_expr_calls(cond, calls);
+                    collect_internal_calls(body, calls);
+                }
+                if let Some(else_body) = &if_stmt.else_branch {
+                    collect_internal_calls(else_body, calls);
+                }
+            }
+            Stmt::While(while_stmt) => {
+                collect_expr_calls(&while_stmt.condition, calls);
+                collect_internal_calls(&while_stmt.body, calls);
+                if let Some(else_body) = &while_stmt.else_branch {
+                    collect_internal_calls(else_body, calls);
+                }
+            }
+            Stmt::For(for_stmt) => {
+                collect_expr_calls(&for_stmt.iterable, calls);
+                collect_internal_calls(&for_stmt.body, calls);
+                if let Some(else_body) = &for_stmt.else_branch {
+                    collect_internal_calls(else_body, calls);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_expr_calls<'a>(expr: &'a Expr, calls: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Call(func, args) => {
+            match &**func {
+                Expr::Ident(name) => calls.push(name),
+                Expr::Attribute(base, name) if matches!(&**base, Expr::Ident(b) if b == "self") => {
+                    calls.push(name)
+                }
+                other => collect_expr_calls(other, calls),
+            }
+            args.iter().for_each(|arg| collect_expr_calls(arg, calls));
+        }
+        Expr::BinOp(left, _, right) | Expr::Index(left, right) => {
+            collect_expr_calls(left, calls);
+            collect_expr_calls(right, calls);
+        }
+        Expr::UnaryOp(_, operand) | Expr::Attribute(operand, _) | Expr::KeywordArg(_, operand) => {
+            collect_expr_calls(operand, calls)
+        }
+        Expr::List(items) | Expr::Tuple(items) | Expr::FString(items) => items.iter().for_each(|item| collect_expr_calls(item, calls)),
+        Expr::IfExp { test, body, orelse } => {
+            collect_expr_calls(test, calls);
+            collect_expr_calls(body, calls);
+            collect_expr_calls(orelse, calls);
+        }
+        Expr::ListComp { element, iterable, condition, .. } => {
+            collect_expr_calls(iterable, calls);
+            collect_expr_calls(element, calls);
+            condition.iter().for_each(|condition| collect_expr_calls(condition, calls));
+        }
+        _ => {}
+    }
+}
+
 /// Validate constructor requirements
 pub fn validate_constructor(has_init: bool, state_vars_count: usize) -> SemanticResult<()> {
     if state_vars_count > 0 && !has_init {
@@ -55,28 +481,53 @@ pub fn validate_constructor(has_init: bool, state_vars_count: usize) -> Semantic
 mod tests {
     use super::*;
 
+    fn decorator(name: &str, args: &[&str]) -> Decorator {
+        Decorator {
+            name: name.to_string(),
+            args: args.iter().map(|arg| Expr::StringLiteral(arg.to_string())).collect(),
+        }
+    }
+
     #[test]
     fn test_valid_decorator() {
-        assert!(validate_decorator("public", "function").is_ok());
-        assert!(validate_decorator("view", "function").is_ok());
-        assert!(validate_decorator("internal", "function").is_ok());
-        assert!(validate_decorator("payable", "function").is_ok());
+        assert!(validate_decorator(&decorator("public", &[]), "function").is_ok());
+        assert!(validate_decorator(&decorator("view", &[]), "function").is_ok());
+        assert!(validate_decorator(&decorator("internal", &[]), "function").is_ok());
+        assert!(validate_decorator(&decorator("payable", &[]), "function").is_ok());
+        assert!(validate_decorator(&decorator("target", &["evm", "ink"]), "function").is_ok());
+        assert!(validate_decorator(&decorator("target", &["cosmos"]), "function").is_err());
+    }
+
+    #[test]
+    fn test_int_literal_range_follows_declared_width() {
+        let uint8 = Type::Simple("uint8".to_string());
+        let int8 = Type::Simple("int8".to_string());
+        let int = |n: &str| Expr::IntLiteral(n.to_string());
+        let neg = |n: &str| Expr::UnaryOp(UnaryOp::Neg, Box::new(int(n)));
+
+        assert!(validate_int_literal_range(&uint8, &int("255")).is_ok());
+        let err = validate_int_literal_range(&uint8, &int("256")).unwrap_err().to_string();
+        assert_eq!(err, "Literal 256 does not fit in uint8 (range 0 to 255)");
+        assert!(validate_int_literal_range(&uint8, &neg("1")).is_err());
+        assert!(validate_int_literal_range(&int8, &neg("128")).is_ok());
+        assert!(validate_int_literal_range(&int8, &int("128")).is_err());
+        assert!(validate_int_literal(UINT256_MAX).is_ok());
     }
 
     #[test]
     fn test_invalid_decorator() {
-        let result = validate_decorator("invalid", "function");
+        let result = validate_decorator(&decorator("invalid", &[]), "function");
         assert!(result.is_err());
         assert!(matches!(result, Err(SemanticError::InvalidDecorator(_, _))));
     }
 
     #[test]
     fn test_view_function_validation() {
-        let view_decorators = vec!["view".to_string()];
+        let view_decorators = vec![decorator("view", &[])];
         assert!(validate_view_function_purity(&view_decorators, false).is_ok());
         assert!(validate_view_function_purity(&view_decorators, true).is_err());
 
-        let no_decorators: Vec<String> = vec![];
+        let no_decorators: Vec<Decorator> = vec![];
         assert!(validate_view_function_purity(&no_decorators, true).is_ok());
     }
 }
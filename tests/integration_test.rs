@@ -57,15 +57,15 @@ contract SimpleToken:
         parse_module(tokens).expect("Parsing failed")
     }
 
-    fn analyze_contract(module: &quorlin_parser::Module) {
+    fn analyze_contract(module: &mut quorlin_parser::Module) {
         let mut analyzer = SemanticAnalyzer::new();
         analyzer.analyze(module).expect("Semantic analysis failed");
     }
 
     #[test]
     fn test_simple_contract_evm() {
-        let module = parse_contract(SIMPLE_CONTRACT);
-        analyze_contract(&module);
+        let mut module = parse_contract(SIMPLE_CONTRACT);
+        analyze_contract(&mut module);
 
         let mut codegen = EvmCodegen::new();
         let yul = codegen.generate(&module).expect("EVM codegen failed");
@@ -77,8 +77,8 @@ contract SimpleToken:
 
     #[test]
     fn test_simple_contract_solana() {
-        let module = parse_contract(SIMPLE_CONTRACT);
-        analyze_contract(&module);
+        let mut module = parse_contract(SIMPLE_CONTRACT);
+        analyze_contract(&mut module);
 
         let mut codegen = SolanaCodegen::new();
         let rust = codegen.generate(&module).expect("Solana codegen failed");
@@ -90,8 +90,8 @@ contract SimpleToken:
 
     #[test]
     fn test_simple_contract_ink() {
-        let module = parse_contract(SIMPLE_CONTRACT);
-        analyze_contract(&module);
+        let mut module = parse_contract(SIMPLE_CONTRACT);
+        analyze_contract(&mut module);
 
         let mut codegen = InkCodegen::new();
         let rust = codegen.generate(&module).expect("ink! codegen failed");
@@ -104,8 +104,8 @@ contract SimpleToken:
 
     #[test]
     fn test_token_contract_evm() {
-        let module = parse_contract(TOKEN_CONTRACT);
-        analyze_contract(&module);
+        let mut module = parse_contract(TOKEN_CONTRACT);
+        analyze_contract(&mut module);
 
         let mut codegen = EvmCodegen::new();
         let yul = codegen.generate(&module).expect("EVM codegen failed");
@@ -117,8 +117,8 @@ contract SimpleToken:
 
     #[test]
     fn test_token_contract_solana() {
-        let module = parse_contract(TOKEN_CONTRACT);
-        analyze_contract(&module);
+        let mut module = parse_contract(TOKEN_CONTRACT);
+        analyze_contract(&mut module);
 
         let mut codegen = SolanaCodegen::new();
         let rust = codegen.generate(&module).expect("Solana codegen failed");
@@ -130,8 +130,8 @@ contract SimpleToken:
 
     #[test]
     fn test_token_contract_ink() {
-        let module = parse_contract(TOKEN_CONTRACT);
-        analyze_contract(&module);
+        let mut module = parse_contract(TOKEN_CONTRACT);
+        analyze_contract(&mut module);
 
         let mut codegen = InkCodegen::new();
         let rust = codegen.generate(&module).expect("ink! codegen failed");
@@ -144,8 +144,8 @@ contract SimpleToken:
 
     #[test]
     fn test_all_backends_produce_output() {
-        let module = parse_contract(TOKEN_CONTRACT);
-        analyze_contract(&module);
+        let mut module = parse_contract(TOKEN_CONTRACT);
+        analyze_contract(&mut module);
 
         // EVM
         let mut evm = EvmCodegen::new();